@@ -0,0 +1,77 @@
+#ifndef ED25519_COMPACT_H
+#define ED25519_COMPACT_H
+
+/*
+ * C API for ed25519-compact, available with the `capi` Cargo feature.
+ *
+ * All functions return 0 on success, and a negative error code on failure.
+ */
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define ED25519_COMPACT_SEEDBYTES 32
+#define ED25519_COMPACT_PUBLICKEYBYTES 32
+#define ED25519_COMPACT_SECRETKEYBYTES 64
+#define ED25519_COMPACT_BYTES 64
+
+#define ED25519_COMPACT_X25519_PUBLICKEYBYTES 32
+#define ED25519_COMPACT_X25519_SECRETKEYBYTES 32
+#define ED25519_COMPACT_X25519_BYTES 32
+
+#define ED25519_COMPACT_OK 0
+#define ED25519_COMPACT_ERR_SIGNATURE_MISMATCH (-1)
+#define ED25519_COMPACT_ERR_WEAK_PUBLIC_KEY (-2)
+#define ED25519_COMPACT_ERR_INVALID_PUBLIC_KEY (-3)
+#define ED25519_COMPACT_ERR_INVALID_SECRET_KEY (-4)
+#define ED25519_COMPACT_ERR_INVALID_SIGNATURE (-5)
+#define ED25519_COMPACT_ERR_INVALID_SEED (-6)
+#define ED25519_COMPACT_ERR_INVALID_BLIND (-7)
+#define ED25519_COMPACT_ERR_INVALID_NOISE (-8)
+#define ED25519_COMPACT_ERR_PARSE_ERROR (-9)
+#define ED25519_COMPACT_ERR_NON_CANONICAL (-10)
+#define ED25519_COMPACT_ERR_NON_CANONICAL_SCALAR (-11)
+#define ED25519_COMPACT_ERR_PUBLIC_KEY_NOT_ON_CURVE (-12)
+#define ED25519_COMPACT_ERR_PEM_MISSING_HEADER (-13)
+#define ED25519_COMPACT_ERR_PEM_MISSING_FOOTER (-14)
+#define ED25519_COMPACT_ERR_INVALID_BASE64 (-15)
+#define ED25519_COMPACT_ERR_DER_INVALID_LENGTH (-16)
+#define ED25519_COMPACT_ERR_DER_UNEXPECTED_HEADER (-17)
+#define ED25519_COMPACT_ERR_FAULT_DETECTED (-18)
+#define ED25519_COMPACT_ERR_RNG_FAILURE (-19)
+#define ED25519_COMPACT_ERR_SELF_TEST_FAILED (-20)
+#define ED25519_COMPACT_ERR_MEMORY_LOCK_FAILED (-21)
+#define ED25519_COMPACT_ERR_INVALID_CONTEXT (-22)
+#define ED25519_COMPACT_ERR_SIGNER_FAILURE (-23)
+#define ED25519_COMPACT_ERR_INVALID_CERTIFICATE (-24)
+#define ED25519_COMPACT_ERR_EXPIRED (-25)
+#define ED25519_COMPACT_ERR_KEY_REVOKED (-26)
+#define ED25519_COMPACT_ERR_POLICY_VIOLATION (-27)
+#define ED25519_COMPACT_ERR_BUFFER_TOO_SMALL (-28)
+#define ED25519_COMPACT_ERR_PEM_INVALID_LINE (-29)
+#define ED25519_COMPACT_ERR_NULL_POINTER (-255)
+
+int ed25519_compact_keypair_from_seed(uint8_t *pk, uint8_t *sk, const uint8_t *seed);
+
+int ed25519_compact_keypair(uint8_t *pk, uint8_t *sk);
+
+int ed25519_compact_sign(uint8_t *sig, const uint8_t *msg, size_t msg_len, const uint8_t *sk);
+
+int ed25519_compact_verify(const uint8_t *sig, const uint8_t *msg, size_t msg_len,
+                           const uint8_t *pk);
+
+int ed25519_compact_x25519_public_key(uint8_t *pk, const uint8_t *sk);
+
+int ed25519_compact_x25519_keypair(uint8_t *pk, uint8_t *sk);
+
+int ed25519_compact_x25519(uint8_t *shared, const uint8_t *sk, const uint8_t *pk);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
@@ -0,0 +1,78 @@
+//! Python bindings for ed25519-compact.
+//!
+//! Build and install the module with `maturin develop` in this directory.
+
+use std::borrow::Cow;
+
+use ed25519_compact::{x25519, Error, KeyPair, PublicKey, SecretKey, Seed, Signature};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+fn err(e: Error) -> PyErr {
+    PyValueError::new_err(e.to_string())
+}
+
+type Bytes = Cow<'static, [u8]>;
+
+/// Returns a `(public_key, secret_key)` Ed25519 key pair, derived from
+/// `seed` if it is given, or random otherwise.
+#[pyfunction]
+#[pyo3(signature = (seed=None))]
+fn keypair(seed: Option<&[u8]>) -> PyResult<(Bytes, Bytes)> {
+    let kp = match seed {
+        Some(seed) => KeyPair::try_from_seed(Seed::from_slice(seed).map_err(err)?),
+        None => KeyPair::try_generate(),
+    }
+    .map_err(err)?;
+    Ok((kp.pk.to_vec().into(), kp.sk.to_vec().into()))
+}
+
+/// Computes an Ed25519 signature for `message`.
+#[pyfunction]
+fn sign(secret_key: &[u8], message: &[u8]) -> PyResult<Bytes> {
+    let sk = SecretKey::from_slice(secret_key).map_err(err)?;
+    Ok(sk.try_sign(message, None).map_err(err)?.to_vec().into())
+}
+
+/// Verifies an Ed25519 signature for `message`, raising `ValueError` if it
+/// is not valid.
+#[pyfunction]
+fn verify(public_key: &[u8], message: &[u8], signature: &[u8]) -> PyResult<()> {
+    let pk = PublicKey::from_slice(public_key).map_err(err)?;
+    let signature = Signature::from_slice(signature).map_err(err)?;
+    pk.verify(message, &signature).map_err(err)
+}
+
+/// Returns a random `(public_key, secret_key)` X25519 key pair.
+#[pyfunction]
+fn x25519_keypair() -> PyResult<(Bytes, Bytes)> {
+    let kp = x25519::KeyPair::try_generate().map_err(err)?;
+    Ok((kp.pk.to_vec().into(), kp.sk.to_vec().into()))
+}
+
+/// Computes the X25519 public key for `secret_key`.
+#[pyfunction]
+fn x25519_public_key(secret_key: &[u8]) -> PyResult<Bytes> {
+    let sk = x25519::SecretKey::from_slice(secret_key).map_err(err)?;
+    Ok(sk.recover_public_key().map_err(err)?.to_vec().into())
+}
+
+/// Computes an X25519 shared secret.
+#[pyfunction]
+fn x25519_dh(public_key: &[u8], secret_key: &[u8]) -> PyResult<Bytes> {
+    let pk = x25519::PublicKey::from_slice(public_key).map_err(err)?;
+    let sk = x25519::SecretKey::from_slice(secret_key).map_err(err)?;
+    Ok(pk.dh(&sk).map_err(err)?.to_vec().into())
+}
+
+#[pymodule]
+#[pyo3(name = "ed25519_compact")]
+fn ed25519_compact_py(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(keypair, m)?)?;
+    m.add_function(wrap_pyfunction!(sign, m)?)?;
+    m.add_function(wrap_pyfunction!(verify, m)?)?;
+    m.add_function(wrap_pyfunction!(x25519_keypair, m)?)?;
+    m.add_function(wrap_pyfunction!(x25519_public_key, m)?)?;
+    m.add_function(wrap_pyfunction!(x25519_dh, m)?)?;
+    Ok(())
+}
@@ -0,0 +1,233 @@
+//! ssh-agent client.
+//!
+//! Lists the Ed25519 keys held by an agent, and computes signatures through
+//! it, so that the secret keys never enter process memory.
+
+use std::env;
+use std::io::{self, Read, Write};
+use std::os::unix::net::UnixStream;
+use std::path::Path;
+use std::sync::Mutex;
+
+use super::ssh::{self, Reader};
+use super::{Error, PublicKey, Signature, Signer};
+
+const SSH_AGENT_FAILURE: u8 = 5;
+const SSH_AGENTC_REQUEST_IDENTITIES: u8 = 11;
+const SSH_AGENT_IDENTITIES_ANSWER: u8 = 12;
+const SSH_AGENTC_SIGN_REQUEST: u8 = 13;
+const SSH_AGENT_SIGN_RESPONSE: u8 = 14;
+
+const MAX_MESSAGE_LEN: usize = 256 * 1024;
+
+/// An Ed25519 key held by an agent.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AgentIdentity {
+    /// The public key.
+    pub pk: PublicKey,
+    /// The comment associated with the key, usually its file name.
+    pub comment: String,
+}
+
+/// A connection to an ssh-agent.
+pub struct Agent {
+    stream: Mutex<UnixStream>,
+}
+
+impl Agent {
+    /// Connects to the agent listening on the Unix socket `path`.
+    pub fn connect(path: impl AsRef<Path>) -> io::Result<Self> {
+        Ok(Self::from_stream(UnixStream::connect(path)?))
+    }
+
+    /// Connects to the agent whose socket is set in the `SSH_AUTH_SOCK`
+    /// environment variable.
+    pub fn connect_env() -> io::Result<Self> {
+        let path = env::var_os("SSH_AUTH_SOCK")
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "SSH_AUTH_SOCK is not set"))?;
+        Self::connect(path)
+    }
+
+    /// Uses an already connected stream.
+    pub fn from_stream(stream: UnixStream) -> Self {
+        Agent {
+            stream: Mutex::new(stream),
+        }
+    }
+
+    /// Returns the Ed25519 keys held by the agent. Keys of other types are
+    /// ignored.
+    pub fn identities(&self) -> io::Result<Vec<AgentIdentity>> {
+        let response = self.request(&[SSH_AGENTC_REQUEST_IDENTITIES])?;
+        let mut reader = Reader::new(&response);
+        check_response_type(&mut reader, SSH_AGENT_IDENTITIES_ANSWER)?;
+        let count = reader.u32().map_err(invalid_data)?;
+        let mut identities = Vec::new();
+        for _ in 0..count {
+            let blob = reader.string().map_err(invalid_data)?;
+            let comment = reader.string().map_err(invalid_data)?;
+            if let Some(pk) = ssh::parse_public_key_blob(blob).map_err(invalid_data)? {
+                identities.push(AgentIdentity {
+                    pk,
+                    comment: String::from_utf8_lossy(comment).into_owned(),
+                });
+            }
+        }
+        reader.finish().map_err(invalid_data)?;
+        Ok(identities)
+    }
+
+    /// Asks the agent to sign the message `message` with the key `pk`.
+    ///
+    /// The returned signature is verified before being returned.
+    pub fn sign(&self, pk: &PublicKey, message: &[u8]) -> io::Result<Signature> {
+        let mut request = vec![SSH_AGENTC_SIGN_REQUEST];
+        ssh::put_string(&mut request, &ssh::public_key_blob(pk));
+        ssh::put_string(&mut request, message);
+        ssh::put_u32(&mut request, 0);
+        let response = self.request(&request)?;
+        let mut reader = Reader::new(&response);
+        check_response_type(&mut reader, SSH_AGENT_SIGN_RESPONSE)?;
+        let blob = reader.string().map_err(invalid_data)?;
+        reader.finish().map_err(invalid_data)?;
+        let signature = ssh::parse_signature_blob(blob).map_err(invalid_data)?;
+        pk.verify(message, &signature).map_err(invalid_data)?;
+        Ok(signature)
+    }
+
+    /// Returns a `Signer` using the agent key `pk`.
+    pub fn signer(&self, pk: PublicKey) -> AgentSigner<'_> {
+        AgentSigner { agent: self, pk }
+    }
+
+    fn request(&self, payload: &[u8]) -> io::Result<Vec<u8>> {
+        let mut stream = self.stream.lock().unwrap_or_else(|e| e.into_inner());
+        let mut message = Vec::with_capacity(4 + payload.len());
+        ssh::put_string(&mut message, payload);
+        stream.write_all(&message)?;
+        let mut len = [0u8; 4];
+        stream.read_exact(&mut len)?;
+        let len = u32::from_be_bytes(len) as usize;
+        if len == 0 || len > MAX_MESSAGE_LEN {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "Unexpected agent response length",
+            ));
+        }
+        let mut response = vec![0u8; len];
+        stream.read_exact(&mut response)?;
+        Ok(response)
+    }
+}
+
+/// A key held by an ssh-agent.
+pub struct AgentSigner<'t> {
+    agent: &'t Agent,
+    pk: PublicKey,
+}
+
+impl Signer for AgentSigner<'_> {
+    fn public_key(&self) -> PublicKey {
+        self.pk
+    }
+
+    /// Computes a signature through the agent. Errors are reported as
+    /// `Error::SignerFailure`.
+    fn sign(&self, message: &[u8]) -> Result<Signature, Error> {
+        self.agent
+            .sign(&self.pk, message)
+            .map_err(|_| Error::SignerFailure)
+    }
+}
+
+fn invalid_data(e: Error) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, e)
+}
+
+fn check_response_type(reader: &mut Reader<'_>, expected: u8) -> io::Result<()> {
+    match reader.u8().map_err(invalid_data)? {
+        x if x == expected => Ok(()),
+        SSH_AGENT_FAILURE => Err(io::Error::other("The agent refused the request")),
+        _ => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "Unexpected agent response",
+        )),
+    }
+}
+
+#[test]
+fn test_agent() {
+    use super::KeyPair;
+    use std::thread;
+
+    let kp = KeyPair::from_seed([42u8; 32].into());
+    let (client, mut server) = UnixStream::pair().unwrap();
+
+    fn read_request(server: &mut UnixStream) -> Vec<u8> {
+        let mut len = [0u8; 4];
+        server.read_exact(&mut len).unwrap();
+        let mut request = vec![0u8; u32::from_be_bytes(len) as usize];
+        server.read_exact(&mut request).unwrap();
+        request
+    }
+
+    fn write_response(server: &mut UnixStream, response: &[u8]) {
+        let mut message = Vec::new();
+        ssh::put_string(&mut message, response);
+        server.write_all(&message).unwrap();
+    }
+
+    let sk = kp.sk;
+    let server = thread::spawn(move || {
+        let request = read_request(&mut server);
+        assert_eq!(request, [SSH_AGENTC_REQUEST_IDENTITIES]);
+        let mut response = vec![SSH_AGENT_IDENTITIES_ANSWER];
+        ssh::put_u32(&mut response, 2);
+        let mut rsa_blob = Vec::new();
+        ssh::put_string(&mut rsa_blob, b"ssh-rsa");
+        ssh::put_string(&mut rsa_blob, &[1, 0, 1]);
+        ssh::put_string(&mut rsa_blob, &[0xff; 64]);
+        ssh::put_string(&mut response, &rsa_blob);
+        ssh::put_string(&mut response, b"rsa key");
+        ssh::put_string(&mut response, &ssh::public_key_blob(&sk.public_key()));
+        ssh::put_string(&mut response, b"ed25519 key");
+        write_response(&mut server, &response);
+
+        for _ in 0..2 {
+            let request = read_request(&mut server);
+            let mut reader = Reader::new(&request);
+            assert_eq!(reader.u8().unwrap(), SSH_AGENTC_SIGN_REQUEST);
+            let pk = ssh::parse_public_key_blob(reader.string().unwrap()).unwrap();
+            let data = reader.string().unwrap();
+            let response = if pk == Some(sk.public_key()) {
+                let mut response = vec![SSH_AGENT_SIGN_RESPONSE];
+                ssh::put_string(&mut response, &ssh::signature_blob(&sk.sign(data, None)));
+                response
+            } else {
+                vec![SSH_AGENT_FAILURE]
+            };
+            write_response(&mut server, &response);
+        }
+    });
+
+    let agent = Agent::from_stream(client);
+    let identities = agent.identities().unwrap();
+    assert_eq!(
+        identities,
+        [AgentIdentity {
+            pk: kp.pk,
+            comment: "ed25519 key".to_string(),
+        }]
+    );
+
+    let signer = agent.signer(identities[0].pk);
+    let signature = signer.sign(b"message").unwrap();
+    assert!(kp.pk.verify(b"message", &signature).is_ok());
+
+    let other = KeyPair::from_seed([1u8; 32].into());
+    assert_eq!(
+        agent.signer(other.pk).sign(b"message"),
+        Err(Error::SignerFailure)
+    );
+    server.join().unwrap();
+}
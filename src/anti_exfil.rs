@@ -0,0 +1,175 @@
+//! Anti-exfiltration signing.
+//!
+//! A malicious signing device could leak bits of its secret key through the
+//! nonces of the signatures it produces. With this protocol, the host
+//! contributes randomness to every nonce, and checks that it was used:
+//!
+//! 1. The host picks 32 random bytes `host_data`, and sends
+//!    `anti_exfil_host_commitment(&host_data)` along with the message.
+//! 2. The signer returns `SecretKey::anti_exfil_signer_commitment()`, a
+//!    commitment to its own nonce.
+//! 3. The host reveals `host_data`.
+//! 4. The signer computes the signature with `SecretKey::anti_exfil_sign()`.
+//! 5. The host checks the signature and the nonce with
+//!    `PublicKey::anti_exfil_verify()`.
+//!
+//! The signer commits to its nonce before learning `host_data`, so it can't
+//! choose the final nonce. The signature is a regular Ed25519 signature.
+
+use super::common::*;
+use super::ed25519::sign_with_secrets;
+use super::edwards25519::{ge_add, ge_scalarmult_base, sc_muladd, sc_reduce, GeP3};
+use super::sha512;
+use super::{Error, KeyPair, PublicKey, SecretKey, Signature};
+
+/// Length of the host randomness, and of the commitments, in bytes.
+pub const ANTI_EXFIL_BYTES: usize = 32;
+
+/// Returns the commitment to `host_data` that the host sends to the signer
+/// before the signer commits to its nonce.
+pub fn anti_exfil_host_commitment(host_data: &[u8; ANTI_EXFIL_BYTES]) -> [u8; ANTI_EXFIL_BYTES] {
+    let mut hasher = sha512::Hash::new();
+    hasher.update(b"ed25519-compact anti-exfil host commitment");
+    hasher.update(host_data);
+    let hash = hasher.finalize();
+    let mut commitment = [0u8; ANTI_EXFIL_BYTES];
+    commitment.copy_from_slice(&hash[0..32]);
+    commitment
+}
+
+fn tweak(
+    signer_commitment: &[u8; ANTI_EXFIL_BYTES],
+    host_data: &[u8; ANTI_EXFIL_BYTES],
+) -> [u8; 64] {
+    let mut hasher = sha512::Hash::new();
+    hasher.update(b"ed25519-compact anti-exfil tweak");
+    hasher.update(signer_commitment);
+    hasher.update(host_data);
+    let mut t = hasher.finalize();
+    sc_reduce(&mut t);
+    t
+}
+
+impl SecretKey {
+    fn anti_exfil_nonce(
+        &self,
+        message: &[u8],
+        host_commitment: &[u8; ANTI_EXFIL_BYTES],
+    ) -> ([u8; 64], [u8; 64]) {
+        let mut az = sha512::Hash::hash(&self[0..32]);
+        KeyPair::clamp_expanded(&mut az);
+        let mut hasher = sha512::Hash::new();
+        hasher.update(b"ed25519-compact anti-exfil nonce");
+        hasher.update(&az[32..64]);
+        hasher.update(host_commitment);
+        hasher.update(message);
+        let mut nonce = hasher.finalize();
+        sc_reduce(&mut nonce);
+        (az, nonce)
+    }
+
+    /// Returns the signer's commitment to its nonce for `message`, given the
+    /// commitment to the host randomness.
+    ///
+    /// The nonce is derived deterministically, so the signer doesn't have to
+    /// keep any state between the two rounds.
+    pub fn anti_exfil_signer_commitment(
+        &self,
+        message: impl AsRef<[u8]>,
+        host_commitment: &[u8; ANTI_EXFIL_BYTES],
+    ) -> [u8; ANTI_EXFIL_BYTES] {
+        let (mut az, mut nonce) = self.anti_exfil_nonce(message.as_ref(), host_commitment);
+        let signer_commitment = ge_scalarmult_base(&nonce[0..32]).to_bytes();
+        wipe(&mut az);
+        wipe(&mut nonce);
+        signer_commitment
+    }
+
+    /// Signs `message`, using a nonce that includes the host randomness.
+    ///
+    /// The nonce commitment is re-derived from the commitment to `host_data`,
+    /// so revealing data that doesn't match the original commitment only
+    /// produces a signature that the host will reject.
+    pub fn anti_exfil_sign(
+        &self,
+        message: impl AsRef<[u8]>,
+        host_data: &[u8; ANTI_EXFIL_BYTES],
+    ) -> Result<Signature, Error> {
+        let message = message.as_ref();
+        let host_commitment = anti_exfil_host_commitment(host_data);
+        sign_with_secrets(
+            &self.public_key(),
+            None,
+            &[message],
+            || {
+                let (mut az, mut nonce0) = self.anti_exfil_nonce(message, &host_commitment);
+                let signer_commitment = ge_scalarmult_base(&nonce0[0..32]).to_bytes();
+                let t = tweak(&signer_commitment, host_data);
+                let mut one = [0u8; 32];
+                one[0] = 1;
+                let mut secrets = ([0u8; 32], [0u8; 32]);
+                secrets.0.copy_from_slice(&az[0..32]);
+                sc_muladd(&mut secrets.1, &one, &t[0..32], &nonce0[0..32]);
+                wipe(&mut az);
+                wipe(&mut nonce0);
+                secrets
+            },
+            true,
+            cfg!(feature = "sign-twice"),
+        )
+    }
+}
+
+impl PublicKey {
+    /// Verifies a signature created with `SecretKey::anti_exfil_sign()`,
+    /// and checks that its nonce was derived from the signer's commitment
+    /// and the host randomness.
+    pub fn anti_exfil_verify(
+        &self,
+        message: impl AsRef<[u8]>,
+        signature: &Signature,
+        signer_commitment: &[u8; ANTI_EXFIL_BYTES],
+        host_data: &[u8; ANTI_EXFIL_BYTES],
+    ) -> Result<(), Error> {
+        self.verify(message, signature)?;
+        let r0 = GeP3::from_bytes_vartime(signer_commitment).ok_or(Error::InvalidSignature)?;
+        let t = tweak(signer_commitment, host_data);
+        let expected_r = ge_add(&r0, &ge_scalarmult_base(&t[0..32])).to_bytes();
+        if !ct_eq(&expected_r, &signature[0..32]) {
+            return Err(Error::SignatureMismatch);
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn test_anti_exfil() {
+    use super::Seed;
+
+    let kp = KeyPair::from_seed(Seed::new([3u8; Seed::BYTES]));
+    let host_data = [0x5au8; ANTI_EXFIL_BYTES];
+    let host_commitment = anti_exfil_host_commitment(&host_data);
+    let signer_commitment = kp.sk.anti_exfil_signer_commitment(b"tx", &host_commitment);
+    let signature = kp.sk.anti_exfil_sign(b"tx", &host_data).unwrap();
+    assert!(kp.pk.verify(b"tx", &signature).is_ok());
+    assert!(kp
+        .pk
+        .anti_exfil_verify(b"tx", &signature, &signer_commitment, &host_data)
+        .is_ok());
+
+    // A signer ignoring the host randomness is detected.
+    let honest = kp.sk.sign(b"tx", None);
+    assert_eq!(
+        kp.pk
+            .anti_exfil_verify(b"tx", &honest, &signer_commitment, &host_data),
+        Err(Error::SignatureMismatch)
+    );
+    let other_commitment = kp.sk.anti_exfil_signer_commitment(b"tx", &[0u8; 32]);
+    assert_eq!(
+        kp.pk
+            .anti_exfil_verify(b"tx", &signature, &other_commitment, &host_data),
+        Err(Error::SignatureMismatch)
+    );
+    let other_signature = kp.sk.anti_exfil_sign(b"tx", &[0x5bu8; 32]).unwrap();
+    assert_ne!(signature, other_signature);
+}
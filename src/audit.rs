@@ -0,0 +1,118 @@
+use std::sync::RwLock;
+
+use super::sha512;
+use super::{PublicKey, Signature, KEY_ID_BYTES};
+
+/// Details about a newly computed signature, passed to the audit hook.
+#[derive(Clone, Debug)]
+pub struct SigningEvent<'t> {
+    /// Public key of the signer.
+    pub public_key: PublicKey,
+    /// Key ID of the signer, as returned by `PublicKey::key_id()`.
+    pub key_id: [u8; KEY_ID_BYTES],
+    /// SHA-512 hash of the signed message. For Ed25519ph, this is the hash
+    /// of the prehashed message.
+    pub message_digest: [u8; 64],
+    /// Context of an Ed25519ph signature.
+    pub context: Option<&'t [u8]>,
+    /// The signature.
+    pub signature: Signature,
+}
+
+type AuditHook = Box<dyn Fn(&SigningEvent<'_>) + Send + Sync>;
+
+static AUDIT_HOOK: RwLock<Option<AuditHook>> = RwLock::new(None);
+
+/// Installs a function called after every successful Ed25519 signature
+/// computed by the crate, including incremental, blind and
+/// anti-exfiltration signatures, for example to keep a log of signing
+/// operations.
+///
+/// Replaces the previously installed hook, if any. The hook is called on
+/// the signing thread, and must not compute signatures itself.
+pub fn set_audit_hook(hook: impl Fn(&SigningEvent<'_>) + Send + Sync + 'static) {
+    *AUDIT_HOOK.write().unwrap_or_else(|e| e.into_inner()) = Some(Box::new(hook));
+}
+
+/// Removes the audit hook.
+pub fn clear_audit_hook() {
+    *AUDIT_HOOK.write().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+pub(crate) fn notify(
+    public_key: PublicKey,
+    dom: Option<(u8, &[u8])>,
+    chunks: &[&[u8]],
+    signature: &Signature,
+) {
+    let hook = AUDIT_HOOK.read().unwrap_or_else(|e| e.into_inner());
+    let hook = match hook.as_ref() {
+        None => return,
+        Some(hook) => hook,
+    };
+    let mut hasher = sha512::Hash::new();
+    for chunk in chunks {
+        hasher.update(chunk);
+    }
+    hook(&SigningEvent {
+        public_key,
+        key_id: public_key.key_id(),
+        message_digest: hasher.finalize(),
+        context: dom.map(|(_, context)| context),
+        signature: *signature,
+    });
+}
+
+#[test]
+fn test_audit() {
+    use std::sync::{Arc, Mutex};
+
+    use super::{KeyPair, Seed};
+
+    let kp = KeyPair::from_seed(Seed::new([0xa5u8; Seed::BYTES]));
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let log2 = log.clone();
+    let pk = kp.pk;
+    #[cfg(feature = "blind-keys")]
+    let blind_kp = kp.blind(&super::Blind::new([7u8; 32]), "audit");
+    #[cfg(feature = "blind-keys")]
+    let blind_pk = PublicKey::new(*blind_kp.blind_pk);
+    #[cfg(not(feature = "blind-keys"))]
+    let blind_pk = pk;
+    set_audit_hook(move |event| {
+        if event.public_key == pk || event.public_key == blind_pk {
+            log2.lock()
+                .unwrap()
+                .push((event.key_id, event.message_digest, event.signature));
+        }
+    });
+    let signature = kp.sk.sign(b"audited", None);
+    let mut st = kp.sk.sign_incremental(None);
+    st.absorb(b"audited");
+    let incremental_signature = st.sign().unwrap();
+    #[cfg(feature = "anti-exfil")]
+    let anti_exfil_signature = kp.sk.anti_exfil_sign(b"audited", &[1u8; 32]).unwrap();
+    #[cfg(feature = "hazmat")]
+    let hazmat_signature =
+        crate::hazmat::ExpandedSecretKey::from_secret_key(&kp.sk).sign(b"audited", None);
+    #[cfg(feature = "blind-keys")]
+    let blind_signature = blind_kp.blind_sk.sign(b"audited", None);
+    clear_audit_hook();
+    kp.sk.sign(b"not audited", None);
+
+    let log = log.lock().unwrap();
+    let mut expected = vec![signature, incremental_signature];
+    #[cfg(feature = "anti-exfil")]
+    expected.push(anti_exfil_signature);
+    #[cfg(feature = "hazmat")]
+    expected.push(hazmat_signature);
+    #[cfg(feature = "blind-keys")]
+    expected.push(blind_signature);
+    assert_eq!(log.len(), expected.len());
+    assert_eq!(log[0].0, kp.pk.key_id());
+    assert_eq!(log[0].1, sha512::Hash::hash(b"audited"));
+    assert_eq!(log[1].1, sha512::Hash::hash(sha512::Hash::hash(b"audited")));
+    for (entry, signature) in log.iter().zip(expected) {
+        assert_eq!(entry.2, signature);
+    }
+}
@@ -0,0 +1,208 @@
+use std::fmt;
+
+use ct_codecs::{Base64, Decoder, Encoder};
+
+use super::ssh;
+use super::{Error, PublicKey};
+
+/// An `authorized_keys` option: a name, and an optional value.
+pub type AuthorizedKeyOption = (String, Option<String>);
+
+/// An entry of an OpenSSH `authorized_keys` file.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AuthorizedKey {
+    /// Options (name and optional value), such as `("command", Some("/bin/true"))`
+    /// or `("no-pty", None)`.
+    pub options: Vec<AuthorizedKeyOption>,
+    /// The public key.
+    pub public_key: PublicKey,
+    /// The comment following the key, if any.
+    pub comment: Option<String>,
+}
+
+impl AuthorizedKey {
+    /// Creates an entry for a public key, with no options and no comment.
+    pub fn new(public_key: PublicKey) -> Self {
+        AuthorizedKey {
+            options: Vec::new(),
+            public_key,
+            comment: None,
+        }
+    }
+
+    /// Returns the value of an option, or `Some("")` for an option without
+    /// a value. Returns `None` if the option is not present.
+    pub fn option(&self, name: &str) -> Option<&str> {
+        self.options
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_deref().unwrap_or(""))
+    }
+
+    /// Parses a line of an `authorized_keys` file.
+    ///
+    /// Returns `Ok(None)` for empty lines, comments, and keys of other types
+    /// than Ed25519.
+    pub fn parse(line: &str) -> Result<Option<Self>, Error> {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            return Ok(None);
+        }
+        let (options, rest) = if is_key_type(first_token(line)) {
+            (Vec::new(), line)
+        } else {
+            parse_options(line)?
+        };
+        let mut tokens = rest.splitn(3, [' ', '\t']);
+        let key_type = tokens.next().ok_or(Error::ParseError)?;
+        if !is_key_type(key_type) {
+            return Err(Error::ParseError);
+        }
+        if key_type.as_bytes() != ssh::ED25519_TYPE {
+            return Ok(None);
+        }
+        let b64 = tokens.next().ok_or(Error::ParseError)?;
+        let blob = Base64::decode_to_vec(b64, None).map_err(|_| Error::InvalidBase64)?;
+        let public_key = ssh::parse_public_key_blob(&blob)?.ok_or(Error::ParseError)?;
+        let comment = tokens
+            .next()
+            .map(str::trim)
+            .filter(|c| !c.is_empty())
+            .map(str::to_string);
+        Ok(Some(AuthorizedKey {
+            options,
+            public_key,
+            comment,
+        }))
+    }
+
+    /// Parses the content of an `authorized_keys` file, and returns its
+    /// Ed25519 entries.
+    pub fn parse_all(content: &str) -> Result<Vec<Self>, Error> {
+        let mut keys = Vec::new();
+        for line in content.lines() {
+            if let Some(key) = Self::parse(line)? {
+                keys.push(key);
+            }
+        }
+        Ok(keys)
+    }
+}
+
+impl fmt::Display for AuthorizedKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, (name, value)) in self.options.iter().enumerate() {
+            if i > 0 {
+                f.write_str(",")?;
+            }
+            f.write_str(name)?;
+            if let Some(value) = value {
+                write!(f, "=\"{}\"", value.replace('"', "\\\""))?;
+            }
+        }
+        if !self.options.is_empty() {
+            f.write_str(" ")?;
+        }
+        let blob = ssh::public_key_blob(&self.public_key);
+        let b64 = Base64::encode_to_string(blob).map_err(|_| fmt::Error)?;
+        write!(f, "ssh-ed25519 {}", b64)?;
+        if let Some(comment) = &self.comment {
+            write!(f, " {}", comment)?;
+        }
+        Ok(())
+    }
+}
+
+fn first_token(line: &str) -> &str {
+    line.split([' ', '\t']).next().unwrap_or("")
+}
+
+fn is_key_type(token: &str) -> bool {
+    token.starts_with("ssh-") || token.starts_with("ecdsa-") || token.starts_with("sk-")
+}
+
+/// Parses the comma-separated options at the beginning of a line, and
+/// returns them along with the rest of the line.
+fn parse_options(line: &str) -> Result<(Vec<AuthorizedKeyOption>, &str), Error> {
+    let mut options = Vec::new();
+    let mut chars = line.char_indices().peekable();
+    loop {
+        let mut name = String::new();
+        let mut value = None;
+        while let Some(&(_, c)) = chars.peek() {
+            if c == ',' || c == '=' || c == ' ' || c == '\t' {
+                break;
+            }
+            name.push(c);
+            chars.next();
+        }
+        if name.is_empty() {
+            return Err(Error::ParseError);
+        }
+        if let Some(&(_, '=')) = chars.peek() {
+            chars.next();
+            if chars.next().map(|(_, c)| c) != Some('"') {
+                return Err(Error::ParseError);
+            }
+            let mut v = String::new();
+            loop {
+                match chars.next().map(|(_, c)| c) {
+                    None => return Err(Error::ParseError),
+                    Some('"') => break,
+                    Some('\\') if chars.peek().map(|&(_, c)| c) == Some('"') => {
+                        chars.next();
+                        v.push('"');
+                    }
+                    Some(c) => v.push(c),
+                }
+            }
+            value = Some(v);
+        }
+        options.push((name, value));
+        match chars.next() {
+            Some((_, ',')) => continue,
+            Some((i, ' ')) | Some((i, '\t')) => return Ok((options, line[i..].trim_start())),
+            _ => return Err(Error::ParseError),
+        }
+    }
+}
+
+#[test]
+fn test_authorized_keys() {
+    let content = "\
+# comment
+
+ssh-rsa AAAAB3NzaC1yc2EAAAADAQABAAABAQ== rsa@host
+ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIB3bin3lAsOqntz9OzKpQKzEn1XiGpso4xnQ5S/0mZbe
+command=\"echo \\\"hi, there\\\"\",no-pty,from=\"10.0.0.0/8\" ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIL7b/acZitc9GH8PMugP92R0O4eUH5CDh8hQ+CR2BMu7 alice@example
+";
+    let keys = AuthorizedKey::parse_all(content).unwrap();
+    assert_eq!(keys.len(), 2);
+    assert!(keys[0].options.is_empty());
+    assert_eq!(keys[0].comment, None);
+    assert_eq!(keys[1].option("command"), Some("echo \"hi, there\""));
+    assert_eq!(keys[1].option("no-pty"), Some(""));
+    assert_eq!(keys[1].option("from"), Some("10.0.0.0/8"));
+    assert_eq!(keys[1].option("no-port-forwarding"), None);
+    assert_eq!(keys[1].comment.as_deref(), Some("alice@example"));
+    assert_eq!(
+        keys[1].public_key,
+        PublicKey::from_any_str(
+            "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIL7b/acZitc9GH8PMugP92R0O4eUH5CDh8hQ+CR2BMu7"
+        )
+        .unwrap()
+    );
+
+    let lines: Vec<_> = content.lines().collect();
+    assert_eq!(keys[0].to_string(), lines[3]);
+    assert_eq!(keys[1].to_string(), lines[4]);
+    for key in &keys {
+        assert_eq!(
+            AuthorizedKey::parse(&key.to_string()).unwrap().as_ref(),
+            Some(key)
+        );
+    }
+
+    assert!(AuthorizedKey::parse("command=\"unterminated ssh-ed25519 AAAA").is_err());
+    assert!(AuthorizedKey::parse("ssh-ed25519 !!!!").is_err());
+}
@@ -0,0 +1,79 @@
+//! Base58 encoding, with the Bitcoin alphabet.
+
+use super::Error;
+
+const ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+/// Encodes `bin` as a Base58 string.
+pub(crate) fn encode(bin: &[u8]) -> String {
+    let zeros = bin.iter().take_while(|&&x| x == 0).count();
+    // Base58 digits, least significant first.
+    let mut digits: Vec<u8> = Vec::with_capacity(max_encoded_len(bin.len()));
+    for &x in &bin[zeros..] {
+        let mut carry = x as u32;
+        for digit in digits.iter_mut() {
+            carry += (*digit as u32) << 8;
+            *digit = (carry % 58) as u8;
+            carry /= 58;
+        }
+        while carry > 0 {
+            digits.push((carry % 58) as u8);
+            carry /= 58;
+        }
+    }
+    let mut s = String::with_capacity(zeros + digits.len());
+    s.push_str(&"1".repeat(zeros));
+    s.extend(digits.iter().rev().map(|&d| ALPHABET[d as usize] as char));
+    s
+}
+
+/// Returns the maximum length of the Base58 encoding of `len` bytes.
+fn max_encoded_len(len: usize) -> usize {
+    len * 138 / 100 + 1
+}
+
+/// Decodes a Base58 string, that is expected to encode at most `max_len`
+/// bytes.
+///
+/// Longer strings are rejected before decoding, since decoding takes
+/// quadratic time in the length of the input.
+pub(crate) fn decode(s: &str, max_len: usize) -> Result<Vec<u8>, Error> {
+    if s.len() > max_encoded_len(max_len) {
+        return Err(Error::ParseError);
+    }
+    let zeros = s.bytes().take_while(|&c| c == b'1').count();
+    // Bytes, least significant first.
+    let mut bytes: Vec<u8> = Vec::with_capacity(s.len() * 733 / 1000 + 1);
+    for c in s.bytes().skip(zeros) {
+        let mut carry = ALPHABET
+            .iter()
+            .position(|&a| a == c)
+            .ok_or(Error::ParseError)? as u32;
+        for byte in bytes.iter_mut() {
+            carry += (*byte as u32) * 58;
+            *byte = carry as u8;
+            carry >>= 8;
+        }
+        while carry > 0 {
+            bytes.push(carry as u8);
+            carry >>= 8;
+        }
+    }
+    let mut bin = vec![0u8; zeros];
+    bin.extend(bytes.iter().rev());
+    Ok(bin)
+}
+
+#[test]
+fn test_base58() {
+    assert_eq!(encode(b""), "");
+    assert_eq!(encode(&[0, 0, 1, 2]), "115T");
+    assert_eq!(encode(&[0u8; 32]), "11111111111111111111111111111111");
+    assert_eq!(decode("115T", 4).unwrap(), [0, 0, 1, 2]);
+    assert_eq!(decode(&encode(b"hello world"), 11).unwrap(), b"hello world");
+    assert!(decode("0OIl", 4).is_err());
+    for bin in [[0u8; 64], [0xffu8; 64]] {
+        assert_eq!(decode(&encode(&bin), 64).unwrap(), bin);
+    }
+    assert_eq!(decode(&"z".repeat(100), 64), Err(Error::ParseError));
+}
@@ -0,0 +1,101 @@
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use super::error::Error;
+use super::{PublicKey, Signature};
+
+/// A signed message to verify as part of a batch: the public key, the
+/// message and its signature.
+pub type BatchItem<'t> = (&'t PublicKey, &'t [u8], &'t Signature);
+
+/// Verifies a batch of signatures, each with its own public key and
+/// message, and returns the error of the first invalid one, if any.
+///
+/// Signatures are verified individually, so the result is exactly the same
+/// as calling `PublicKey::verify()` for every item.
+pub fn verify_batch(items: &[BatchItem]) -> Result<(), Error> {
+    items
+        .iter()
+        .try_for_each(|(pk, message, signature)| pk.verify(message, signature))
+}
+
+/// Returns the indices of the items of a batch whose signature is invalid,
+/// so that they can be rejected while the valid ones are accepted.
+pub fn invalid_batch_items<'t>(items: &'t [BatchItem<'t>]) -> impl Iterator<Item = usize> + 't {
+    items
+        .iter()
+        .enumerate()
+        .filter(|(_, (pk, message, signature))| pk.verify(message, signature).is_err())
+        .map(|(i, _)| i)
+}
+
+/// Verifies a batch of signatures, and returns the indices of the invalid
+/// ones if there are any.
+#[cfg(feature = "alloc")]
+pub fn verify_batch_with_culprits(items: &[BatchItem]) -> Result<(), Vec<usize>> {
+    let culprits: Vec<usize> = invalid_batch_items(items).collect();
+    if culprits.is_empty() {
+        Ok(())
+    } else {
+        Err(culprits)
+    }
+}
+
+#[test]
+fn test_verify_batch() {
+    use super::KeyPair;
+
+    let kps = [
+        KeyPair::from_seed([1u8; 32].into()),
+        KeyPair::from_seed([2u8; 32].into()),
+        KeyPair::from_seed([3u8; 32].into()),
+    ];
+    let messages: [&[u8]; 3] = [b"first", b"second", b"third"];
+    let signatures = [
+        kps[0].sk.sign(messages[0], None),
+        kps[1].sk.sign(messages[1], None),
+        kps[2].sk.sign(messages[2], None),
+    ];
+    let items = [
+        (&kps[0].pk, messages[0], &signatures[0]),
+        (&kps[1].pk, messages[1], &signatures[1]),
+        (&kps[2].pk, messages[2], &signatures[2]),
+    ];
+    assert_eq!(verify_batch(&items), Ok(()));
+    assert_eq!(invalid_batch_items(&items).next(), None);
+
+    let items = [
+        (&kps[0].pk, messages[0], &signatures[0]),
+        (&kps[1].pk, messages[2], &signatures[1]),
+        (&kps[2].pk, messages[2], &signatures[0]),
+    ];
+    assert_eq!(verify_batch(&items), Err(Error::SignatureMismatch));
+    let mut culprits = invalid_batch_items(&items);
+    assert_eq!(culprits.next(), Some(1));
+    assert_eq!(culprits.next(), Some(2));
+    assert_eq!(culprits.next(), None);
+    #[cfg(feature = "alloc")]
+    assert_eq!(verify_batch_with_culprits(&items), Err(alloc::vec![1, 2]));
+
+    let signatures = [
+        kps[0].sk.sign(messages[0], None),
+        kps[0].sk.sign(messages[1], None),
+        kps[0].sk.sign(messages[2], None),
+    ];
+    let mut items = [
+        (messages[0], &signatures[0]),
+        (messages[1], &signatures[1]),
+        (messages[2], &signatures[2]),
+    ];
+    assert_eq!(kps[0].pk.verify_batch(&items), Ok(()));
+    assert_eq!(kps[0].pk.verify_batch(&[]), Ok(()));
+    assert_eq!(
+        kps[1].pk.verify_batch(&items),
+        Err(Error::SignatureMismatch)
+    );
+    items[2].0 = messages[1];
+    assert_eq!(
+        kps[0].pk.verify_batch(&items),
+        Err(Error::SignatureMismatch)
+    );
+}
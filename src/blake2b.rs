@@ -0,0 +1,196 @@
+//! A small, self-contained BLAKE2b implementation
+
+#![allow(dead_code, clippy::unreadable_literal)]
+
+const IV: [u64; 8] = [
+    0x6a09e667f3bcc908,
+    0xbb67ae8584caa73b,
+    0x3c6ef372fe94f82b,
+    0xa54ff53a5f1d36f1,
+    0x510e527fade682d1,
+    0x9b05688c2b3e6c1f,
+    0x1f83d9abfb41bd6b,
+    0x5be0cd19137e2179,
+];
+
+const SIGMA: [[usize; 16]; 12] = [
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+    [14, 10, 4, 8, 9, 15, 13, 6, 1, 12, 0, 2, 11, 7, 5, 3],
+    [11, 8, 12, 0, 5, 2, 15, 13, 10, 14, 3, 6, 7, 1, 9, 4],
+    [7, 9, 3, 1, 13, 12, 11, 14, 2, 6, 5, 10, 4, 0, 15, 8],
+    [9, 0, 5, 7, 2, 4, 10, 15, 14, 1, 11, 12, 6, 8, 3, 13],
+    [2, 12, 6, 10, 0, 11, 8, 3, 4, 13, 7, 5, 15, 14, 1, 9],
+    [12, 5, 1, 15, 14, 13, 4, 10, 0, 7, 6, 3, 9, 2, 8, 11],
+    [13, 11, 7, 14, 12, 1, 3, 9, 5, 0, 15, 4, 8, 6, 2, 10],
+    [6, 15, 14, 9, 11, 3, 0, 8, 12, 2, 13, 7, 1, 4, 10, 5],
+    [10, 2, 8, 4, 7, 6, 1, 5, 15, 11, 9, 14, 3, 12, 13, 0],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+    [14, 10, 4, 8, 9, 15, 13, 6, 1, 12, 0, 2, 11, 7, 5, 3],
+];
+
+#[derive(Copy, Clone)]
+pub struct Hash {
+    h: [u64; 8],
+    t: u128,
+    w: [u8; 128],
+    r: usize,
+    out_len: usize,
+}
+
+impl Hash {
+    /// Maximum output length.
+    pub const MAX_BYTES: usize = 64;
+
+    /// Creates a hash function with an output of `out_len` bytes.
+    pub fn new(out_len: usize) -> Hash {
+        Hash::new_keyed(out_len, &[])
+    }
+
+    /// Creates a keyed hash function with an output of `out_len` bytes.
+    pub fn new_keyed(out_len: usize, key: &[u8]) -> Hash {
+        Hash::new_with_params(out_len, key, &[0u8; 16], &[0u8; 16])
+    }
+
+    /// Creates a keyed hash function with an output of `out_len` bytes, a
+    /// salt and a personalization string.
+    pub fn new_with_params(
+        out_len: usize,
+        key: &[u8],
+        salt: &[u8; 16],
+        personal: &[u8; 16],
+    ) -> Hash {
+        assert!(out_len > 0 && out_len <= Hash::MAX_BYTES && key.len() <= 64);
+        let mut h = IV;
+        h[0] ^= 0x01010000 ^ ((key.len() as u64) << 8) ^ out_len as u64;
+        for (i, x) in salt.iter().chain(personal.iter()).enumerate() {
+            h[4 + i / 8] ^= (*x as u64) << (8 * (i % 8));
+        }
+        let mut hash = Hash {
+            h,
+            t: 0,
+            w: [0u8; 128],
+            r: 0,
+            out_len,
+        };
+        if !key.is_empty() {
+            hash.w[..key.len()].copy_from_slice(key);
+            hash.r = 128;
+        }
+        hash
+    }
+
+    fn compress(&mut self, last: bool) {
+        let mut m = [0u64; 16];
+        for (i, e) in m.iter_mut().enumerate() {
+            let mut x = [0u8; 8];
+            x.copy_from_slice(&self.w[i * 8..i * 8 + 8]);
+            *e = u64::from_le_bytes(x);
+        }
+        let mut v = [0u64; 16];
+        v[..8].copy_from_slice(&self.h);
+        v[8..].copy_from_slice(&IV);
+        v[12] ^= self.t as u64;
+        v[13] ^= (self.t >> 64) as u64;
+        if last {
+            v[14] = !v[14];
+        }
+        for s in SIGMA.iter() {
+            let mut g = |a: usize, b: usize, c: usize, d: usize, x: u64, y: u64| {
+                v[a] = v[a].wrapping_add(v[b]).wrapping_add(x);
+                v[d] = (v[d] ^ v[a]).rotate_right(32);
+                v[c] = v[c].wrapping_add(v[d]);
+                v[b] = (v[b] ^ v[c]).rotate_right(24);
+                v[a] = v[a].wrapping_add(v[b]).wrapping_add(y);
+                v[d] = (v[d] ^ v[a]).rotate_right(16);
+                v[c] = v[c].wrapping_add(v[d]);
+                v[b] = (v[b] ^ v[c]).rotate_right(63);
+            };
+            g(0, 4, 8, 12, m[s[0]], m[s[1]]);
+            g(1, 5, 9, 13, m[s[2]], m[s[3]]);
+            g(2, 6, 10, 14, m[s[4]], m[s[5]]);
+            g(3, 7, 11, 15, m[s[6]], m[s[7]]);
+            g(0, 5, 10, 15, m[s[8]], m[s[9]]);
+            g(1, 6, 11, 12, m[s[10]], m[s[11]]);
+            g(2, 7, 8, 13, m[s[12]], m[s[13]]);
+            g(3, 4, 9, 14, m[s[14]], m[s[15]]);
+        }
+        for i in 0..8 {
+            self.h[i] ^= v[i] ^ v[i + 8];
+        }
+    }
+
+    /// Absorb content
+    pub fn update<T: AsRef<[u8]>>(&mut self, input: T) {
+        for &x in input.as_ref() {
+            // The last block must be compressed with the final flag, so a
+            // full buffer is only compressed once more input arrives.
+            if self.r == 128 {
+                self.t += 128;
+                self.compress(false);
+                self.r = 0;
+            }
+            self.w[self.r] = x;
+            self.r += 1;
+        }
+    }
+
+    /// Compute BLAKE2b(absorbed content). Only the first `out_len` bytes of
+    /// the result are set.
+    pub fn finalize(mut self) -> [u8; 64] {
+        self.t += self.r as u128;
+        for x in self.w[self.r..].iter_mut() {
+            *x = 0;
+        }
+        self.compress(true);
+        let mut out = [0u8; 64];
+        for (i, e) in self.h.iter().enumerate() {
+            out[i * 8..i * 8 + 8].copy_from_slice(&e.to_le_bytes());
+        }
+        for x in out[self.out_len..].iter_mut() {
+            *x = 0;
+        }
+        out
+    }
+
+    /// Compute BLAKE2b-512(`input`)
+    pub fn hash<T: AsRef<[u8]>>(input: T) -> [u8; 64] {
+        let mut h = Hash::new(64);
+        h.update(input);
+        h.finalize()
+    }
+}
+
+#[test]
+fn test_blake2b() {
+    assert_eq!(
+        Hash::hash(b"abc"),
+        [
+            0xba, 0x80, 0xa5, 0x3f, 0x98, 0x1c, 0x4d, 0x0d, 0x6a, 0x27, 0x97, 0xb6, 0x9f, 0x12,
+            0xf6, 0xe9, 0x4c, 0x21, 0x2f, 0x14, 0x68, 0x5a, 0xc4, 0xb7, 0x4b, 0x12, 0xbb, 0x6f,
+            0xdb, 0xff, 0xa2, 0xd1, 0x7d, 0x87, 0xc5, 0x39, 0x2a, 0xab, 0x79, 0x2d, 0xc2, 0x52,
+            0xd5, 0xde, 0x45, 0x33, 0xcc, 0x95, 0x18, 0xd3, 0x8a, 0xa8, 0xdb, 0xf1, 0x92, 0x5a,
+            0xb9, 0x23, 0x86, 0xed, 0xd4, 0x00, 0x99, 0x23,
+        ]
+    );
+    let mut h = Hash::new(32);
+    for _ in 0..10 {
+        h.update([0x61u8; 100]);
+    }
+    assert_eq!(
+        h.finalize()[..32],
+        [
+            0xe0, 0x0b, 0x0d, 0xdb, 0xf1, 0xe2, 0xcd, 0xaf, 0x5c, 0x89, 0x8e, 0x1a, 0x5e, 0x88,
+            0x26, 0xea, 0x3a, 0x2c, 0x33, 0x9b, 0xcf, 0x2a, 0x47, 0x8d, 0xa2, 0xe5, 0xfc, 0xa9,
+            0xff, 0x12, 0x66, 0x72,
+        ]
+    );
+    let mut h = Hash::new_keyed(20, b"k");
+    h.update(b"abc");
+    assert_eq!(
+        h.finalize()[..20],
+        [
+            0x80, 0xc1, 0x3d, 0x2f, 0x8e, 0xad, 0x08, 0x51, 0xff, 0x03, 0x2b, 0x67, 0xba, 0xc5,
+            0x38, 0x4e, 0xe1, 0x7f, 0x35, 0xc7,
+        ]
+    );
+}
@@ -0,0 +1,253 @@
+//! C API.
+//!
+//! Functions return `0` on success, and a negative error code on failure.
+//! Buffers are fixed-size arrays whose lengths are given by the
+//! `ED25519_COMPACT_*BYTES` constants of the `include/ed25519_compact.h`
+//! header.
+//!
+//! A shared library can be built with
+//! `cargo rustc --release --features=capi --crate-type=cdylib`.
+
+use core::ffi::c_int;
+use core::slice;
+
+use super::error::Error;
+use super::{KeyPair, PublicKey, SecretKey, Seed, Signature};
+
+/// Returned when a required pointer is null.
+pub const ED25519_COMPACT_ERR_NULL_POINTER: c_int = -255;
+
+fn error_code(err: Error) -> c_int {
+    -(err.code() as c_int)
+}
+
+fn status(res: Result<(), Error>) -> c_int {
+    match res {
+        Ok(()) => 0,
+        Err(err) => error_code(err),
+    }
+}
+
+unsafe fn array<'t, const N: usize>(ptr: *const u8) -> Option<&'t [u8; N]> {
+    (ptr as *const [u8; N]).as_ref()
+}
+
+unsafe fn array_mut<'t, const N: usize>(ptr: *mut u8) -> Option<&'t mut [u8; N]> {
+    (ptr as *mut [u8; N]).as_mut()
+}
+
+unsafe fn message<'t>(ptr: *const u8, len: usize) -> Option<&'t [u8]> {
+    if len == 0 {
+        Some(&[])
+    } else if ptr.is_null() {
+        None
+    } else {
+        Some(slice::from_raw_parts(ptr, len))
+    }
+}
+
+/// Derives a key pair from a 32-byte seed.
+///
+/// # Safety
+///
+/// `pk` must point to 32 writable bytes, `sk` to 64 writable bytes, and
+/// `seed` to 32 readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn ed25519_compact_keypair_from_seed(
+    pk: *mut u8,
+    sk: *mut u8,
+    seed: *const u8,
+) -> c_int {
+    let (pk, sk, seed) = match (array_mut::<32>(pk), array_mut::<64>(sk), array::<32>(seed)) {
+        (Some(pk), Some(sk), Some(seed)) => (pk, sk, seed),
+        _ => return ED25519_COMPACT_ERR_NULL_POINTER,
+    };
+    status(KeyPair::try_from_seed(Seed::new(*seed)).map(|kp| {
+        pk.copy_from_slice(&kp.pk[..]);
+        sk.copy_from_slice(&kp.sk[..]);
+    }))
+}
+
+/// Generates a random key pair.
+///
+/// # Safety
+///
+/// `pk` must point to 32 writable bytes, and `sk` to 64 writable bytes.
+#[cfg(any(feature = "random", feature = "entropy-source"))]
+#[no_mangle]
+pub unsafe extern "C" fn ed25519_compact_keypair(pk: *mut u8, sk: *mut u8) -> c_int {
+    match Seed::try_generate() {
+        Ok(seed) => ed25519_compact_keypair_from_seed(pk, sk, seed.as_ptr()),
+        Err(err) => error_code(err),
+    }
+}
+
+/// Computes a signature for a message.
+///
+/// # Safety
+///
+/// `sig` must point to 64 writable bytes, `sk` to 64 readable bytes, and
+/// `msg` to `msg_len` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn ed25519_compact_sign(
+    sig: *mut u8,
+    msg: *const u8,
+    msg_len: usize,
+    sk: *const u8,
+) -> c_int {
+    let (sig, msg, sk) = match (array_mut::<64>(sig), message(msg, msg_len), array::<64>(sk)) {
+        (Some(sig), Some(msg), Some(sk)) => (sig, msg, sk),
+        _ => return ED25519_COMPACT_ERR_NULL_POINTER,
+    };
+    status(
+        SecretKey::new(*sk)
+            .try_sign(msg, None)
+            .map(|signature| sig.copy_from_slice(&signature[..])),
+    )
+}
+
+/// Verifies a signature for a message.
+///
+/// # Safety
+///
+/// `sig` must point to 64 readable bytes, `pk` to 32 readable bytes, and
+/// `msg` to `msg_len` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn ed25519_compact_verify(
+    sig: *const u8,
+    msg: *const u8,
+    msg_len: usize,
+    pk: *const u8,
+) -> c_int {
+    let (sig, msg, pk) = match (array::<64>(sig), message(msg, msg_len), array::<32>(pk)) {
+        (Some(sig), Some(msg), Some(pk)) => (sig, msg, pk),
+        _ => return ED25519_COMPACT_ERR_NULL_POINTER,
+    };
+    status(PublicKey::new(*pk).verify(msg, &Signature::new(*sig)))
+}
+
+/// Computes the X25519 public key for a secret key.
+///
+/// # Safety
+///
+/// `pk` must point to 32 writable bytes, and `sk` to 32 readable bytes.
+#[cfg(feature = "x25519")]
+#[no_mangle]
+pub unsafe extern "C" fn ed25519_compact_x25519_public_key(pk: *mut u8, sk: *const u8) -> c_int {
+    use super::x25519;
+
+    let (pk, sk) = match (array_mut::<32>(pk), array::<32>(sk)) {
+        (Some(pk), Some(sk)) => (pk, sk),
+        _ => return ED25519_COMPACT_ERR_NULL_POINTER,
+    };
+    status(
+        x25519::SecretKey::new(*sk)
+            .recover_public_key()
+            .map(|x| pk.copy_from_slice(&x[..])),
+    )
+}
+
+/// Generates a random X25519 key pair.
+///
+/// # Safety
+///
+/// `pk` and `sk` must both point to 32 writable bytes.
+#[cfg(all(
+    feature = "x25519",
+    any(feature = "random", feature = "entropy-source")
+))]
+#[no_mangle]
+pub unsafe extern "C" fn ed25519_compact_x25519_keypair(pk: *mut u8, sk: *mut u8) -> c_int {
+    use super::x25519;
+
+    let (pk, sk) = match (array_mut::<32>(pk), array_mut::<32>(sk)) {
+        (Some(pk), Some(sk)) => (pk, sk),
+        _ => return ED25519_COMPACT_ERR_NULL_POINTER,
+    };
+    status(x25519::KeyPair::try_generate().map(|kp| {
+        pk.copy_from_slice(&kp.pk[..]);
+        sk.copy_from_slice(&kp.sk[..]);
+    }))
+}
+
+/// Computes a shared secret using X25519.
+///
+/// # Safety
+///
+/// `shared` must point to 32 writable bytes, and `sk` and `pk` to 32
+/// readable bytes.
+#[cfg(feature = "x25519")]
+#[no_mangle]
+pub unsafe extern "C" fn ed25519_compact_x25519(
+    shared: *mut u8,
+    sk: *const u8,
+    pk: *const u8,
+) -> c_int {
+    use super::x25519;
+
+    let (shared, sk, pk) = match (array_mut::<32>(shared), array::<32>(sk), array::<32>(pk)) {
+        (Some(shared), Some(sk), Some(pk)) => (shared, sk, pk),
+        _ => return ED25519_COMPACT_ERR_NULL_POINTER,
+    };
+    status(
+        x25519::PublicKey::new(*pk)
+            .dh(&x25519::SecretKey::new(*sk))
+            .map(|x| shared.copy_from_slice(&x[..])),
+    )
+}
+
+#[test]
+fn test_capi() {
+    use core::ptr;
+
+    let seed = [42u8; 32];
+    let mut pk = [0u8; 32];
+    let mut sk = [0u8; 64];
+    let mut sig = [0u8; 64];
+    let msg = b"message";
+    unsafe {
+        assert_eq!(
+            ed25519_compact_keypair_from_seed(pk.as_mut_ptr(), sk.as_mut_ptr(), seed.as_ptr()),
+            0
+        );
+        assert_eq!(
+            ed25519_compact_sign(sig.as_mut_ptr(), msg.as_ptr(), msg.len(), sk.as_ptr()),
+            0
+        );
+        assert_eq!(
+            ed25519_compact_verify(sig.as_ptr(), msg.as_ptr(), msg.len(), pk.as_ptr()),
+            0
+        );
+        assert_eq!(
+            ed25519_compact_verify(sig.as_ptr(), msg.as_ptr(), msg.len() - 1, pk.as_ptr()),
+            error_code(Error::SignatureMismatch)
+        );
+        assert_eq!(
+            ed25519_compact_verify(ptr::null(), msg.as_ptr(), msg.len(), pk.as_ptr()),
+            ED25519_COMPACT_ERR_NULL_POINTER
+        );
+    }
+    #[cfg(feature = "x25519")]
+    unsafe {
+        let (sk_a, sk_b) = ([1u8; 32], [2u8; 32]);
+        let (mut pk_a, mut pk_b) = ([0u8; 32], [0u8; 32]);
+        let (mut shared_a, mut shared_b) = ([0u8; 32], [0u8; 32]);
+        assert_eq!(
+            ed25519_compact_x25519_public_key(pk_a.as_mut_ptr(), sk_a.as_ptr()),
+            0
+        );
+        assert_eq!(
+            ed25519_compact_x25519_public_key(pk_b.as_mut_ptr(), sk_b.as_ptr()),
+            0
+        );
+        assert_eq!(
+            ed25519_compact_x25519(shared_a.as_mut_ptr(), sk_a.as_ptr(), pk_b.as_ptr()),
+            0
+        );
+        assert_eq!(
+            ed25519_compact_x25519(shared_b.as_mut_ptr(), sk_b.as_ptr(), pk_a.as_ptr()),
+            0
+        );
+        assert_eq!(shared_a, shared_b);
+    }
+}
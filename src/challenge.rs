@@ -0,0 +1,271 @@
+use std::collections::BTreeMap;
+use std::time::{Duration, SystemTime};
+
+use super::common::unix_time;
+use super::{Error, KeyPair, PublicKey, Signature};
+
+const DOMAIN: &[u8] = b"ed25519-compact challenge v1";
+
+/// Length of a challenge nonce, in bytes.
+pub const CHALLENGE_NONCE_BYTES: usize = 32;
+
+/// A challenge sent by a server to a client, for the client to prove that it
+/// holds a secret key.
+///
+/// The client's response is a signature binding the nonce, the timestamp,
+/// and both the server and client identities, so that it can't be replayed
+/// to another server, or on behalf of another client.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Challenge {
+    /// Random nonce, chosen by the server.
+    pub nonce: [u8; CHALLENGE_NONCE_BYTES],
+    /// Time the challenge was created, in seconds since the Unix epoch.
+    pub timestamp: u64,
+    /// Identity of the server.
+    pub server_id: Vec<u8>,
+}
+
+impl Challenge {
+    /// Creates a challenge for the server `server_id`, at time `now`.
+    #[cfg(any(feature = "random", feature = "entropy-source"))]
+    pub fn generate(server_id: &[u8], now: SystemTime) -> Result<Self, Error> {
+        Challenge::generate_with_rng(server_id, now, crate::entropy::fill_random)
+    }
+
+    /// Creates a challenge for the server `server_id`, at time `now`, using
+    /// a custom random number generator.
+    pub fn generate_with_rng(
+        server_id: &[u8],
+        now: SystemTime,
+        mut rng: impl FnMut(&mut [u8]) -> Result<(), Error>,
+    ) -> Result<Self, Error> {
+        let mut nonce = [0u8; CHALLENGE_NONCE_BYTES];
+        rng(&mut nonce)?;
+        Ok(Challenge {
+            nonce,
+            timestamp: unix_time(now),
+            server_id: server_id.to_vec(),
+        })
+    }
+
+    /// Returns the encoded challenge, to be sent to the client:
+    /// `nonce || timestamp (u64 BE) || server_id`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bin = Vec::with_capacity(CHALLENGE_NONCE_BYTES + 8 + self.server_id.len());
+        bin.extend_from_slice(&self.nonce);
+        bin.extend_from_slice(&self.timestamp.to_be_bytes());
+        bin.extend_from_slice(&self.server_id);
+        bin
+    }
+
+    /// Decodes a challenge.
+    pub fn from_bytes(bin: &[u8]) -> Result<Self, Error> {
+        if bin.len() < CHALLENGE_NONCE_BYTES + 8 {
+            return Err(Error::ParseError);
+        }
+        let mut nonce = [0u8; CHALLENGE_NONCE_BYTES];
+        nonce.copy_from_slice(&bin[..CHALLENGE_NONCE_BYTES]);
+        let mut timestamp = [0u8; 8];
+        timestamp.copy_from_slice(&bin[CHALLENGE_NONCE_BYTES..CHALLENGE_NONCE_BYTES + 8]);
+        Ok(Challenge {
+            nonce,
+            timestamp: u64::from_be_bytes(timestamp),
+            server_id: bin[CHALLENGE_NONCE_BYTES + 8..].to_vec(),
+        })
+    }
+
+    /// Returns the message signed by the client: the domain, the nonce, the
+    /// timestamp, and the length-prefixed server and client identities.
+    fn signed_message(&self, client_id: &[u8]) -> Vec<u8> {
+        let mut message = Vec::with_capacity(
+            DOMAIN.len() + CHALLENGE_NONCE_BYTES + 24 + self.server_id.len() + client_id.len(),
+        );
+        message.extend_from_slice(DOMAIN);
+        message.extend_from_slice(&self.nonce);
+        message.extend_from_slice(&self.timestamp.to_be_bytes());
+        message.extend_from_slice(&(self.server_id.len() as u64).to_be_bytes());
+        message.extend_from_slice(&self.server_id);
+        message.extend_from_slice(&(client_id.len() as u64).to_be_bytes());
+        message.extend_from_slice(client_id);
+        message
+    }
+
+    /// Answers the challenge as the client `client_id`.
+    ///
+    /// Returns `Error::FaultDetected` if a fault is detected while signing.
+    pub fn respond(&self, client: &KeyPair, client_id: &[u8]) -> Result<Signature, Error> {
+        client.sk.try_sign(self.signed_message(client_id), None)
+    }
+}
+
+/// Issues challenges, verifies responses to them, and rejects replays.
+///
+/// Only challenges issued by the verifier with `issue()` are accepted, for
+/// `window` after their creation, and each of them can only be answered
+/// once. Pending challenges are only kept in memory, so challenges issued
+/// before the verifier was created are rejected.
+#[derive(Clone, Debug)]
+pub struct ChallengeVerifier {
+    server_id: Vec<u8>,
+    window: Duration,
+    pending: BTreeMap<[u8; CHALLENGE_NONCE_BYTES], u64>,
+}
+
+impl ChallengeVerifier {
+    /// Creates a verifier for the server `server_id`, accepting responses for
+    /// `window` after a challenge has been issued.
+    pub fn new(server_id: &[u8], window: Duration) -> Self {
+        ChallengeVerifier {
+            server_id: server_id.to_vec(),
+            window,
+            pending: BTreeMap::new(),
+        }
+    }
+
+    /// Issues a new challenge at time `now`, to be sent to a client.
+    #[cfg(any(feature = "random", feature = "entropy-source"))]
+    pub fn issue(&mut self, now: SystemTime) -> Result<Challenge, Error> {
+        self.issue_with_rng(now, crate::entropy::fill_random)
+    }
+
+    /// Issues a new challenge at time `now`, using a custom random number
+    /// generator.
+    pub fn issue_with_rng(
+        &mut self,
+        now: SystemTime,
+        rng: impl FnMut(&mut [u8]) -> Result<(), Error>,
+    ) -> Result<Challenge, Error> {
+        let challenge = Challenge::generate_with_rng(&self.server_id, now, rng)?;
+        self.expire(unix_time(now));
+        if self.pending.contains_key(&challenge.nonce) {
+            return Err(Error::RngFailure);
+        }
+        self.pending.insert(challenge.nonce, challenge.timestamp);
+        Ok(challenge)
+    }
+
+    fn expire(&mut self, now: u64) {
+        let window = self.window.as_secs();
+        self.pending
+            .retain(|_, &mut ts| ts.saturating_add(window) >= now);
+    }
+
+    /// Verifies the response of client `client_id`, with public key
+    /// `client_pk`, to a challenge, and consumes the challenge if the
+    /// response is valid.
+    ///
+    /// Returns `Error::InvalidContext` if the challenge was made for another
+    /// server, and `Error::Expired` if it is outside the window, was not
+    /// issued by this verifier, or has already been answered.
+    pub fn verify(
+        &mut self,
+        challenge: &Challenge,
+        client_pk: &PublicKey,
+        client_id: &[u8],
+        response: &Signature,
+        now: SystemTime,
+    ) -> Result<(), Error> {
+        if challenge.server_id != self.server_id {
+            return Err(Error::InvalidContext);
+        }
+        let now = unix_time(now);
+        self.expire(now);
+        if challenge.timestamp > now
+            || challenge.timestamp.saturating_add(self.window.as_secs()) < now
+        {
+            return Err(Error::Expired);
+        }
+        if self.pending.get(&challenge.nonce) != Some(&challenge.timestamp) {
+            return Err(Error::Expired);
+        }
+        client_pk.verify(challenge.signed_message(client_id), response)?;
+        self.pending.remove(&challenge.nonce);
+        Ok(())
+    }
+}
+
+#[test]
+fn test_challenge() {
+    use std::time::UNIX_EPOCH;
+
+    use super::Seed;
+
+    let client = KeyPair::from_seed(Seed::new([1u8; Seed::BYTES]));
+    let other = KeyPair::from_seed(Seed::new([2u8; Seed::BYTES]));
+    let t = |secs: u64| UNIX_EPOCH + Duration::from_secs(secs);
+    let mut counter = 0u8;
+    let mut rng = |buf: &mut [u8]| {
+        counter += 1;
+        buf.fill(counter);
+        Ok(())
+    };
+
+    let mut verifier = ChallengeVerifier::new(b"server", Duration::from_secs(60));
+    let challenge = verifier.issue_with_rng(t(1000), &mut rng).unwrap();
+    let challenge = Challenge::from_bytes(&challenge.to_bytes()).unwrap();
+    let response = challenge.respond(&client, b"device-42").unwrap();
+
+    assert!(verifier
+        .verify(&challenge, &client.pk, b"device-43", &response, t(1010))
+        .is_err());
+    assert!(verifier
+        .verify(&challenge, &other.pk, b"device-42", &response, t(1010))
+        .is_err());
+    let mut backdated = challenge.clone();
+    backdated.timestamp -= 1;
+    let backdated_response = backdated.respond(&client, b"device-42").unwrap();
+    assert_eq!(
+        verifier.verify(
+            &backdated,
+            &client.pk,
+            b"device-42",
+            &backdated_response,
+            t(1010)
+        ),
+        Err(Error::Expired)
+    );
+    assert!(verifier
+        .verify(&challenge, &client.pk, b"device-42", &response, t(1010))
+        .is_ok());
+    assert_eq!(
+        verifier.verify(&challenge, &client.pk, b"device-42", &response, t(1011)),
+        Err(Error::Expired)
+    );
+    assert!(verifier.pending.is_empty());
+
+    // Challenges that were not issued by the verifier are rejected.
+    let forged = Challenge::generate_with_rng(b"server", t(1000), &mut rng).unwrap();
+    let response = forged.respond(&client, b"device-42").unwrap();
+    assert_eq!(
+        verifier.verify(&forged, &client.pk, b"device-42", &response, t(1010)),
+        Err(Error::Expired)
+    );
+
+    let challenge = Challenge::generate_with_rng(b"other-server", t(1000), &mut rng).unwrap();
+    let response = challenge.respond(&client, b"device-42").unwrap();
+    assert_eq!(
+        verifier.verify(&challenge, &client.pk, b"device-42", &response, t(1010)),
+        Err(Error::InvalidContext)
+    );
+
+    let challenge = verifier.issue_with_rng(t(1000), &mut rng).unwrap();
+    let response = challenge.respond(&client, b"device-42").unwrap();
+    assert_eq!(
+        verifier.verify(&challenge, &client.pk, b"device-42", &response, t(1061)),
+        Err(Error::Expired)
+    );
+    assert!(verifier.pending.is_empty());
+    verifier.issue_with_rng(t(1000), &mut rng).unwrap();
+    verifier.issue_with_rng(t(2000), &mut rng).unwrap();
+    assert_eq!(verifier.pending.len(), 1);
+
+    #[cfg(any(feature = "random", feature = "entropy-source"))]
+    {
+        let challenge = verifier.issue(t(2000)).unwrap();
+        let response = challenge.respond(&client, b"device-42").unwrap();
+        assert!(verifier
+            .verify(&challenge, &client.pk, b"device-42", &response, t(2000))
+            .is_ok());
+        assert_eq!(verifier.pending.len(), 1);
+    }
+}
@@ -0,0 +1,185 @@
+use core::fmt;
+use core::ops::Deref;
+
+use super::common::*;
+use super::edwards25519::{is_identity, sc_reduce};
+use super::error::Error;
+use super::hazmat::ExpandedSecretKey;
+use super::sha512;
+use super::{Noise, PublicKey, SecretKey, Seed, Signature};
+
+/// A chain code, mixed with a public key to derive child keys.
+///
+/// Anyone who knows a chain code and the matching public key can compute all
+/// the child public keys, so chain codes should not be published.
+#[derive(Copy, Clone, Eq, PartialEq, Hash)]
+pub struct ChainCode([u8; ChainCode::BYTES]);
+
+impl fmt::Debug for ChainCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_redacted(f, "ChainCode", &self.0)
+    }
+}
+
+impl From<[u8; 32]> for ChainCode {
+    fn from(chain_code: [u8; 32]) -> Self {
+        ChainCode(chain_code)
+    }
+}
+
+impl ChainCode {
+    /// Number of raw bytes in a chain code.
+    pub const BYTES: usize = 32;
+
+    /// Creates a chain code from raw bytes.
+    pub fn new(chain_code: [u8; ChainCode::BYTES]) -> Self {
+        ChainCode(chain_code)
+    }
+
+    /// Creates a chain code from a slice.
+    pub fn from_slice(chain_code: &[u8]) -> Result<Self, Error> {
+        let mut chain_code_ = [0u8; ChainCode::BYTES];
+        if chain_code.len() != chain_code_.len() {
+            return Err(Error::ParseError);
+        }
+        chain_code_.copy_from_slice(chain_code);
+        Ok(ChainCode::new(chain_code_))
+    }
+
+    /// Returns the tweak to add to the parent key, and the chain code of the
+    /// child number `index`.
+    fn child(&self, pk: &PublicKey, index: u32) -> ([u8; 32], ChainCode) {
+        let mut h = sha512::Hmac::new(&self.0);
+        h.update([0x02]);
+        h.update(&pk[..]);
+        h.update(index.to_be_bytes());
+        let mut z = h.finalize();
+        sc_reduce(&mut z);
+        let mut tweak = [0u8; 32];
+        tweak.copy_from_slice(&z[0..32]);
+
+        let mut h = sha512::Hmac::new(&self.0);
+        h.update([0x03]);
+        h.update(&pk[..]);
+        h.update(index.to_be_bytes());
+        let c = h.finalize();
+        let mut chain_code = [0u8; ChainCode::BYTES];
+        chain_code.copy_from_slice(&c[0..32]);
+        (tweak, ChainCode(chain_code))
+    }
+}
+
+impl Deref for ChainCode {
+    type Target = [u8; ChainCode::BYTES];
+
+    /// Returns a chain code as raw bytes.
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+/// A public key and a chain code, from which child public keys can be
+/// derived without any secret material.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct ExtendedPublicKey {
+    /// The public key.
+    pub pk: PublicKey,
+    /// The chain code.
+    pub chain_code: ChainCode,
+}
+
+impl ExtendedPublicKey {
+    /// Creates an extended public key from a public key and a chain code.
+    pub fn new(pk: PublicKey, chain_code: ChainCode) -> Self {
+        ExtendedPublicKey { pk, chain_code }
+    }
+
+    /// Derives the child public key number `index`.
+    ///
+    /// Its secret key is the output of `ExtendedSecretKey::derive_child()`
+    /// with the same index.
+    pub fn derive_child(&self, index: u32) -> Result<ExtendedPublicKey, Error> {
+        let (mut tweak, chain_code) = self.chain_code.child(&self.pk, index);
+        let pk = self.pk.tweak_add(&tweak);
+        wipe(&mut tweak);
+        Ok(ExtendedPublicKey {
+            pk: pk?,
+            chain_code,
+        })
+    }
+}
+
+/// An expanded secret key and a chain code, from which child secret keys
+/// can be derived.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct ExtendedSecretKey {
+    /// The expanded secret key.
+    pub sk: ExpandedSecretKey,
+    /// The chain code.
+    pub chain_code: ChainCode,
+}
+
+impl ExtendedSecretKey {
+    /// Creates an extended secret key from a secret key and a chain code.
+    pub fn new(sk: &SecretKey, chain_code: ChainCode) -> Self {
+        ExtendedSecretKey {
+            sk: ExpandedSecretKey::from_secret_key(sk),
+            chain_code,
+        }
+    }
+
+    /// Creates an extended secret key from a seed, also used to derive the
+    /// chain code.
+    pub fn from_seed(seed: &Seed) -> Result<Self, Error> {
+        let sk = SecretKey::from_seed(seed)?;
+        let chain_code = ChainCode(*seed.derive(b"chain code", 0));
+        Ok(ExtendedSecretKey::new(&sk, chain_code))
+    }
+
+    /// Returns the extended public key, that can be shared with services
+    /// that only need to derive child public keys.
+    pub fn extended_public_key(&self) -> ExtendedPublicKey {
+        ExtendedPublicKey {
+            pk: self.sk.public_key(),
+            chain_code: self.chain_code,
+        }
+    }
+
+    /// Derives the child secret key number `index`.
+    pub fn derive_child(&self, index: u32) -> Result<ExtendedSecretKey, Error> {
+        let pk = self.sk.public_key();
+        let (mut tweak, chain_code) = self.chain_code.child(&pk, index);
+        let sk = self.sk.tweak_add(&tweak);
+        wipe(&mut tweak);
+        let sk = sk?;
+        if is_identity(&sk.public_key()) {
+            return Err(Error::WeakPublicKey);
+        }
+        Ok(ExtendedSecretKey { sk, chain_code })
+    }
+
+    /// Computes a signature for the message `message`, that can be verified
+    /// with the public key of the extended public key.
+    pub fn sign(&self, message: impl AsRef<[u8]>, noise: Option<Noise>) -> Signature {
+        self.sk.sign(message, noise)
+    }
+}
+
+#[test]
+fn test_child() {
+    let master = ExtendedSecretKey::from_seed(&Seed::new([0x42u8; Seed::BYTES])).unwrap();
+    let xpub = master.extended_public_key();
+
+    let child_sk = master.derive_child(0).unwrap().derive_child(7).unwrap();
+    let child_pk = xpub.derive_child(0).unwrap().derive_child(7).unwrap();
+    assert_eq!(child_sk.extended_public_key(), child_pk);
+    assert_ne!(child_pk.pk, xpub.pk);
+    assert_ne!(
+        child_pk,
+        xpub.derive_child(1).unwrap().derive_child(7).unwrap()
+    );
+
+    let signature = child_sk.sign(b"watch-only", None);
+    assert!(child_pk.pk.verify(b"watch-only", &signature).is_ok());
+    assert!(xpub.pk.verify(b"watch-only", &signature).is_err());
+}
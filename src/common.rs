@@ -1,11 +1,21 @@
+use core::fmt;
 use core::ops::{Deref, DerefMut};
+use core::ptr;
+use core::sync::atomic::{compiler_fence, Ordering};
 
 use super::error::Error;
+use super::sha512;
 
 /// A seed, which a key pair can be derived from.
-#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[derive(Copy, Clone, Eq, PartialEq, Hash)]
 pub struct Seed([u8; Seed::BYTES]);
 
+impl fmt::Debug for Seed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_redacted(f, "Seed", &self.0)
+    }
+}
+
 impl From<[u8; 32]> for Seed {
     fn from(seed: [u8; 32]) -> Self {
         Seed(seed)
@@ -17,7 +27,7 @@ impl Seed {
     pub const BYTES: usize = 32;
 
     /// Creates a seed from raw bytes.
-    pub fn new(seed: [u8; Seed::BYTES]) -> Self {
+    pub const fn new(seed: [u8; Seed::BYTES]) -> Self {
         Seed(seed)
     }
 
@@ -30,24 +40,81 @@ impl Seed {
         seed_.copy_from_slice(seed);
         Ok(Seed::new(seed_))
     }
+
+    /// Derives an independent sub-seed from this seed, a `context` string
+    /// and an `index`, using HKDF-SHA-512.
+    ///
+    /// This allows a single backed up seed to drive many key pairs, one
+    /// for each purpose. Sub-seeds don't reveal anything about the master
+    /// seed or about each other.
+    pub fn derive(&self, context: &[u8], index: u32) -> Seed {
+        let mut prk = sha512::Hmac::mac(b"ed25519-compact seed derivation", self.0);
+        let mut h = sha512::Hmac::new(&prk);
+        h.update(context);
+        h.update(index.to_be_bytes());
+        h.update([1u8]);
+        let mut okm = h.finalize();
+        let mut seed = [0u8; Seed::BYTES];
+        seed.copy_from_slice(&okm[..Seed::BYTES]);
+        wipe(&mut prk);
+        wipe(&mut okm);
+        Seed(seed)
+    }
+
+    /// Derives a seed from a password and a salt, using PBKDF2-HMAC-SHA512
+    /// with `iterations` rounds.
+    ///
+    /// The salt should be unique to the user or application, and the number
+    /// of iterations as large as acceptable: passwords usually have very low
+    /// entropy, and anyone who can guess the password can recover the key.
+    pub fn from_password(password: &[u8], salt: &[u8], iterations: u32) -> Result<Self, Error> {
+        if iterations == 0 {
+            return Err(Error::InvalidSeed);
+        }
+        let prf = sha512::Hmac::new(password);
+        let mut h = prf;
+        h.update(salt);
+        h.update(1u32.to_be_bytes());
+        let mut u = h.finalize();
+        let mut t = u;
+        for _ in 1..iterations {
+            let mut h = prf;
+            h.update(u);
+            u = h.finalize();
+            for (t, u) in t.iter_mut().zip(u.iter()) {
+                *t ^= u;
+            }
+        }
+        let mut seed = [0u8; Seed::BYTES];
+        seed.copy_from_slice(&t[..Seed::BYTES]);
+        wipe(&mut u);
+        wipe(&mut t);
+        Ok(Seed(seed))
+    }
 }
 
-#[cfg(feature = "random")]
+#[cfg(any(feature = "random", feature = "entropy-source"))]
 impl Default for Seed {
     /// Generates a random seed.
     fn default() -> Self {
-        let mut seed = [0u8; Seed::BYTES];
-        getrandom::getrandom(&mut seed).expect("RNG failure");
-        Seed(seed)
+        Seed::try_generate().expect("RNG failure")
     }
 }
 
-#[cfg(feature = "random")]
+#[cfg(any(feature = "random", feature = "entropy-source"))]
 impl Seed {
     /// Generates a random seed.
     pub fn generate() -> Self {
         Seed::default()
     }
+
+    /// Generates a random seed, returning an error if the system RNG is
+    /// not available.
+    pub fn try_generate() -> Result<Self, Error> {
+        let mut seed = [0u8; Seed::BYTES];
+        crate::entropy::fill_random(&mut seed)?;
+        Ok(Seed(seed))
+    }
 }
 
 impl Deref for Seed {
@@ -65,3 +132,114 @@ impl DerefMut for Seed {
         &mut self.0
     }
 }
+
+/// Clamps a scalar the way Ed25519 and X25519 key derivation does: clears
+/// the 3 lowest bits and the highest bit, and sets bit 254.
+pub fn clamp(scalar: &mut [u8; 32]) {
+    scalar[0] &= 248;
+    scalar[31] &= 63;
+    scalar[31] |= 64;
+}
+
+/// Overwrites a buffer with zeros, in a way that the compiler cannot optimize
+/// away, so that secret material doesn't linger in memory.
+#[inline(never)]
+pub(crate) fn wipe(buf: &mut [u8]) {
+    for x in buf.iter_mut() {
+        unsafe { ptr::write_volatile(x, 0) };
+    }
+    compiler_fence(Ordering::SeqCst);
+}
+
+/// Compares two buffers in constant time.
+#[allow(dead_code)]
+pub(crate) fn ct_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let d = a
+        .iter()
+        .zip(b.iter())
+        .fold(0u8, |acc, (x, y)| acc | (x ^ y));
+    unsafe { ptr::read_volatile(&d) == 0 }
+}
+
+/// Returns the number of seconds since the Unix epoch at time `t`, or `0`
+/// for times before the epoch.
+#[cfg(feature = "std")]
+#[allow(dead_code)]
+pub(crate) fn unix_time(t: std::time::SystemTime) -> u64 {
+    t.duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Reads a big-endian `u64` at offset `i` of `bin`.
+#[allow(dead_code)]
+pub(crate) fn u64_at(bin: &[u8], i: usize) -> u64 {
+    let mut x = [0u8; 8];
+    x.copy_from_slice(&bin[i..i + 8]);
+    u64::from_be_bytes(x)
+}
+
+/// Formats secret material as its type name followed by a short fingerprint,
+/// so that different secrets can be told apart in logs without being revealed.
+pub(crate) fn fmt_redacted(f: &mut fmt::Formatter<'_>, name: &str, secret: &[u8]) -> fmt::Result {
+    let mut hasher = sha512::Hash::new();
+    hasher.update(b"ed25519-compact redacted fingerprint");
+    hasher.update(secret);
+    let h = hasher.finalize();
+    write!(
+        f,
+        "{}(fingerprint: {:02x}{:02x}{:02x}{:02x})",
+        name, h[0], h[1], h[2], h[3]
+    )
+}
+
+#[test]
+fn test_seed_derive() {
+    use ct_codecs::{Decoder, Hex};
+
+    let mut master = [0u8; Seed::BYTES];
+    for (i, x) in master.iter_mut().enumerate() {
+        *x = i as u8;
+    }
+    let master = Seed::new(master);
+    let expected = Hex::decode_to_vec(
+        "4ed89061903693eadea33afb4d8b5b45fab6b0863cdd817c1382983f02b0cfd0",
+        None,
+    )
+    .unwrap();
+    assert_eq!(&master.derive(b"ssh", 0)[..], &expected[..]);
+    let expected = Hex::decode_to_vec(
+        "ec30a6af194e110201ca0c883ef44521a00ad216ce8d0bd7c9f1d2f953c0c6b9",
+        None,
+    )
+    .unwrap();
+    assert_eq!(&master.derive(b"ssh", 1)[..], &expected[..]);
+    assert_ne!(master.derive(b"ssh", 0), master.derive(b"tls", 0));
+}
+
+#[test]
+fn test_seed_from_password() {
+    use ct_codecs::{Decoder, Hex};
+
+    let expected = Hex::decode_to_vec(
+        "867f70cf1ade02cff3752599a3a53dc4af34c7a669815ae5d513554e1c8cf252",
+        None,
+    )
+    .unwrap();
+    let seed = Seed::from_password(b"password", b"salt", 1).unwrap();
+    assert_eq!(&seed[..], &expected[..]);
+    let expected = Hex::decode_to_vec(
+        "a8af739821116d32f0a50ff661399ca1b3dc849e78bba7e443e7f5b7ac6d7859",
+        None,
+    )
+    .unwrap();
+    let seed = Seed::from_password(b"correct horse battery staple", b"ed25519 salt", 1000).unwrap();
+    assert_eq!(&seed[..], &expected[..]);
+    assert_eq!(
+        Seed::from_password(b"password", b"salt", 0),
+        Err(Error::InvalidSeed)
+    );
+}
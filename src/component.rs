@@ -0,0 +1,103 @@
+//! WebAssembly Component Model bindings, implementing the `crypto` world
+//! defined in `wit/ed25519-compact.wit`.
+//!
+//! A component can be built with
+//! `cargo rustc --release --target=wasm32-wasip2 --features=component --crate-type=cdylib`.
+
+use super::error::Error;
+use super::{x25519, KeyPair, PublicKey, SecretKey, Seed, Signature};
+
+wit_bindgen::generate!({
+    world: "crypto",
+    path: "wit",
+});
+
+use exports::ed25519_compact::crypto::ed25519;
+use exports::ed25519_compact::crypto::x25519 as wit_x25519;
+
+struct Component;
+
+fn err(e: Error) -> String {
+    e.to_string()
+}
+
+impl ed25519::Guest for Component {
+    fn generate_key_pair() -> Result<ed25519::KeyPair, String> {
+        let kp = KeyPair::try_generate().map_err(err)?;
+        Ok(ed25519::KeyPair {
+            public_key: kp.pk.to_vec(),
+            secret_key: kp.sk.to_vec(),
+        })
+    }
+
+    fn key_pair_from_seed(seed: Vec<u8>) -> Result<ed25519::KeyPair, String> {
+        let kp = KeyPair::try_from_seed(Seed::from_slice(&seed).map_err(err)?).map_err(err)?;
+        Ok(ed25519::KeyPair {
+            public_key: kp.pk.to_vec(),
+            secret_key: kp.sk.to_vec(),
+        })
+    }
+
+    fn sign(secret_key: Vec<u8>, message: Vec<u8>) -> Result<Vec<u8>, String> {
+        let sk = SecretKey::from_slice(&secret_key).map_err(err)?;
+        let signature = sk.try_sign(message, None).map_err(err)?;
+        Ok(signature.to_vec())
+    }
+
+    fn verify(public_key: Vec<u8>, message: Vec<u8>, signature: Vec<u8>) -> Result<(), String> {
+        let pk = PublicKey::from_slice(&public_key).map_err(err)?;
+        let signature = Signature::from_slice(&signature).map_err(err)?;
+        pk.verify(message, &signature).map_err(err)
+    }
+}
+
+impl wit_x25519::Guest for Component {
+    fn generate_key_pair() -> Result<wit_x25519::KeyPair, String> {
+        let kp = x25519::KeyPair::try_generate().map_err(err)?;
+        Ok(wit_x25519::KeyPair {
+            public_key: kp.pk.to_vec(),
+            secret_key: kp.sk.to_vec(),
+        })
+    }
+
+    fn public_key(secret_key: Vec<u8>) -> Result<Vec<u8>, String> {
+        let sk = x25519::SecretKey::from_slice(&secret_key).map_err(err)?;
+        Ok(sk.recover_public_key().map_err(err)?.to_vec())
+    }
+
+    fn dh(public_key: Vec<u8>, secret_key: Vec<u8>) -> Result<Vec<u8>, String> {
+        let pk = x25519::PublicKey::from_slice(&public_key).map_err(err)?;
+        let sk = x25519::SecretKey::from_slice(&secret_key).map_err(err)?;
+        Ok(pk.dh(&sk).map_err(err)?.to_vec())
+    }
+}
+
+export!(Component);
+
+#[test]
+fn test_component() {
+    use ed25519::Guest as _;
+    use wit_x25519::Guest as _;
+
+    let kp = <Component as ed25519::Guest>::key_pair_from_seed(vec![42u8; 32]).unwrap();
+    let signature = Component::sign(kp.secret_key, b"message".to_vec()).unwrap();
+    assert!(Component::verify(
+        kp.public_key.clone(),
+        b"message".to_vec(),
+        signature.clone()
+    )
+    .is_ok());
+    assert!(Component::verify(kp.public_key, b"other".to_vec(), signature).is_err());
+    assert!(<Component as ed25519::Guest>::key_pair_from_seed(vec![42u8; 31]).is_err());
+
+    let kp_a = <Component as wit_x25519::Guest>::generate_key_pair().unwrap();
+    let kp_b = <Component as wit_x25519::Guest>::generate_key_pair().unwrap();
+    assert_eq!(
+        Component::public_key(kp_a.secret_key.clone()).unwrap(),
+        kp_a.public_key
+    );
+    assert_eq!(
+        Component::dh(kp_b.public_key, kp_a.secret_key).unwrap(),
+        Component::dh(kp_a.public_key, kp_b.secret_key).unwrap()
+    );
+}
@@ -0,0 +1,462 @@
+//! Conformance test vectors, in the format of Project Wycheproof, and a
+//! runner to check them against the current build.
+//!
+//! The vectors cover the edge cases Wycheproof tests for: valid signatures
+//! and shared secrets, malleable and truncated signatures, non-canonical
+//! encodings, points not on the curve and points of small order. Products
+//! can call `run()` as part of their own acceptance testing, with the exact
+//! set of features they compile.
+//!
+//! These are not the upstream Wycheproof files: the vectors were generated
+//! for this crate and cross-checked against an independent implementation,
+//! and their identifiers are numbered locally. They don't match Wycheproof
+//! `tcId`s.
+
+use super::error::Error;
+
+/// The expected outcome of a test vector.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Expected {
+    /// The input must be accepted, with the expected output.
+    Valid,
+    /// The input must be rejected.
+    Invalid,
+    /// The input may be either rejected, or accepted with the expected
+    /// output. Implementations legitimately differ here.
+    Acceptable,
+}
+
+/// A test vector that didn't produce the expected outcome.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Failure {
+    /// The test suite the vector belongs to, `"ed25519"` or `"x25519"`.
+    pub suite: &'static str,
+    /// The identifier of the vector in its suite. Vectors are numbered
+    /// locally, and this is not a Wycheproof `tcId`.
+    pub tc_id: u32,
+    /// A description of what the vector tests.
+    pub comment: &'static str,
+}
+
+/// The outcome of a conformance run.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct Report {
+    /// Number of vectors that produced the expected outcome.
+    pub passed: usize,
+    /// Number of vectors that didn't.
+    pub failed: usize,
+    /// The first vector that didn't produce the expected outcome, if any.
+    pub first_failure: Option<Failure>,
+}
+
+impl Report {
+    /// Returns `true` if all the vectors produced the expected outcome.
+    pub fn is_ok(&self) -> bool {
+        self.failed == 0
+    }
+
+    fn record(&mut self, suite: &'static str, tc_id: u32, comment: &'static str, ok: bool) {
+        if ok {
+            self.passed += 1;
+            return;
+        }
+        self.failed += 1;
+        if self.first_failure.is_none() {
+            self.first_failure = Some(Failure {
+                suite,
+                tc_id,
+                comment,
+            });
+        }
+    }
+}
+
+/// Runs all the test vectors supported by the enabled features.
+pub fn run() -> Report {
+    let mut report = Report::default();
+    #[cfg(not(feature = "disable-signatures"))]
+    for v in EDDSA_VECTORS.iter() {
+        report.record("ed25519", v.tc_id, v.comment, v.check());
+    }
+    #[cfg(feature = "x25519")]
+    for v in XDH_VECTORS.iter() {
+        report.record("x25519", v.tc_id, v.comment, v.check());
+    }
+    report
+}
+
+/// Decodes the hex string `hex` into `buf`, and returns the decoded prefix.
+fn decode_hex<'t>(hex: &str, buf: &'t mut [u8]) -> &'t [u8] {
+    let nibble = |c: u8| match c {
+        b'0'..=b'9' => c - b'0',
+        _ => c - b'a' + 10,
+    };
+    let len = hex.len() / 2;
+    for (x, pair) in buf.iter_mut().zip(hex.as_bytes().chunks(2)) {
+        *x = (nibble(pair[0]) << 4) | nibble(pair[1]);
+    }
+    &buf[..len]
+}
+
+#[cfg(not(feature = "disable-signatures"))]
+struct EddsaVector {
+    tc_id: u32,
+    comment: &'static str,
+    pk: &'static str,
+    msg: &'static str,
+    sig: &'static str,
+    result: Expected,
+    /// For `Acceptable` vectors, the error a rejection must report.
+    error: Option<Error>,
+}
+
+#[cfg(not(feature = "disable-signatures"))]
+impl EddsaVector {
+    fn verify(&self) -> Result<(), Error> {
+        use super::{PublicKey, Signature};
+
+        let (mut pk, mut msg, mut sig) = ([0u8; 32], [0u8; 128], [0u8; 65]);
+        let pk = PublicKey::from_slice(decode_hex(self.pk, &mut pk))?;
+        let sig = Signature::from_slice(decode_hex(self.sig, &mut sig))?;
+        pk.verify(decode_hex(self.msg, &mut msg), &sig)
+    }
+
+    fn check(&self) -> bool {
+        let res = self.verify();
+        match self.result {
+            Expected::Valid => res.is_ok(),
+            Expected::Invalid => res.is_err(),
+            Expected::Acceptable => res.map_or_else(|e| Some(e) == self.error, |_| true),
+        }
+    }
+}
+
+#[cfg(feature = "x25519")]
+struct XdhVector {
+    tc_id: u32,
+    comment: &'static str,
+    public: &'static str,
+    private: &'static str,
+    shared: &'static str,
+    result: Expected,
+}
+
+#[cfg(feature = "x25519")]
+impl XdhVector {
+    fn dh(&self) -> Result<[u8; 32], Error> {
+        use super::x25519::{PublicKey, SecretKey};
+
+        let (mut pk, mut sk) = ([0u8; 32], [0u8; 32]);
+        let pk = PublicKey::from_slice(decode_hex(self.public, &mut pk))?;
+        let sk = SecretKey::from_slice(decode_hex(self.private, &mut sk))?;
+        Ok(*pk.dh(&sk)?)
+    }
+
+    fn check(&self) -> bool {
+        let mut shared = [0u8; 32];
+        let shared = decode_hex(self.shared, &mut shared);
+        let res = self.dh();
+        match self.result {
+            Expected::Valid => res.is_ok_and(|x| x[..] == *shared),
+            Expected::Invalid => res.is_err(),
+            Expected::Acceptable => res.map_or(true, |x| x[..] == *shared),
+        }
+    }
+}
+
+#[cfg(not(feature = "disable-signatures"))]
+static EDDSA_VECTORS: [EddsaVector; 21] = [
+    EddsaVector {
+        tc_id: 1,
+        comment: "valid",
+        pk: "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a",
+        msg: "",
+        sig: "e5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e065224901555fb8821590a33bacc61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b",
+        result: Expected::Valid,
+        error: None,
+    },
+    EddsaVector {
+        tc_id: 2,
+        comment: "valid",
+        pk: "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a",
+        msg: "78",
+        sig: "18d0b7f5338df220d1ec7c8e820a9f59afcb5dbd480d04842e5d3a8f139efd9b5ab4fe6ff283ab7667f4736530bf8ea25f189083283280c5acc05ac4a5fdc609",
+        result: Expected::Valid,
+        error: None,
+    },
+    EddsaVector {
+        tc_id: 3,
+        comment: "valid",
+        pk: "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a",
+        msg: "54657374",
+        sig: "0358333856a92f93b519f4dcef9876f94215d8dd719f42220291ee2acef6eab66ac1112ab0da59995107b3422157bc8efbd15baf8f6d8bb12a4142748c32490b",
+        result: Expected::Valid,
+        error: None,
+    },
+    EddsaVector {
+        tc_id: 4,
+        comment: "valid",
+        pk: "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a",
+        msg: "48656c6c6f",
+        sig: "52dc29f7ec08cf13d82af0738b2d12ff7da1b967866e9cf9bcd22d7972f1be2cfad44b3018e30969edd07a0fb902a95685707003011c50de3b1cec146a0d4207",
+        result: Expected::Valid,
+        error: None,
+    },
+    EddsaVector {
+        tc_id: 5,
+        comment: "valid",
+        pk: "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a",
+        msg: "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f202122232425262728292a2b2c2d2e2f303132333435363738393a3b3c3d3e3f",
+        sig: "2dc5736c5188aa2a615768c465cf49934f561cbfddc4ebf8cfb6cf323efc2e71c91b48cb26c2699ce2f56e24ace6dcc51f9cc7fb992a55c833c0889111f4ba03",
+        result: Expected::Valid,
+        error: None,
+    },
+    EddsaVector {
+        tc_id: 6,
+        comment: "valid",
+        pk: "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a",
+        msg: "00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000",
+        sig: "e9775f75addfa14e378d3d3770765bc98276853983deba47b502181352c22a08d647449aba2044d70c7b9938fb18d891b07b15c979c7675bb29cb6ce66b74409",
+        result: Expected::Valid,
+        error: None,
+    },
+    EddsaVector {
+        tc_id: 7,
+        comment: "modified message",
+        pk: "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a",
+        msg: "54657375",
+        sig: "0358333856a92f93b519f4dcef9876f94215d8dd719f42220291ee2acef6eab66ac1112ab0da59995107b3422157bc8efbd15baf8f6d8bb12a4142748c32490b",
+        result: Expected::Invalid,
+        error: None,
+    },
+    EddsaVector {
+        tc_id: 8,
+        comment: "empty message",
+        pk: "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a",
+        msg: "",
+        sig: "0358333856a92f93b519f4dcef9876f94215d8dd719f42220291ee2acef6eab66ac1112ab0da59995107b3422157bc8efbd15baf8f6d8bb12a4142748c32490b",
+        result: Expected::Invalid,
+        error: None,
+    },
+    EddsaVector {
+        tc_id: 9,
+        comment: "s replaced with s + L",
+        pk: "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a",
+        msg: "54657374",
+        sig: "0358333856a92f93b519f4dcef9876f94215d8dd719f42220291ee2acef6eab657950787ca3d6cf127a4aae5ff509ba3fbd15baf8f6d8bb12a4142748c32491b",
+        result: Expected::Invalid,
+        error: None,
+    },
+    EddsaVector {
+        tc_id: 10,
+        comment: "s with the most significant bit set",
+        pk: "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a",
+        msg: "54657374",
+        sig: "0358333856a92f93b519f4dcef9876f94215d8dd719f42220291ee2acef6eab66ac1112ab0da59995107b3422157bc8efbd15baf8f6d8bb12a4142748c32498b",
+        result: Expected::Invalid,
+        error: None,
+    },
+    EddsaVector {
+        tc_id: 11,
+        comment: "s = 0",
+        pk: "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a",
+        msg: "54657374",
+        sig: "0358333856a92f93b519f4dcef9876f94215d8dd719f42220291ee2acef6eab60000000000000000000000000000000000000000000000000000000000000000",
+        result: Expected::Invalid,
+        error: None,
+    },
+    EddsaVector {
+        tc_id: 12,
+        comment: "modified R",
+        pk: "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a",
+        msg: "54657374",
+        sig: "0258333856a92f93b519f4dcef9876f94215d8dd719f42220291ee2acef6eab66ac1112ab0da59995107b3422157bc8efbd15baf8f6d8bb12a4142748c32490b",
+        result: Expected::Invalid,
+        error: None,
+    },
+    EddsaVector {
+        tc_id: 13,
+        comment: "R with the sign bit flipped",
+        pk: "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a",
+        msg: "54657374",
+        sig: "0358333856a92f93b519f4dcef9876f94215d8dd719f42220291ee2acef6ea366ac1112ab0da59995107b3422157bc8efbd15baf8f6d8bb12a4142748c32490b",
+        result: Expected::Invalid,
+        error: None,
+    },
+    EddsaVector {
+        tc_id: 14,
+        comment: "R = identity, s = 0",
+        pk: "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a",
+        msg: "54657374",
+        sig: "01000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000",
+        result: Expected::Invalid,
+        error: None,
+    },
+    EddsaVector {
+        tc_id: 15,
+        comment: "R not on the curve",
+        pk: "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a",
+        msg: "54657374",
+        sig: "02000000000000000000000000000000000000000000000000000000000000006ac1112ab0da59995107b3422157bc8efbd15baf8f6d8bb12a4142748c32490b",
+        result: Expected::Invalid,
+        error: None,
+    },
+    EddsaVector {
+        tc_id: 16,
+        comment: "public key = identity",
+        pk: "0100000000000000000000000000000000000000000000000000000000000000",
+        msg: "54657374",
+        sig: "01000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000",
+        result: Expected::Acceptable,
+        error: Some(Error::WeakPublicKey),
+    },
+    EddsaVector {
+        tc_id: 17,
+        comment: "non-canonical encoding of the identity as a public key",
+        pk: "eeffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7f",
+        msg: "54657374",
+        sig: "01000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000",
+        result: Expected::Acceptable,
+        error: Some(Error::WeakPublicKey),
+    },
+    EddsaVector {
+        tc_id: 18,
+        comment: "public key not on the curve",
+        pk: "0200000000000000000000000000000000000000000000000000000000000000",
+        msg: "54657374",
+        sig: "0358333856a92f93b519f4dcef9876f94215d8dd719f42220291ee2acef6eab66ac1112ab0da59995107b3422157bc8efbd15baf8f6d8bb12a4142748c32490b",
+        result: Expected::Invalid,
+        error: None,
+    },
+    EddsaVector {
+        tc_id: 19,
+        comment: "truncated signature",
+        pk: "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a",
+        msg: "54657374",
+        sig: "0358333856a92f93b519f4dcef9876f94215d8dd719f42220291ee2acef6eab66ac1112ab0da59995107b3422157bc8efbd15baf8f6d8bb12a4142748c3249",
+        result: Expected::Invalid,
+        error: None,
+    },
+    EddsaVector {
+        tc_id: 20,
+        comment: "signature with a trailing byte",
+        pk: "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a",
+        msg: "54657374",
+        sig: "0358333856a92f93b519f4dcef9876f94215d8dd719f42220291ee2acef6eab66ac1112ab0da59995107b3422157bc8efbd15baf8f6d8bb12a4142748c32490b00",
+        result: Expected::Invalid,
+        error: None,
+    },
+    EddsaVector {
+        tc_id: 21,
+        comment: "signature for another key",
+        pk: "3b6a27bcceb6a42d62a3a8d02a6f0d73653215771de243a63ac048a18b59da29",
+        msg: "54657374",
+        sig: "0358333856a92f93b519f4dcef9876f94215d8dd719f42220291ee2acef6eab66ac1112ab0da59995107b3422157bc8efbd15baf8f6d8bb12a4142748c32490b",
+        result: Expected::Invalid,
+        error: None,
+    },
+];
+
+#[cfg(feature = "x25519")]
+static XDH_VECTORS: [XdhVector; 10] = [
+    XdhVector {
+        tc_id: 1,
+        comment: "RFC 7748 alice",
+        public: "de9edb7d7b7dc1b4d35b61c2ece435373f8343c85b78674dadfc7e146f882b4f",
+        private: "77076d0a7318a57d3c16c17251b26645df4c2f87ebc0992ab177fba51db92c2a",
+        shared: "4a5d9d5ba4ce2de1728e3bf480350f25e07e21c947d19e3376f09b3c1e161742",
+        result: Expected::Valid,
+    },
+    XdhVector {
+        tc_id: 2,
+        comment: "RFC 7748 bob",
+        public: "8520f0098930a754748b7ddcb43ef75a0dbf3a0d26381af4eba4a98eaa9b4e6a",
+        private: "5dab087e624a8a4b79e17f8b83800ee66f3bb1292618b6fd1c2f8b27ff88e0eb",
+        shared: "4a5d9d5ba4ce2de1728e3bf480350f25e07e21c947d19e3376f09b3c1e161742",
+        result: Expected::Valid,
+    },
+    XdhVector {
+        tc_id: 3,
+        comment: "valid",
+        public: "f3c76ec456fe7d95e5d36a4ea4e2134e659b78fc2cf2fb597498d6f8cbfb3203",
+        private: "7f16352acffa82667f5e80f6f9ab2d84356f287d912501ff25bc9e06231eb0fe",
+        shared: "bd3588dee8b6e991bb827b4be824f163886c424297a4019b0ffa007b9053960e",
+        result: Expected::Valid,
+    },
+    XdhVector {
+        tc_id: 4,
+        comment: "valid",
+        public: "a115b9f198550f6294d96a7cff329536c7b102c33d069c83b3f33a4916b99673",
+        private: "9af1d0a8d01006c2d889bf088bf9b037e082f796f3d83b78d805ac689643d178",
+        shared: "5b59d212779df0f333b34606093d4c03f4b7b0d988c751d337057b68b60b025b",
+        result: Expected::Valid,
+    },
+    XdhVector {
+        tc_id: 5,
+        comment: "valid",
+        public: "06554a99633ee9003275e09db970e2d996f9f7ec7969157a2671023a97e86c5d",
+        private: "a8ed08a492e3966dc01f4c828e67704c0a59c9812b7ae1bc2a87707dd1f266b4",
+        shared: "af70c3a57eb63f9d452a7638e8006743626393b8adf70eef1e363746112b9b21",
+        result: Expected::Valid,
+    },
+    XdhVector {
+        tc_id: 6,
+        comment: "public key with the most significant bit set",
+        public: "de9edb7d7b7dc1b4d35b61c2ece435373f8343c85b78674dadfc7e146f882bcf",
+        private: "77076d0a7318a57d3c16c17251b26645df4c2f87ebc0992ab177fba51db92c2a",
+        shared: "4a5d9d5ba4ce2de1728e3bf480350f25e07e21c947d19e3376f09b3c1e161742",
+        result: Expected::Acceptable,
+    },
+    XdhVector {
+        tc_id: 7,
+        comment: "public key of small order (u = 0)",
+        public: "0000000000000000000000000000000000000000000000000000000000000000",
+        private: "77076d0a7318a57d3c16c17251b26645df4c2f87ebc0992ab177fba51db92c2a",
+        shared: "0000000000000000000000000000000000000000000000000000000000000000",
+        result: Expected::Acceptable,
+    },
+    XdhVector {
+        tc_id: 8,
+        comment: "public key of small order (u = 1)",
+        public: "0100000000000000000000000000000000000000000000000000000000000000",
+        private: "77076d0a7318a57d3c16c17251b26645df4c2f87ebc0992ab177fba51db92c2a",
+        shared: "0000000000000000000000000000000000000000000000000000000000000000",
+        result: Expected::Acceptable,
+    },
+    XdhVector {
+        tc_id: 9,
+        comment: "public key of order 8",
+        public: "e0eb7a7c3b41b8ae1656e3faf19fc46ada098deb9c32b1fd866205165f49b800",
+        private: "77076d0a7318a57d3c16c17251b26645df4c2f87ebc0992ab177fba51db92c2a",
+        shared: "0000000000000000000000000000000000000000000000000000000000000000",
+        result: Expected::Acceptable,
+    },
+    XdhVector {
+        tc_id: 10,
+        comment: "non-canonical public key (u = p + 9)",
+        public: "f6ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7f",
+        private: "77076d0a7318a57d3c16c17251b26645df4c2f87ebc0992ab177fba51db92c2a",
+        shared: "8520f0098930a754748b7ddcb43ef75a0dbf3a0d26381af4eba4a98eaa9b4e6a",
+        result: Expected::Acceptable,
+    },
+];
+
+#[test]
+fn test_conformance() {
+    let report = run();
+    assert_eq!(report.first_failure, None);
+    assert!(report.is_ok());
+    assert!(report.passed > 0);
+
+    #[cfg(not(feature = "disable-signatures"))]
+    {
+        let v = &EDDSA_VECTORS[15];
+        assert_eq!(v.result, Expected::Acceptable);
+        assert!(v.check());
+        let v = EddsaVector {
+            error: Some(Error::PublicKeyNotOnCurve),
+            ..*v
+        };
+        assert!(!v.check());
+    }
+}
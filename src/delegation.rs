@@ -0,0 +1,198 @@
+use std::time::SystemTime;
+
+use super::common::{u64_at, unix_time};
+use super::{Error, KeyPair, PublicKey, Signature};
+
+const CONTEXT: &[u8] = b"ed25519-compact delegation v1";
+const HEADER_BYTES: usize = PublicKey::BYTES * 2 + 8 + 8 + 2;
+
+/// A statement, signed by an issuer key, delegating to a subject key.
+///
+/// Delegations allow long-lived identities to rotate keys: the old key signs
+/// a delegation to the new key, and verifiers that trust the old key can
+/// follow the chain with `Delegation::verify_chain()`.
+///
+/// The `context` binds a delegation to an application or purpose, so that
+/// delegations made for one purpose can't be used for another.
+///
+/// The encoding is `issuer || subject || valid_from (u64 BE) ||
+/// valid_until (u64 BE) || context length (u16 BE) || context || signature`.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Delegation {
+    /// The key that signed the delegation.
+    pub issuer: PublicKey,
+    /// The key being delegated to.
+    pub subject: PublicKey,
+    /// Start of the validity period, in seconds since the Unix epoch.
+    pub valid_from: u64,
+    /// End of the validity period, in seconds since the Unix epoch.
+    pub valid_until: u64,
+    /// Application-defined context.
+    pub context: Vec<u8>,
+    signature: Signature,
+}
+
+impl Delegation {
+    /// Creates a delegation from `issuer` to `subject`, valid from
+    /// `valid_from` until `valid_until`, for the given context.
+    ///
+    /// Returns `Error::InvalidContext` if the context is longer than 65535
+    /// bytes, and `Error::FaultDetected` if a fault is detected while
+    /// signing.
+    pub fn issue(
+        issuer: &KeyPair,
+        subject: &PublicKey,
+        valid_from: SystemTime,
+        valid_until: SystemTime,
+        context: &[u8],
+    ) -> Result<Self, Error> {
+        if context.len() > u16::MAX as usize {
+            return Err(Error::InvalidContext);
+        }
+        let mut delegation = Delegation {
+            issuer: issuer.pk,
+            subject: *subject,
+            valid_from: unix_time(valid_from),
+            valid_until: unix_time(valid_until),
+            context: context.to_vec(),
+            signature: Signature::new([0u8; Signature::BYTES]),
+        };
+        delegation.signature = issuer
+            .sk
+            .try_sign_vectored(&[CONTEXT, &delegation.signed_bytes()], None)?;
+        Ok(delegation)
+    }
+
+    fn signed_bytes(&self) -> Vec<u8> {
+        let mut bin = Vec::with_capacity(HEADER_BYTES + self.context.len() + Signature::BYTES);
+        bin.extend_from_slice(&self.issuer[..]);
+        bin.extend_from_slice(&self.subject[..]);
+        bin.extend_from_slice(&self.valid_from.to_be_bytes());
+        bin.extend_from_slice(&self.valid_until.to_be_bytes());
+        bin.extend_from_slice(&(self.context.len() as u16).to_be_bytes());
+        bin.extend_from_slice(&self.context);
+        bin
+    }
+
+    /// Returns the encoded delegation.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bin = self.signed_bytes();
+        bin.extend_from_slice(&self.signature[..]);
+        bin
+    }
+
+    /// Decodes a delegation. The signature is not verified.
+    pub fn from_bytes(bin: &[u8]) -> Result<Self, Error> {
+        if bin.len() < HEADER_BYTES + Signature::BYTES {
+            return Err(Error::ParseError);
+        }
+        let context_len = u16::from_be_bytes([bin[HEADER_BYTES - 2], bin[HEADER_BYTES - 1]]);
+        let context_end = HEADER_BYTES + context_len as usize;
+        if bin.len() != context_end + Signature::BYTES {
+            return Err(Error::ParseError);
+        }
+        Ok(Delegation {
+            issuer: PublicKey::from_slice(&bin[..PublicKey::BYTES])?,
+            subject: PublicKey::from_slice(&bin[PublicKey::BYTES..PublicKey::BYTES * 2])?,
+            valid_from: u64_at(bin, PublicKey::BYTES * 2),
+            valid_until: u64_at(bin, PublicKey::BYTES * 2 + 8),
+            context: bin[HEADER_BYTES..context_end].to_vec(),
+            signature: Signature::from_slice(&bin[context_end..])?,
+        })
+    }
+
+    /// Verifies the signature of the delegation, and checks that it is valid
+    /// at time `now` for the given context.
+    ///
+    /// Returns `Error::Expired` if `now` is not within the validity period,
+    /// and `Error::InvalidContext` if the context doesn't match.
+    pub fn verify(&self, now: SystemTime, context: &[u8]) -> Result<(), Error> {
+        let mut message = Vec::with_capacity(CONTEXT.len() + HEADER_BYTES + self.context.len());
+        message.extend_from_slice(CONTEXT);
+        message.extend_from_slice(&self.signed_bytes());
+        self.issuer.verify(message, &self.signature)?;
+        if self.context != context {
+            return Err(Error::InvalidContext);
+        }
+        let now = unix_time(now);
+        if now < self.valid_from || now >= self.valid_until {
+            return Err(Error::Expired);
+        }
+        Ok(())
+    }
+
+    /// Verifies a chain of delegations starting from a trusted `root` key,
+    /// and returns the key at the end of the chain.
+    ///
+    /// Each delegation must be issued by the subject of the previous one (or
+    /// by `root` for the first one), and be valid at time `now` for the
+    /// given context. An empty chain returns `root`.
+    pub fn verify_chain(
+        root: &PublicKey,
+        chain: &[Delegation],
+        now: SystemTime,
+        context: &[u8],
+    ) -> Result<PublicKey, Error> {
+        let mut current = *root;
+        for delegation in chain {
+            if delegation.issuer != current {
+                return Err(Error::SignatureMismatch);
+            }
+            delegation.verify(now, context)?;
+            current = delegation.subject;
+        }
+        Ok(current)
+    }
+}
+
+#[test]
+fn test_delegation() {
+    use super::Seed;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    let k1 = KeyPair::from_seed(Seed::new([1u8; Seed::BYTES]));
+    let k2 = KeyPair::from_seed(Seed::new([2u8; Seed::BYTES]));
+    let k3 = KeyPair::from_seed(Seed::new([3u8; Seed::BYTES]));
+    let t = |secs: u64| UNIX_EPOCH + Duration::from_secs(secs);
+    let context = b"example.com release signing";
+
+    let d12 = Delegation::issue(&k1, &k2.pk, t(1000), t(3000), context).unwrap();
+    let d23 = Delegation::issue(&k2, &k3.pk, t(2000), t(4000), context).unwrap();
+    let d23 = Delegation::from_bytes(&d23.to_bytes()).unwrap();
+    assert_eq!(d23.issuer, k2.pk);
+
+    let chain = [d12.clone(), d23.clone()];
+    assert_eq!(
+        Delegation::verify_chain(&k1.pk, &chain, t(2500), context).unwrap(),
+        k3.pk
+    );
+    assert_eq!(
+        Delegation::verify_chain(&k1.pk, &[], t(2500), context).unwrap(),
+        k1.pk
+    );
+    assert_eq!(
+        Delegation::verify_chain(&k1.pk, &chain, t(3500), context),
+        Err(Error::Expired)
+    );
+    assert_eq!(
+        Delegation::verify_chain(&k1.pk, &chain, t(2500), b"other"),
+        Err(Error::InvalidContext)
+    );
+    assert_eq!(
+        Delegation::verify_chain(&k2.pk, &chain, t(2500), context),
+        Err(Error::SignatureMismatch)
+    );
+    assert_eq!(
+        Delegation::verify_chain(&k1.pk, &[d23], t(2500), context),
+        Err(Error::SignatureMismatch)
+    );
+
+    let mut forged = d12.to_bytes();
+    forged[PublicKey::BYTES * 2 + 15] ^= 1;
+    let forged = Delegation::from_bytes(&forged).unwrap();
+    assert_eq!(
+        forged.verify(t(2500), context),
+        Err(Error::SignatureMismatch)
+    );
+    assert!(Delegation::from_bytes(&d12.to_bytes()[1..]).is_err());
+}
@@ -0,0 +1,192 @@
+//! Minimal DER encoding and decoding, for X.509 structures.
+
+pub(crate) const TAG_INTEGER: u8 = 0x02;
+pub(crate) const TAG_BIT_STRING: u8 = 0x03;
+pub(crate) const TAG_OCTET_STRING: u8 = 0x04;
+pub(crate) const TAG_OID: u8 = 0x06;
+pub(crate) const TAG_UTF8_STRING: u8 = 0x0c;
+pub(crate) const TAG_UTC_TIME: u8 = 0x17;
+pub(crate) const TAG_GENERALIZED_TIME: u8 = 0x18;
+pub(crate) const TAG_SEQUENCE: u8 = 0x30;
+pub(crate) const TAG_SET: u8 = 0x31;
+pub(crate) const TAG_BOOLEAN: u8 = 0x01;
+
+/// `id-Ed25519` (1.3.101.112)
+pub(crate) const OID_ED25519: &[u8] = &[43, 101, 112];
+
+/// Encodes a value with the tag `tag`.
+pub(crate) fn tlv(tag: u8, content: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(content.len() + 6);
+    out.push(tag);
+    let len = content.len();
+    if len < 0x80 {
+        out.push(len as u8);
+    } else {
+        let len_bytes = len.to_be_bytes();
+        let skip = len_bytes.iter().take_while(|&&x| x == 0).count();
+        out.push(0x80 | (len_bytes.len() - skip) as u8);
+        out.extend_from_slice(&len_bytes[skip..]);
+    }
+    out.extend_from_slice(content);
+    out
+}
+
+/// Encodes the concatenation of `parts` with the tag `tag`.
+pub(crate) fn constructed(tag: u8, parts: &[&[u8]]) -> Vec<u8> {
+    tlv(tag, &parts.concat())
+}
+
+pub(crate) fn sequence(parts: &[&[u8]]) -> Vec<u8> {
+    constructed(TAG_SEQUENCE, parts)
+}
+
+/// Encodes an explicitly tagged, context-specific value.
+pub(crate) fn explicit(n: u8, content: &[u8]) -> Vec<u8> {
+    tlv(0xa0 | n, content)
+}
+
+/// Encodes a non-negative integer, given as big-endian bytes.
+pub(crate) fn unsigned_integer(be: &[u8]) -> Vec<u8> {
+    let skip = be.iter().take_while(|&&x| x == 0).count();
+    let be = &be[skip.min(be.len().saturating_sub(1))..];
+    if be.is_empty() || be[0] & 0x80 != 0 {
+        tlv(TAG_INTEGER, &[&[0u8][..], be].concat())
+    } else {
+        tlv(TAG_INTEGER, be)
+    }
+}
+
+pub(crate) fn boolean(x: bool) -> Vec<u8> {
+    tlv(TAG_BOOLEAN, &[if x { 0xff } else { 0x00 }])
+}
+
+pub(crate) fn bit_string(bytes: &[u8]) -> Vec<u8> {
+    tlv(TAG_BIT_STRING, &[&[0u8][..], bytes].concat())
+}
+
+/// Encodes an Ed25519 `AlgorithmIdentifier`.
+pub(crate) fn ed25519_algorithm() -> Vec<u8> {
+    sequence(&[&tlv(TAG_OID, OID_ED25519)])
+}
+
+/// Encodes a `Name` made of the attributes `(oid, value)`, skipping empty
+/// values.
+pub(crate) fn name(attributes: &[(&[u8], &str)]) -> Vec<u8> {
+    let rdns: Vec<Vec<u8>> = attributes
+        .iter()
+        .filter(|(_, value)| !value.is_empty())
+        .map(|(oid, value)| {
+            constructed(
+                TAG_SET,
+                &[&sequence(&[
+                    &tlv(TAG_OID, oid),
+                    &tlv(TAG_UTF8_STRING, value.as_bytes()),
+                ])],
+            )
+        })
+        .collect();
+    let rdns: Vec<&[u8]> = rdns.iter().map(Vec::as_slice).collect();
+    sequence(&rdns)
+}
+
+/// Encodes a time, given in seconds since the Unix epoch, as a `UTCTime`
+/// until 2049, and as a `GeneralizedTime` afterwards, as required by
+/// RFC 5280.
+pub(crate) fn time(unix_secs: u64) -> Vec<u8> {
+    let days = unix_secs / 86400;
+    let secs = unix_secs % 86400;
+    let (year, month, day) = civil_from_days(days);
+    let (hh, mm, ss) = (secs / 3600, secs / 60 % 60, secs % 60);
+    if year < 2050 {
+        let s = format!(
+            "{:02}{:02}{:02}{:02}{:02}{:02}Z",
+            year % 100,
+            month,
+            day,
+            hh,
+            mm,
+            ss
+        );
+        tlv(TAG_UTC_TIME, s.as_bytes())
+    } else {
+        let s = format!(
+            "{:04}{:02}{:02}{:02}{:02}{:02}Z",
+            year, month, day, hh, mm, ss
+        );
+        tlv(TAG_GENERALIZED_TIME, s.as_bytes())
+    }
+}
+
+/// Converts a number of days since the Unix epoch into a (year, month,
+/// day) date.
+fn civil_from_days(days: u64) -> (u64, u64, u64) {
+    let z = days + 719468;
+    let era = z / 146097;
+    let doe = z % 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+/// A reader for DER-encoded data.
+pub(crate) struct Reader<'t> {
+    buf: &'t [u8],
+}
+
+impl<'t> Reader<'t> {
+    pub fn new(buf: &'t [u8]) -> Self {
+        Reader { buf }
+    }
+
+    /// Returns the tag of the next value, if there is one.
+    pub fn peek_tag(&self) -> Option<u8> {
+        self.buf.first().copied()
+    }
+
+    /// Reads the next value, and returns its tag, its content, and its full
+    /// encoding. Only definite, minimally-encoded lengths are accepted.
+    pub fn read_tlv(&mut self) -> Option<(u8, &'t [u8], &'t [u8])> {
+        let tag = *self.buf.first()?;
+        let first = *self.buf.get(1)? as usize;
+        let (len, header_len) = if first < 0x80 {
+            (first, 2)
+        } else {
+            let len_len = first & 0x7f;
+            if len_len == 0 || len_len > 4 {
+                return None;
+            }
+            let len_bytes = self.buf.get(2..2 + len_len)?;
+            if len_bytes[0] == 0 {
+                return None;
+            }
+            let len = len_bytes
+                .iter()
+                .fold(0usize, |acc, &x| acc << 8 | x as usize);
+            if len < 0x80 {
+                return None;
+            }
+            (len, 2 + len_len)
+        };
+        let end = header_len.checked_add(len)?;
+        let tlv = self.buf.get(..end)?;
+        self.buf = &self.buf[end..];
+        Some((tag, &tlv[header_len..], tlv))
+    }
+
+    /// Reads the next value, which must have the tag `tag`, and returns its
+    /// content.
+    pub fn read(&mut self, tag: u8) -> Option<&'t [u8]> {
+        match self.read_tlv()? {
+            (tag_, content, _) if tag_ == tag => Some(content),
+            _ => None,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.buf.is_empty()
+    }
+}
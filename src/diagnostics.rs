@@ -0,0 +1,129 @@
+use super::edwards25519::sc_reject_noncanonical;
+use super::edwards25519::{ge_add, ge_scalarmult, ge_scalarmult_base, sc_reduce, GeP3};
+use super::error::Error;
+use super::field25519::Fe;
+use super::sha512;
+use super::{PublicKey, Signature};
+
+/// Details about the verification of a signature, returned by
+/// `PublicKey::verify_explain()`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct VerificationReport {
+    /// The result of `PublicKey::verify()` with the same parameters.
+    pub result: Result<(), Error>,
+    /// The public key encodes a coordinate that is reduced modulo p.
+    pub public_key_canonical: bool,
+    /// The public key encodes a point on the curve.
+    pub public_key_on_curve: bool,
+    /// The public key is a point of small order.
+    pub public_key_small_order: bool,
+    /// `R` encodes a coordinate that is reduced modulo p.
+    pub r_canonical: bool,
+    /// `R` encodes a point on the curve.
+    pub r_on_curve: bool,
+    /// `R` is a point of small order.
+    pub r_small_order: bool,
+    /// `s` is reduced modulo the group order.
+    pub s_canonical: bool,
+    /// Whether `[s]B = R + [k]A` holds, comparing encodings, as this crate
+    /// and RFC 8032 do. `None` if the points or `s` are invalid.
+    pub cofactorless_equation: Option<bool>,
+    /// Whether `[8][s]B = [8]R + [8][k]A` holds, as some other
+    /// implementations check. `None` if the points or `s` are invalid.
+    pub cofactored_equation: Option<bool>,
+}
+
+/// Returns `true` if the y coordinate encoded in `s` is below p.
+fn is_canonical(s: &[u8; 32]) -> bool {
+    let mut y = *s;
+    y[31] &= 0x7f;
+    Fe::reject_noncanonical(&y).is_ok()
+}
+
+impl PublicKey {
+    /// Verifies that the signature `signature` is valid for the message
+    /// `message`, and explains the outcome.
+    ///
+    /// This is slow, and only meant to debug interoperability issues with
+    /// other implementations.
+    pub fn verify_explain(
+        &self,
+        message: impl AsRef<[u8]>,
+        signature: &Signature,
+    ) -> VerificationReport {
+        let message = message.as_ref();
+        let mut r = [0u8; 32];
+        r.copy_from_slice(&signature[0..32]);
+        let s = &signature[32..64];
+
+        let neg_a = GeP3::from_bytes_negate_vartime(self);
+        let neg_r = GeP3::from_bytes_negate_vartime(&r);
+        let s_canonical = sc_reject_noncanonical(s).is_ok();
+
+        let mut cofactorless_equation = None;
+        let mut cofactored_equation = None;
+        if let (Some(neg_a), Some(neg_r), true) = (neg_a, neg_r, s_canonical) {
+            let mut hasher = sha512::Hash::new();
+            hasher.update(r);
+            hasher.update(&self[..]);
+            hasher.update(message);
+            let mut k = hasher.finalize();
+            sc_reduce(&mut k);
+            let sb_minus_ka = ge_add(&ge_scalarmult_base(s), &ge_scalarmult(&k[0..32], &neg_a));
+            cofactorless_equation = Some(sb_minus_ka.to_bytes() == r);
+            cofactored_equation = Some(ge_add(&sb_minus_ka, &neg_r).has_small_order());
+        }
+
+        VerificationReport {
+            result: self.verify(message, signature),
+            public_key_canonical: is_canonical(self),
+            public_key_on_curve: neg_a.is_some(),
+            public_key_small_order: neg_a.is_some_and(|a| a.has_small_order()),
+            r_canonical: is_canonical(&r),
+            r_on_curve: neg_r.is_some(),
+            r_small_order: neg_r.is_some_and(|r| r.has_small_order()),
+            s_canonical,
+            cofactorless_equation,
+            cofactored_equation,
+        }
+    }
+}
+
+#[test]
+fn test_verify_explain() {
+    use super::KeyPair;
+
+    let kp = KeyPair::from_seed([42u8; 32].into());
+    let message = b"Hello, World!";
+    let signature = kp.sk.sign(message, None);
+    let report = kp.pk.verify_explain(message, &signature);
+    assert_eq!(report.result, Ok(()));
+    assert!(report.public_key_canonical && report.public_key_on_curve);
+    assert!(!report.public_key_small_order && !report.r_small_order);
+    assert_eq!(report.cofactorless_equation, Some(true));
+    assert_eq!(report.cofactored_equation, Some(true));
+
+    let report = kp.pk.verify_explain(b"Hello, world!", &signature);
+    assert_eq!(report.result, Err(Error::SignatureMismatch));
+    assert_eq!(report.cofactorless_equation, Some(false));
+    assert_eq!(report.cofactored_equation, Some(false));
+
+    let mut non_canonical = *signature;
+    non_canonical[63] |= 0xf0;
+    let report = kp
+        .pk
+        .verify_explain(message, &Signature::new(non_canonical));
+    assert_eq!(report.result, Err(Error::NonCanonicalScalar));
+    assert!(!report.s_canonical);
+    assert_eq!(report.cofactorless_equation, None);
+
+    // Identity public key, and a signature with R = identity and s = 0
+    let mut identity = [0u8; 32];
+    identity[0] = 1;
+    let mut sig = [0u8; 64];
+    sig[0] = 1;
+    let report = PublicKey::new(identity).verify_explain(message, &Signature::new(sig));
+    assert_eq!(report.result, Err(Error::WeakPublicKey));
+    assert!(report.public_key_small_order && report.r_small_order);
+    assert_eq!(report.cofactorless_equation, Some(true));
+}
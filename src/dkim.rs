@@ -0,0 +1,121 @@
+use ct_codecs::{Base64, Decoder, Encoder};
+
+use super::sha256;
+use super::{Error, KeyPair, PublicKey, Signature};
+
+impl KeyPair {
+    /// Computes the `b=` value of a DKIM signature with the `ed25519-sha256`
+    /// algorithm (RFC 8463).
+    ///
+    /// `header_hash` is the SHA-256 hash of the canonicalized header fields,
+    /// including the DKIM-Signature header field with an empty `b=` value.
+    pub fn sign_dkim(&self, header_hash: &[u8; 32]) -> String {
+        let signature = self.sk.sign(header_hash, None);
+        Base64::encode_to_string(&signature[..]).unwrap()
+    }
+}
+
+impl PublicKey {
+    /// Verifies the `b=` value of a DKIM signature with the `ed25519-sha256`
+    /// algorithm (RFC 8463).
+    ///
+    /// Folding whitespace in `b` is ignored.
+    pub fn verify_dkim(&self, header_hash: &[u8; 32], b: &str) -> Result<(), Error> {
+        let mut signature = [0u8; Signature::BYTES];
+        let len = Base64::decode(&mut signature, b, Some(b" \t\r\n"))
+            .map_err(|_| Error::InvalidBase64)?
+            .len();
+        let signature = Signature::from_slice(&signature[..len])?;
+        self.verify(header_hash, &signature)
+    }
+
+    /// Returns the content of a DKIM key record, to be published in a DNS
+    /// TXT record: `v=DKIM1; k=ed25519; p=<key>`.
+    pub fn to_dkim_record(&self) -> String {
+        format!(
+            "v=DKIM1; k=ed25519; p={}",
+            Base64::encode_to_string(&self[..]).unwrap()
+        )
+    }
+
+    /// Imports a public key from the content of a DKIM key record.
+    ///
+    /// The record must have a `k=ed25519` tag. Revoked keys (empty `p=`
+    /// tag) are rejected with `Error::InvalidPublicKey`.
+    pub fn from_dkim_record(record: &str) -> Result<Self, Error> {
+        let mut key_type = None;
+        let mut key = None;
+        for tag in record.split(';') {
+            let tag = tag.trim();
+            if tag.is_empty() {
+                continue;
+            }
+            let (name, value) = tag.split_once('=').ok_or(Error::ParseError)?;
+            match name.trim() {
+                "k" => key_type = Some(value.trim()),
+                "p" => key = Some(value),
+                _ => {}
+            }
+        }
+        if key_type != Some("ed25519") {
+            return Err(Error::InvalidPublicKey);
+        }
+        let key = key.ok_or(Error::ParseError)?;
+        let mut pk = [0u8; PublicKey::BYTES];
+        let len = Base64::decode(&mut pk, key, Some(b" \t\r\n"))
+            .map_err(|_| Error::InvalidBase64)?
+            .len();
+        PublicKey::from_slice(&pk[..len])
+    }
+}
+
+/// Computes the hash of canonicalized DKIM header fields, to be signed with
+/// `KeyPair::sign_dkim()` or verified with `PublicKey::verify_dkim()`.
+pub fn dkim_header_hash(canonicalized_headers: &[u8]) -> [u8; 32] {
+    sha256::Hash::hash(canonicalized_headers)
+}
+
+#[test]
+fn test_dkim() {
+    use super::Seed;
+
+    // RFC 8463, Appendix A
+    let mut seed = [0u8; Seed::BYTES];
+    Base64::decode(
+        &mut seed,
+        "nWGxne/9WmC6hEr0kuwsxERJxWl7MmkZcDusAxyuf2A=",
+        None,
+    )
+    .unwrap();
+    let kp = KeyPair::from_seed(Seed::new(seed));
+    let record = kp.pk.to_dkim_record();
+    assert_eq!(
+        record,
+        "v=DKIM1; k=ed25519; p=11qYAYKxCrfVS/7TyWQHOg7hcvPapiMlrwIaaPcHURo="
+    );
+    assert_eq!(PublicKey::from_dkim_record(&record).unwrap(), kp.pk);
+    assert!(
+        PublicKey::from_dkim_record("v=DKIM1; p=11qYAYKxCrfVS/7TyWQHOg7hcvPapiMlrwIaaPcHURo=")
+            .is_err()
+    );
+    assert!(PublicKey::from_dkim_record("v=DKIM1; k=ed25519; p=").is_err());
+
+    let headers = "from:Joe SixPack <joe@football.example.com>\r\n\
+        to:Suzie Q <suzie@shopping.example.net>\r\n\
+        subject:Is dinner ready?\r\n\
+        date:Fri, 11 Jul 2003 21:00:37 -0700 (PDT)\r\n\
+        message-id:<20030712040037.46341.5F8J@football.example.com>\r\n\
+        dkim-signature:v=1; a=ed25519-sha256; c=relaxed/relaxed; \
+        d=football.example.com; i=@football.example.com; q=dns/txt; \
+        s=brisbane; t=1528637909; h=from : to : subject : date : message-id : \
+        from : subject : date; bh=2jUSOH9NhtVGCQWNr9BrIAPreKQjO6Sn7XIkfJVOzv8=; b=";
+    let header_hash = dkim_header_hash(headers.as_bytes());
+    let b = kp.sign_dkim(&header_hash);
+    assert_eq!(
+        b,
+        "/gCrinpcQOoIfuHNQIbq4pgh9kyIK3AQUdt9OdqQehSwhEIug4D11BusFa3bT3FY5OsU7ZbnKELq+eXdp1Q1Dw=="
+    );
+    let folded = format!("{}\r\n\t{}", &b[..40], &b[40..]);
+    assert!(kp.pk.verify_dkim(&header_hash, &folded).is_ok());
+    assert!(kp.pk.verify_dkim(&[0u8; 32], &b).is_err());
+}
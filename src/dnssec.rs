@@ -0,0 +1,356 @@
+use super::{Error, KeyPair, PublicKey, Signature};
+
+/// DNSSEC algorithm number for Ed25519 (RFC 8080).
+pub const DNSSEC_ALGORITHM_ED25519: u8 = 15;
+
+/// DNSKEY flags for a zone key.
+pub const DNSKEY_FLAG_ZONE: u16 = 0x0100;
+
+/// DNSKEY flags for a secure entry point (key signing key).
+pub const DNSKEY_FLAG_SEP: u16 = 0x0001;
+
+/// An Ed25519 DNSKEY record.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Dnskey {
+    /// Flags, usually `DNSKEY_FLAG_ZONE`, or `DNSKEY_FLAG_ZONE | DNSKEY_FLAG_SEP`
+    /// for a key signing key.
+    pub flags: u16,
+    /// The public key.
+    pub public_key: PublicKey,
+}
+
+impl Dnskey {
+    /// Length of the RDATA of an Ed25519 DNSKEY record.
+    pub const RDATA_BYTES: usize = 4 + PublicKey::BYTES;
+
+    /// Returns the RDATA of the record.
+    pub fn to_rdata(&self) -> [u8; Dnskey::RDATA_BYTES] {
+        let mut rdata = [0u8; Dnskey::RDATA_BYTES];
+        rdata[..2].copy_from_slice(&self.flags.to_be_bytes());
+        rdata[2] = 3;
+        rdata[3] = DNSSEC_ALGORITHM_ED25519;
+        rdata[4..].copy_from_slice(&self.public_key[..]);
+        rdata
+    }
+
+    /// Decodes the RDATA of a DNSKEY record.
+    pub fn from_rdata(rdata: &[u8]) -> Result<Self, Error> {
+        if rdata.len() != Dnskey::RDATA_BYTES || rdata[2] != 3 {
+            return Err(Error::ParseError);
+        }
+        if rdata[3] != DNSSEC_ALGORITHM_ED25519 {
+            return Err(Error::InvalidPublicKey);
+        }
+        Ok(Dnskey {
+            flags: u16::from_be_bytes([rdata[0], rdata[1]]),
+            public_key: PublicKey::from_slice(&rdata[4..])?,
+        })
+    }
+
+    /// Returns the key tag of the record (RFC 4034, Appendix B).
+    pub fn key_tag(&self) -> u16 {
+        let mut ac = 0u32;
+        for (i, &x) in self.to_rdata().iter().enumerate() {
+            ac += if i & 1 == 0 {
+                (x as u32) << 8
+            } else {
+                x as u32
+            };
+        }
+        ac += ac >> 16;
+        ac as u16
+    }
+}
+
+/// An RRSIG record made with an Ed25519 key.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Rrsig {
+    /// Type of the signed RRset.
+    pub type_covered: u16,
+    /// Number of labels of the owner name, not counting a wildcard label.
+    pub labels: u8,
+    /// TTL of the signed RRset.
+    pub original_ttl: u32,
+    /// End of the validity period, in seconds since the Unix epoch, modulo 2^32.
+    pub expiration: u32,
+    /// Start of the validity period, in seconds since the Unix epoch, modulo 2^32.
+    pub inception: u32,
+    /// Key tag of the DNSKEY that made the signature.
+    pub key_tag: u16,
+    /// Name of the zone the DNSKEY belongs to.
+    pub signer_name: String,
+    /// The signature.
+    pub signature: Signature,
+}
+
+impl Rrsig {
+    /// Signs an RRset.
+    ///
+    /// `owner` is the owner name of the RRset, and `rdatas` the RDATA of its
+    /// records. Domain names embedded in RDATA must already be in canonical
+    /// (lowercase, uncompressed) form.
+    #[allow(clippy::too_many_arguments)]
+    pub fn sign(
+        kp: &KeyPair,
+        dnskey_flags: u16,
+        signer_name: &str,
+        owner: &str,
+        type_covered: u16,
+        class: u16,
+        ttl: u32,
+        rdatas: &[&[u8]],
+        inception: u32,
+        expiration: u32,
+    ) -> Result<Self, Error> {
+        let dnskey = Dnskey {
+            flags: dnskey_flags,
+            public_key: kp.pk,
+        };
+        let mut rrsig = Rrsig {
+            type_covered,
+            labels: label_count(owner)?,
+            original_ttl: ttl,
+            expiration,
+            inception,
+            key_tag: dnskey.key_tag(),
+            signer_name: signer_name.to_string(),
+            signature: Signature::new([0u8; Signature::BYTES]),
+        };
+        let data = rrsig.signed_data(owner, class, rdatas)?;
+        rrsig.signature = kp.sk.sign(data, None);
+        Ok(rrsig)
+    }
+
+    /// Verifies the signature of an RRset.
+    ///
+    /// The validity period is not checked: use `is_valid_at()` for that.
+    pub fn verify(
+        &self,
+        dnskey: &Dnskey,
+        owner: &str,
+        class: u16,
+        rdatas: &[&[u8]],
+    ) -> Result<(), Error> {
+        if dnskey.key_tag() != self.key_tag || dnskey.flags & DNSKEY_FLAG_ZONE == 0 {
+            return Err(Error::SignatureMismatch);
+        }
+        let data = self.signed_data(owner, class, rdatas)?;
+        dnskey.public_key.verify(data, &self.signature)
+    }
+
+    /// Returns `true` if `now` (in seconds since the Unix epoch) is within the
+    /// validity period, using serial number arithmetic (RFC 1982).
+    pub fn is_valid_at(&self, now: u64) -> bool {
+        let now = now as u32;
+        (now.wrapping_sub(self.inception) as i32) >= 0
+            && (self.expiration.wrapping_sub(now) as i32) >= 0
+    }
+
+    /// Returns the RDATA of the record.
+    pub fn to_rdata(&self) -> Result<Vec<u8>, Error> {
+        let mut rdata = self.rdata_without_signature()?;
+        rdata.extend_from_slice(&self.signature[..]);
+        Ok(rdata)
+    }
+
+    /// Decodes the RDATA of an RRSIG record.
+    pub fn from_rdata(rdata: &[u8]) -> Result<Self, Error> {
+        if rdata.len() < 18 + 1 + Signature::BYTES {
+            return Err(Error::ParseError);
+        }
+        if rdata[2] != DNSSEC_ALGORITHM_ED25519 {
+            return Err(Error::InvalidSignature);
+        }
+        let u32_at =
+            |i: usize| u32::from_be_bytes([rdata[i], rdata[i + 1], rdata[i + 2], rdata[i + 3]]);
+        let (signer_name, name_len) = decode_name(&rdata[18..])?;
+        let signature = &rdata[18 + name_len..];
+        Ok(Rrsig {
+            type_covered: u16::from_be_bytes([rdata[0], rdata[1]]),
+            labels: rdata[3],
+            original_ttl: u32_at(4),
+            expiration: u32_at(8),
+            inception: u32_at(12),
+            key_tag: u16::from_be_bytes([rdata[16], rdata[17]]),
+            signer_name,
+            signature: Signature::from_slice(signature)?,
+        })
+    }
+
+    fn rdata_without_signature(&self) -> Result<Vec<u8>, Error> {
+        let mut rdata = Vec::with_capacity(18 + 256 + Signature::BYTES);
+        rdata.extend_from_slice(&self.type_covered.to_be_bytes());
+        rdata.push(DNSSEC_ALGORITHM_ED25519);
+        rdata.push(self.labels);
+        rdata.extend_from_slice(&self.original_ttl.to_be_bytes());
+        rdata.extend_from_slice(&self.expiration.to_be_bytes());
+        rdata.extend_from_slice(&self.inception.to_be_bytes());
+        rdata.extend_from_slice(&self.key_tag.to_be_bytes());
+        encode_name(&mut rdata, &self.signer_name)?;
+        Ok(rdata)
+    }
+
+    /// Returns the data covered by the signature (RFC 4034, Section 3.1.8.1).
+    fn signed_data(&self, owner: &str, class: u16, rdatas: &[&[u8]]) -> Result<Vec<u8>, Error> {
+        let owner_labels = label_count(owner)?;
+        if self.labels > owner_labels {
+            return Err(Error::SignatureMismatch);
+        }
+        // Expand wildcards: keep only the rightmost `labels` labels.
+        let owner = owner.trim_end_matches('.');
+        let owner = if self.labels < owner_labels {
+            let skip = (owner_labels - self.labels) as usize;
+            let suffix = owner.splitn(skip + 1, '.').last().unwrap_or("");
+            format!("*.{}", suffix)
+        } else {
+            owner.to_string()
+        };
+        let mut rr_prefix = Vec::new();
+        encode_name(&mut rr_prefix, &owner)?;
+        rr_prefix.extend_from_slice(&self.type_covered.to_be_bytes());
+        rr_prefix.extend_from_slice(&class.to_be_bytes());
+        rr_prefix.extend_from_slice(&self.original_ttl.to_be_bytes());
+
+        let mut rdatas = rdatas.to_vec();
+        rdatas.sort_unstable();
+        rdatas.dedup();
+        let mut data = self.rdata_without_signature()?;
+        for rdata in rdatas {
+            if rdata.len() > 0xffff {
+                return Err(Error::ParseError);
+            }
+            data.extend_from_slice(&rr_prefix);
+            data.extend_from_slice(&(rdata.len() as u16).to_be_bytes());
+            data.extend_from_slice(rdata);
+        }
+        Ok(data)
+    }
+}
+
+/// Returns the number of labels of a name, not counting the root and a
+/// leading wildcard label.
+fn label_count(name: &str) -> Result<u8, Error> {
+    let name = name.trim_end_matches('.');
+    if name.is_empty() {
+        return Ok(0);
+    }
+    let name = name.strip_prefix("*.").unwrap_or(name);
+    let count = name.split('.').count();
+    if count > 127 {
+        return Err(Error::ParseError);
+    }
+    Ok(count as u8)
+}
+
+/// Appends the canonical wire format of a domain name.
+fn encode_name(out: &mut Vec<u8>, name: &str) -> Result<(), Error> {
+    let name = name.trim_end_matches('.');
+    let start = out.len();
+    if !name.is_empty() {
+        for label in name.split('.') {
+            if label.is_empty() || label.len() > 63 {
+                return Err(Error::ParseError);
+            }
+            out.push(label.len() as u8);
+            out.extend(label.bytes().map(|c| c.to_ascii_lowercase()));
+        }
+    }
+    out.push(0);
+    if out.len() - start > 255 {
+        return Err(Error::ParseError);
+    }
+    Ok(())
+}
+
+/// Decodes an uncompressed domain name, and returns it along with its encoded
+/// length.
+fn decode_name(wire: &[u8]) -> Result<(String, usize), Error> {
+    let mut name = String::new();
+    let mut i = 0;
+    loop {
+        let len = *wire.get(i).ok_or(Error::ParseError)? as usize;
+        i += 1;
+        if len == 0 {
+            break;
+        }
+        if len > 63 || i + len > wire.len() || i + len > 255 {
+            return Err(Error::ParseError);
+        }
+        let label = core::str::from_utf8(&wire[i..i + len]).map_err(|_| Error::ParseError)?;
+        name.push_str(label);
+        name.push('.');
+        i += len;
+    }
+    if name.is_empty() {
+        name.push('.');
+    }
+    Ok((name, i))
+}
+
+#[test]
+fn test_dnssec() {
+    use super::Seed;
+    use ct_codecs::{Base64, Decoder};
+
+    // RFC 8080, Section 6, example 1
+    let seed = Seed::from_slice(b"82260384628080122645190204142262").unwrap();
+    let kp = KeyPair::from_seed(seed);
+    let dnskey = Dnskey {
+        flags: DNSKEY_FLAG_ZONE | DNSKEY_FLAG_SEP,
+        public_key: kp.pk,
+    };
+    assert_eq!(dnskey.key_tag(), 3613);
+    assert_eq!(Dnskey::from_rdata(&dnskey.to_rdata()).unwrap(), dnskey);
+
+    let mx = b"\x00\x0a\x04mail\x07example\x03com\x00";
+    let rrsig = Rrsig::sign(
+        &kp,
+        dnskey.flags,
+        "example.com.",
+        "example.com.",
+        15,
+        1,
+        3600,
+        &[mx],
+        1438207200,
+        1440021600,
+    )
+    .unwrap();
+    assert_eq!(rrsig.labels, 2);
+    assert_eq!(
+        rrsig.signature,
+        Signature::from_slice(
+            &Base64::decode_to_vec(
+                "oL9krJun7xfBOIWcGHi7mag5/hdZrKWw15jPGrHpjQeRAvTdszaPD+QLs3fx8A4M3e23mRZ9VrbpMngwcrqNAg==",
+                None
+            )
+            .unwrap()
+        )
+        .unwrap()
+    );
+    let rrsig = Rrsig::from_rdata(&rrsig.to_rdata().unwrap()).unwrap();
+    assert_eq!(rrsig.signer_name, "example.com.");
+    assert!(rrsig.verify(&dnskey, "EXAMPLE.com", 1, &[mx]).is_ok());
+    assert!(rrsig.verify(&dnskey, "example.org", 1, &[mx]).is_err());
+    assert!(rrsig
+        .verify(&dnskey, "example.com", 1, &[&mx[..3]])
+        .is_err());
+    assert!(rrsig.is_valid_at(1439000000));
+    assert!(!rrsig.is_valid_at(1441000000));
+
+    // Wildcard expansion
+    let rrsig = Rrsig::sign(
+        &kp,
+        dnskey.flags,
+        "example.com",
+        "*.example.com",
+        15,
+        1,
+        3600,
+        &[mx],
+        0,
+        u32::MAX,
+    )
+    .unwrap();
+    assert!(rrsig.verify(&dnskey, "www.example.com", 1, &[mx]).is_ok());
+}
@@ -1,4 +1,8 @@
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::convert::TryInto;
 use core::fmt;
+use core::hash::{Hash, Hasher};
 use core::ops::{Deref, DerefMut};
 
 use super::common::*;
@@ -20,7 +24,7 @@ impl PublicKey {
     pub const BYTES: usize = 32;
 
     /// Creates a public key from raw bytes.
-    pub fn new(pk: [u8; PublicKey::BYTES]) -> Self {
+    pub const fn new(pk: [u8; PublicKey::BYTES]) -> Self {
         PublicKey(pk)
     }
 
@@ -45,20 +49,33 @@ impl Deref for PublicKey {
 }
 
 /// A secret key.
-#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[derive(Copy, Clone, Eq, PartialEq, Hash)]
 pub struct SecretKey([u8; SecretKey::BYTES]);
 
+impl fmt::Debug for SecretKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_redacted(f, "SecretKey", &self.0)
+    }
+}
+
 impl SecretKey {
     /// Number of bytes in a secret key.
     pub const BYTES: usize = 32 + PublicKey::BYTES;
 
     /// Creates a secret key from raw bytes.
-    pub fn new(sk: [u8; SecretKey::BYTES]) -> Self {
+    pub const fn new(sk: [u8; SecretKey::BYTES]) -> Self {
         SecretKey(sk)
     }
 
     /// Creates a secret key from a slice.
+    ///
+    /// The slice can either contain a full secret key (seed followed by the
+    /// public key), or only a seed, in which case the public key is derived
+    /// from it.
     pub fn from_slice(sk: &[u8]) -> Result<Self, Error> {
+        if sk.len() == Seed::BYTES {
+            return SecretKey::from_seed(&Seed::from_slice(sk)?);
+        }
         let mut sk_ = [0u8; SecretKey::BYTES];
         if sk.len() != sk_.len() {
             return Err(Error::InvalidSecretKey);
@@ -74,9 +91,32 @@ impl SecretKey {
         PublicKey(pk)
     }
 
+    /// Derives a secret key from a seed.
+    ///
+    /// Returns `Error::InvalidSeed` if the seed is all zeros.
+    pub fn from_seed(seed: &Seed) -> Result<Self, Error> {
+        Ok(KeyPair::try_from_seed(*seed)?.sk)
+    }
+
     /// Returns the seed of a secret key.
     pub fn seed(&self) -> Seed {
-        Seed::from_slice(&self[0..Seed::BYTES]).unwrap()
+        let mut seed = [0u8; Seed::BYTES];
+        seed.copy_from_slice(&self[0..Seed::BYTES]);
+        Seed::new(seed)
+    }
+
+    /// Checks that the public key embedded in the secret key matches the one
+    /// derived from its seed.
+    ///
+    /// Signing with a secret key whose public half was corrupted or replaced
+    /// can leak the secret scalar, so keys coming from untrusted storage
+    /// should be validated first.
+    pub fn validate(&self) -> Result<(), Error> {
+        let kp = KeyPair::try_from_seed(self.seed()).map_err(|_| Error::InvalidSecretKey)?;
+        if kp.sk != *self {
+            return Err(Error::InvalidSecretKey);
+        }
+        Ok(())
     }
 }
 
@@ -96,6 +136,93 @@ impl DerefMut for SecretKey {
     }
 }
 
+/// A secret key that only stores its 32-byte seed.
+///
+/// This halves the storage required for a secret key, at the cost of having
+/// to recompute the public key every time a signature is computed.
+#[derive(Copy, Clone, Eq, PartialEq, Hash)]
+pub struct CompactSecretKey([u8; CompactSecretKey::BYTES]);
+
+impl fmt::Debug for CompactSecretKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_redacted(f, "CompactSecretKey", &self.0)
+    }
+}
+
+impl CompactSecretKey {
+    /// Number of bytes in a compact secret key.
+    pub const BYTES: usize = Seed::BYTES;
+
+    /// Creates a compact secret key from a seed.
+    ///
+    /// Returns `Error::InvalidSeed` if the seed is all zeros.
+    pub fn from_seed(seed: &Seed) -> Result<Self, Error> {
+        if seed.iter().fold(0, |acc, x| acc | x) == 0 {
+            return Err(Error::InvalidSeed);
+        }
+        Ok(CompactSecretKey(**seed))
+    }
+
+    /// Creates a compact secret key from a slice.
+    pub fn from_slice(sk: &[u8]) -> Result<Self, Error> {
+        Self::from_seed(&Seed::from_slice(sk)?)
+    }
+
+    /// Returns the seed of a compact secret key.
+    pub fn seed(&self) -> Seed {
+        Seed::new(self.0)
+    }
+
+    /// Returns the full secret key, including the public key.
+    pub fn to_secret_key(&self) -> SecretKey {
+        KeyPair::derive(&self.seed()).sk
+    }
+
+    /// Computes the public counterpart of a compact secret key.
+    pub fn public_key(&self) -> PublicKey {
+        KeyPair::derive(&self.seed()).pk
+    }
+
+    /// Computes a signature for the message `message` using the compact
+    /// secret key.
+    ///
+    /// Panics if the `self-verify` or `sign-twice` feature is enabled and a
+    /// fault is detected. Use `try_sign()` to handle this case.
+    pub fn sign(&self, message: impl AsRef<[u8]>, noise: Option<Noise>) -> Signature {
+        self.try_sign(message, noise)
+            .expect("Newly created signature cannot be verified")
+    }
+
+    /// Computes a signature for the message `message` using the compact
+    /// secret key, returning `Error::FaultDetected` instead of panicking if
+    /// a fault is detected.
+    pub fn try_sign(
+        &self,
+        message: impl AsRef<[u8]>,
+        noise: Option<Noise>,
+    ) -> Result<Signature, Error> {
+        let mut sk = self.to_secret_key();
+        let signature = sk.try_sign(message, noise);
+        wipe(&mut sk[..]);
+        signature
+    }
+}
+
+impl From<SecretKey> for CompactSecretKey {
+    fn from(sk: SecretKey) -> Self {
+        CompactSecretKey(*sk.seed())
+    }
+}
+
+impl Deref for CompactSecretKey {
+    type Target = [u8; CompactSecretKey::BYTES];
+
+    /// Returns a compact secret key as bytes.
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
 /// A key pair.
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
 pub struct KeyPair {
@@ -106,9 +233,24 @@ pub struct KeyPair {
 }
 
 /// An Ed25519 signature.
-#[derive(Copy, Clone, Eq, PartialEq, Hash)]
+#[derive(Copy, Clone)]
 pub struct Signature([u8; Signature::BYTES]);
 
+impl PartialEq for Signature {
+    /// Compares two signatures in constant time.
+    fn eq(&self, other: &Signature) -> bool {
+        ct_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for Signature {}
+
+impl Hash for Signature {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.hash(state);
+    }
+}
+
 impl fmt::Debug for Signature {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.write_fmt(format_args!("{:x?}", &self.0))
@@ -126,7 +268,7 @@ impl Signature {
     pub const BYTES: usize = 64;
 
     /// Creates a signature from raw bytes.
-    pub fn new(bytes: [u8; Signature::BYTES]) -> Self {
+    pub const fn new(bytes: [u8; Signature::BYTES]) -> Self {
         Signature(bytes)
     }
 
@@ -139,6 +281,65 @@ impl Signature {
         signature_.copy_from_slice(signature);
         Ok(Signature::new(signature_))
     }
+
+    /// Creates a signature from its `R` and `s` components.
+    pub fn from_parts(r: [u8; 32], s: [u8; 32]) -> Self {
+        let mut signature = [0u8; Signature::BYTES];
+        signature[0..32].copy_from_slice(&r);
+        signature[32..64].copy_from_slice(&s);
+        Signature(signature)
+    }
+
+    /// Returns the `R` component of the signature (an encoded point).
+    pub fn r(&self) -> [u8; 32] {
+        let mut r = [0u8; 32];
+        r.copy_from_slice(&self.0[0..32]);
+        r
+    }
+
+    /// Returns the `s` component of the signature (a scalar).
+    pub fn s(&self) -> [u8; 32] {
+        let mut s = [0u8; 32];
+        s.copy_from_slice(&self.0[32..64]);
+        s
+    }
+
+    /// Returns `true` if the `s` component of the signature is a canonical
+    /// scalar. Signatures for which this is not the case never verify.
+    pub fn s_is_canonical(&self) -> bool {
+        Scalar::is_canonical(&self.s())
+    }
+}
+
+/// A canonical scalar, i.e. an integer lower than the order of the main
+/// subgroup.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Scalar([u8; Scalar::BYTES]);
+
+impl Scalar {
+    /// Number of raw bytes in a scalar.
+    pub const BYTES: usize = 32;
+
+    /// Returns `true` if `s` is the canonical encoding of a scalar.
+    pub fn is_canonical(s: &[u8; Scalar::BYTES]) -> bool {
+        sc_reject_noncanonical(s).is_ok()
+    }
+
+    /// Creates a scalar from its canonical encoding, returning
+    /// `Error::NonCanonicalScalar` if it is not reduced.
+    pub fn from_canonical_bytes(s: [u8; Scalar::BYTES]) -> Result<Self, Error> {
+        sc_reject_noncanonical(&s)?;
+        Ok(Scalar(s))
+    }
+}
+
+impl Deref for Scalar {
+    type Target = [u8; Scalar::BYTES];
+
+    /// Returns a scalar as bytes.
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
 }
 
 impl Deref for Signature {
@@ -154,38 +355,135 @@ impl PublicKey {
     /// Verifies that the signature `signature` is valid for the message
     /// `message`.
     pub fn verify(&self, message: impl AsRef<[u8]>, signature: &Signature) -> Result<(), Error> {
+        self.verify_vectored(&[message.as_ref()], signature)
+    }
+
+    /// Verifies that the signature `signature` is valid for the message made
+    /// of the concatenation of `chunks`, without having to concatenate them.
+    pub fn verify_vectored(&self, chunks: &[&[u8]], signature: &Signature) -> Result<(), Error> {
+        self.verify_with_dom(None, chunks, signature)
+    }
+
+    /// Verifies an Ed25519ph (RFC 8032) signature, given the SHA-512 hash of
+    /// the message and an optional context of up to 255 bytes.
+    pub fn verify_prehashed(
+        &self,
+        prehashed: &[u8; 64],
+        context: &[u8],
+        signature: &Signature,
+    ) -> Result<(), Error> {
+        self.verify_with_dom(Some((1, context)), &[prehashed], signature)
+    }
+
+    /// Verifies a signature computed by `SecretKey::sign_with_options()`,
+    /// using the same context and prehash mode as `options`.
+    ///
+    /// Returns `Error::InvalidContext` if the context is longer than 255
+    /// bytes, or empty without `prehash`.
+    pub fn verify_with_options(
+        &self,
+        message: impl AsRef<[u8]>,
+        signature: &Signature,
+        options: &SignOptions,
+    ) -> Result<(), Error> {
+        options.check()?;
+        let mut prehashed = [0u8; 64];
+        let (dom, message) = options.dom(message.as_ref(), &mut prehashed);
+        check_dom(dom)?;
+        self.verify_with_dom(dom, &[message], signature)
+    }
+
+    fn verify_with_dom(
+        &self,
+        dom: Option<(u8, &[u8])>,
+        chunks: &[&[u8]],
+        signature: &Signature,
+    ) -> Result<(), Error> {
+        let mut st = self.verify_incremental_with_dom(dom, signature)?;
+        for chunk in chunks {
+            st.absorb(chunk);
+        }
+        st.verify()
+    }
+
+    /// Starts an incremental verification of the signature `signature`.
+    ///
+    /// The message can then be provided in multiple chunks using
+    /// `VerifyingState::absorb()`, and the signature checked with
+    /// `VerifyingState::verify()`.
+    pub fn verify_incremental(&self, signature: &Signature) -> Result<VerifyingState, Error> {
+        self.verify_incremental_with_dom(None, signature)
+    }
+
+    fn verify_incremental_with_dom(
+        &self,
+        dom: Option<(u8, &[u8])>,
+        signature: &Signature,
+    ) -> Result<VerifyingState, Error> {
+        check_dom(dom)?;
         let r = &signature[0..32];
         let s = &signature[32..64];
         sc_reject_noncanonical(s)?;
-        if is_identity(self) || self.iter().fold(0, |acc, x| acc | x) == 0 {
-            return Err(Error::WeakPublicKey);
-        }
-        let a = match GeP3::from_bytes_negate_vartime(self) {
-            Some(g) => g,
-            None => {
-                return Err(Error::InvalidPublicKey);
-            }
-        };
+        let a = self.negated_point()?;
 
         let mut hasher = sha512::Hash::new();
+        absorb_dom(&mut hasher, dom);
         hasher.update(r);
         hasher.update(&self[..]);
-        hasher.update(message);
-        let mut hash = hasher.finalize();
-        sc_reduce(&mut hash);
+        Ok(VerifyingState {
+            hasher,
+            signature: *signature,
+            pk: *self,
+            a,
+        })
+    }
 
-        let r = GeP2::double_scalarmult_vartime(hash.as_ref(), a, s);
-        if r.to_bytes()
-            .as_ref()
-            .iter()
-            .zip(signature.iter())
-            .fold(0, |acc, (x, y)| acc | (x ^ y))
-            != 0
-        {
-            Err(Error::SignatureMismatch)
-        } else {
-            Ok(())
+    /// Verifies many signatures made with this public key, and returns the
+    /// error of the first invalid one, if any.
+    ///
+    /// This is equivalent to calling `verify()` for every message, but the
+    /// public key is only decoded once, and its precomputed multiples are
+    /// shared by all the verifications.
+    pub fn verify_batch(&self, items: &[(&[u8], &Signature)]) -> Result<(), Error> {
+        let a = self.negated_point()?;
+        #[cfg(not(feature = "low-stack"))]
+        let ai = a.odd_multiples();
+        for (message, signature) in items {
+            let s = &signature[32..64];
+            sc_reject_noncanonical(s)?;
+            let mut hasher = sha512::Hash::new();
+            hasher.update(&signature[0..32]);
+            hasher.update(&self[..]);
+            hasher.update(message);
+            let mut hash = hasher.finalize();
+            sc_reduce(&mut hash);
+            #[cfg(not(feature = "low-stack"))]
+            let r = GeP2::double_scalarmult_multiples_vartime(&hash[0..32], &ai, s);
+            #[cfg(feature = "low-stack")]
+            let r = GeP2::double_scalarmult_vartime(&hash[0..32], a, s);
+            check_r(&r, signature)?;
+        }
+        Ok(())
+    }
+
+    /// Rejects weak public keys, and returns the negated point.
+    fn negated_point(&self) -> Result<GeP3, Error> {
+        if is_identity(self) || self.iter().fold(0, |acc, x| acc | x) == 0 {
+            return Err(Error::WeakPublicKey);
+        }
+        GeP3::from_bytes_negate_vartime(self).ok_or(Error::PublicKeyNotOnCurve)
+    }
+
+    /// Verifies a signed message produced by `SecretKey::sign_attached()`
+    /// (`signature || message`, as NaCl's `crypto_sign_open()`), and returns
+    /// the message if the signature is valid.
+    pub fn open<'t>(&self, signed_message: &'t [u8]) -> Result<&'t [u8], Error> {
+        if signed_message.len() < Signature::BYTES {
+            return Err(Error::InvalidSignature);
         }
+        let (signature, message) = signed_message.split_at(Signature::BYTES);
+        self.verify(message, &Signature::from_slice(signature)?)?;
+        Ok(message)
     }
 }
 
@@ -193,79 +491,569 @@ impl SecretKey {
     /// Computes a signature for the message `message` using the secret key.
     /// The noise parameter is optional, but recommended in order to mitigate
     /// fault attacks.
+    ///
+    /// Panics if the `self-verify` feature is enabled and the newly created
+    /// signature cannot be verified. Use `try_sign()` to handle this case.
     pub fn sign(&self, message: impl AsRef<[u8]>, noise: Option<Noise>) -> Signature {
+        self.try_sign(message, noise)
+            .expect("Newly created signature cannot be verified")
+    }
+
+    /// Computes a signature for the message `message` using the secret key.
+    ///
+    /// Unlike `sign()`, this function doesn't panic if the `self-verify` or
+    /// `sign-twice` feature is enabled and a fault is detected, but returns
+    /// `Error::FaultDetected` instead.
+    pub fn try_sign(
+        &self,
+        message: impl AsRef<[u8]>,
+        noise: Option<Noise>,
+    ) -> Result<Signature, Error> {
+        self.try_sign_vectored(&[message.as_ref()], noise)
+    }
+
+    /// Computes a signature for the message made of the concatenation of
+    /// `chunks`, without having to concatenate them.
+    ///
+    /// Panics if a fault is detected. Use `try_sign_vectored()` to handle
+    /// this case.
+    pub fn sign_vectored(&self, chunks: &[&[u8]], noise: Option<Noise>) -> Signature {
+        self.try_sign_vectored(chunks, noise)
+            .expect("Newly created signature cannot be verified")
+    }
+
+    /// Computes a signature for the message made of the concatenation of
+    /// `chunks`, returning `Error::FaultDetected` if a fault is detected.
+    pub fn try_sign_vectored(
+        &self,
+        chunks: &[&[u8]],
+        noise: Option<Noise>,
+    ) -> Result<Signature, Error> {
+        self.try_sign_with_dom(None, chunks, noise.into())
+    }
+
+    /// Computes an Ed25519ph (RFC 8032) signature, given the SHA-512 hash of
+    /// the message and an optional context of up to 255 bytes.
+    ///
+    /// Returns `Error::InvalidContext` if the context is longer than 255
+    /// bytes (Ed25519ph allows an empty context), and `Error::FaultDetected`
+    /// if a fault is detected.
+    pub fn sign_prehashed(
+        &self,
+        prehashed: &[u8; 64],
+        context: &[u8],
+        noise: Option<Noise>,
+    ) -> Result<Signature, Error> {
+        self.try_sign_with_dom(Some((1, context)), &[prehashed], noise.into())
+    }
+
+    /// Computes a hedged signature for the message `message`, mixing the
+    /// noise into the nonce as specified in
+    /// draft-irtf-cfrg-det-sigs-with-noise, instead of the ad-hoc
+    /// construction used by `sign()`.
+    ///
+    /// Returns `Error::FaultDetected` if a fault is detected.
+    #[cfg(feature = "hedged")]
+    pub fn sign_hedged(
+        &self,
+        message: impl AsRef<[u8]>,
+        noise: HedgedNoise,
+    ) -> Result<Signature, Error> {
+        self.sign_hedged_vectored(&[message.as_ref()], noise)
+    }
+
+    /// Computes a hedged signature for the message made of the
+    /// concatenation of `chunks`.
+    #[cfg(feature = "hedged")]
+    pub fn sign_hedged_vectored(
+        &self,
+        chunks: &[&[u8]],
+        noise: HedgedNoise,
+    ) -> Result<Signature, Error> {
+        self.try_sign_with_dom(None, chunks, Hedge::Cfrg(noise))
+    }
+
+    /// Computes a signature for the message `message`, with the variant
+    /// (Ed25519, Ed25519ctx or Ed25519ph) and the fault detection
+    /// countermeasures selected at runtime by `options`.
+    ///
+    /// Unlike `try_sign()`, the `self-verify` and `sign-twice` features only
+    /// set the default options, and can be overridden for individual calls.
+    ///
+    /// Returns `Error::InvalidContext` if the context is longer than 255
+    /// bytes, or empty without `prehash`.
+    pub fn sign_with_options(
+        &self,
+        message: impl AsRef<[u8]>,
+        options: &SignOptions,
+    ) -> Result<Signature, Error> {
+        options.check()?;
+        let mut prehashed = [0u8; 64];
+        let (dom, message) = options.dom(message.as_ref(), &mut prehashed);
+        self.sign_with_dom(
+            dom,
+            &[message],
+            options.noise.into(),
+            options.self_verify,
+            options.sign_twice,
+        )
+    }
+
+    fn try_sign_with_dom(
+        &self,
+        dom: Option<(u8, &[u8])>,
+        chunks: &[&[u8]],
+        noise: Hedge,
+    ) -> Result<Signature, Error> {
+        self.sign_with_dom(
+            dom,
+            chunks,
+            noise,
+            cfg!(feature = "self-verify"),
+            cfg!(feature = "sign-twice"),
+        )
+    }
+
+    fn sign_with_dom(
+        &self,
+        dom: Option<(u8, &[u8])>,
+        chunks: &[&[u8]],
+        noise: Hedge,
+        self_verify: bool,
+        sign_twice: bool,
+    ) -> Result<Signature, Error> {
+        sign_with_secrets(
+            &self.public_key(),
+            dom,
+            chunks,
+            || self.secrets(dom, chunks, noise),
+            self_verify,
+            sign_twice,
+        )
+    }
+
+    /// Returns the secret scalar, and the nonce for a signature.
+    fn secrets(
+        &self,
+        dom: Option<(u8, &[u8])>,
+        chunks: &[&[u8]],
+        noise: Hedge,
+    ) -> ([u8; 32], [u8; 32]) {
         let seed = &self[0..32];
-        let pk = &self[32..64];
-        let az: [u8; 64] = {
+        let mut az: [u8; 64] = {
             let mut hash_output = sha512::Hash::hash(seed);
-            hash_output[0] &= 248;
-            hash_output[31] &= 63;
-            hash_output[31] |= 64;
+            KeyPair::clamp_expanded(&mut hash_output);
             hash_output
         };
-        let nonce = {
+        let mut nonce = {
             let mut hasher = sha512::Hash::new();
-            if let Some(noise) = noise {
-                hasher.update(&noise[..]);
-                hasher.update(&az[..]);
-            } else {
-                hasher.update(&az[32..64]);
+            absorb_dom(&mut hasher, dom);
+            match noise {
+                Hedge::Deterministic => hasher.update(&az[32..64]),
+                Hedge::Noise(noise) => {
+                    hasher.update(&noise[..]);
+                    hasher.update(&az[..]);
+                }
+                #[cfg(feature = "hedged")]
+                Hedge::Cfrg(noise) => {
+                    // dom2 || Z, zero-padded to a full SHA-512 block, then the
+                    // prefix.
+                    let dom_len = dom.map_or(0, |(_, context)| 34 + context.len());
+                    hasher.update(&noise[..]);
+                    let pad_len = (128 - (dom_len + HedgedNoise::BYTES) % 128) % 128;
+                    hasher.update(&[0u8; 128][..pad_len]);
+                    hasher.update(&az[32..64]);
+                }
+            }
+            for chunk in chunks {
+                hasher.update(chunk);
             }
-            hasher.update(&message);
             let mut hash_output = hasher.finalize();
             sc_reduce(&mut hash_output[0..64]);
             hash_output
         };
-        let mut signature: [u8; 64] = [0; 64];
-        let r = ge_scalarmult_base(&nonce[0..32]);
-        signature[0..32].copy_from_slice(&r.to_bytes()[..]);
-        signature[32..64].copy_from_slice(pk);
-        let mut hasher = sha512::Hash::new();
-        hasher.update(signature.as_ref());
-        hasher.update(&message);
-        let mut hram = hasher.finalize();
-        sc_reduce(&mut hram);
-        sc_muladd(
-            &mut signature[32..64],
-            &hram[0..32],
-            &az[0..32],
-            &nonce[0..32],
-        );
-        let signature = Signature(signature);
-
-        #[cfg(feature = "self-verify")]
-        {
-            PublicKey::from_slice(pk)
-                .expect("Key length changed")
-                .verify(message, &signature)
-                .expect("Newly created signature cannot be verified");
+        let mut secrets = ([0u8; 32], [0u8; 32]);
+        secrets.0.copy_from_slice(&az[0..32]);
+        secrets.1.copy_from_slice(&nonce[0..32]);
+        wipe(&mut az);
+        wipe(&mut nonce);
+        secrets
+    }
+
+    /// Computes a signature for the message `message` and returns it
+    /// followed by the message itself (`signature || message`), as NaCl's
+    /// `crypto_sign()` does. Use `PublicKey::open()` to verify the result.
+    #[cfg(feature = "alloc")]
+    pub fn sign_attached(&self, message: impl AsRef<[u8]>, noise: Option<Noise>) -> Vec<u8> {
+        let message = message.as_ref();
+        let mut signed_message = Vec::with_capacity(Signature::BYTES + message.len());
+        signed_message.extend_from_slice(&self.sign(message, noise)[..]);
+        signed_message.extend_from_slice(message);
+        signed_message
+    }
+}
+
+fn check_dom(dom: Option<(u8, &[u8])>) -> Result<(), Error> {
+    match dom {
+        Some((_, context)) if context.len() > 255 => Err(Error::InvalidContext),
+        _ => Ok(()),
+    }
+}
+
+/// Computes a signature for the public key `pk`, with the secret scalar and
+/// the nonce returned by `secrets`.
+///
+/// Every signing path goes through this function, so that fault detection
+/// and the audit hook apply to all of them. With `sign_twice`, `secrets` is
+/// called again to recompute the signature, and must return the same values.
+pub(crate) fn sign_with_secrets(
+    pk: &PublicKey,
+    dom: Option<(u8, &[u8])>,
+    chunks: &[&[u8]],
+    secrets: impl Fn() -> ([u8; 32], [u8; 32]),
+    self_verify: bool,
+    sign_twice: bool,
+) -> Result<Signature, Error> {
+    check_dom(dom)?;
+    let signature = sign_once(pk, dom, chunks, &secrets);
+    if sign_twice && !ct_eq(&signature[..], &sign_once(pk, dom, chunks, &secrets)[..]) {
+        return Err(Error::FaultDetected);
+    }
+    if self_verify {
+        pk.verify_with_dom(dom, chunks, &signature)
+            .map_err(|_| Error::FaultDetected)?;
+    }
+    #[cfg(feature = "audit")]
+    crate::audit::notify(*pk, dom, chunks, &signature);
+    Ok(signature)
+}
+
+fn sign_once(
+    pk: &PublicKey,
+    dom: Option<(u8, &[u8])>,
+    chunks: &[&[u8]],
+    secrets: &impl Fn() -> ([u8; 32], [u8; 32]),
+) -> Signature {
+    let (mut scalar, mut nonce) = secrets();
+    let mut signature: [u8; 64] = [0; 64];
+    let r = ge_scalarmult_base(&nonce);
+    signature[0..32].copy_from_slice(&r.to_bytes()[..]);
+    signature[32..64].copy_from_slice(&pk[..]);
+    let mut hasher = sha512::Hash::new();
+    absorb_dom(&mut hasher, dom);
+    hasher.update(signature.as_ref());
+    for chunk in chunks {
+        hasher.update(chunk);
+    }
+    let mut hram = hasher.finalize();
+    sc_reduce(&mut hram);
+    sc_muladd(&mut signature[32..64], &hram[0..32], &scalar, &nonce);
+    wipe(&mut scalar);
+    wipe(&mut nonce);
+    wipe(&mut hram);
+    Signature(signature)
+}
+
+/// Options for `SecretKey::sign_with_options()`.
+///
+/// The default options produce plain Ed25519 signatures, and enable the
+/// countermeasures selected by the `self-verify` and `sign-twice` features.
+/// Other options are set with the `with_*()` builder methods, as new fields
+/// may be added in the future.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[non_exhaustive]
+pub struct SignOptions<'t> {
+    /// Optional noise, to mitigate fault attacks.
+    pub noise: Option<Noise>,
+    /// Optional context of up to 255 bytes, binding the signature to an
+    /// application or protocol (Ed25519ctx, RFC 8032). Ed25519ctx requires
+    /// a non-empty context, an empty one is only allowed with `prehash`.
+    pub context: Option<&'t [u8]>,
+    /// Sign the SHA-512 hash of the message instead of the message itself
+    /// (Ed25519ph, RFC 8032).
+    pub prehash: bool,
+    /// Verify the signature after having computed it.
+    pub self_verify: bool,
+    /// Compute the signature twice and compare the results.
+    pub sign_twice: bool,
+}
+
+#[allow(clippy::derivable_impls)]
+impl Default for SignOptions<'_> {
+    fn default() -> Self {
+        SignOptions {
+            noise: None,
+            context: None,
+            prehash: false,
+            self_verify: cfg!(feature = "self-verify"),
+            sign_twice: cfg!(feature = "sign-twice"),
         }
+    }
+}
+
+impl<'t> SignOptions<'t> {
+    /// Sets the noise used to mitigate fault attacks.
+    pub fn with_noise(mut self, noise: Noise) -> Self {
+        self.noise = Some(noise);
+        self
+    }
+
+    /// Sets the context, binding the signature to an application or
+    /// protocol.
+    pub fn with_context(mut self, context: &'t [u8]) -> Self {
+        self.context = Some(context);
+        self
+    }
+
+    /// Signs the SHA-512 hash of the message (Ed25519ph).
+    pub fn prehashed(mut self) -> Self {
+        self.prehash = true;
+        self
+    }
+
+    /// Enables or disables verifying the signature after having computed
+    /// it.
+    pub fn with_self_verify(mut self, self_verify: bool) -> Self {
+        self.self_verify = self_verify;
+        self
+    }
+
+    /// Enables or disables computing the signature twice and comparing the
+    /// results.
+    pub fn with_sign_twice(mut self, sign_twice: bool) -> Self {
+        self.sign_twice = sign_twice;
+        self
+    }
+
+    /// Rejects an empty context without prehashing, that Ed25519ctx doesn't
+    /// allow.
+    fn check(&self) -> Result<(), Error> {
+        match self.context {
+            Some(context) if context.is_empty() && !self.prehash => Err(Error::InvalidContext),
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns the `dom2` parameters for the selected variant, and the
+    /// message to sign, hashed into `prehashed` if required.
+    fn dom<'m>(
+        &self,
+        message: &'m [u8],
+        prehashed: &'m mut [u8; 64],
+    ) -> (Option<(u8, &'t [u8])>, &'m [u8]) {
+        if self.prehash {
+            *prehashed = sha512::Hash::hash(message);
+            (Some((1, self.context.unwrap_or(&[]))), &prehashed[..])
+        } else {
+            (self.context.map(|context| (0, context)), message)
+        }
+    }
+}
+
+/// How the noise is mixed into the nonce.
+#[derive(Copy, Clone)]
+enum Hedge {
+    Deterministic,
+    Noise(Noise),
+    #[cfg(feature = "hedged")]
+    Cfrg(HedgedNoise),
+}
+
+impl From<Option<Noise>> for Hedge {
+    fn from(noise: Option<Noise>) -> Self {
+        match noise {
+            None => Hedge::Deterministic,
+            Some(noise) => Hedge::Noise(noise),
+        }
+    }
+}
+
+/// Absorbs the `dom2(phflag, context)` prefix of RFC 8032 variants.
+fn absorb_dom(hasher: &mut sha512::Hash, dom: Option<(u8, &[u8])>) {
+    if let Some((phflag, context)) = dom {
+        hasher.update(b"SigEd25519 no Ed25519 collisions");
+        hasher.update([phflag, context.len() as u8]);
+        hasher.update(context);
+    }
+}
+
+/// The state of an incremental signature verification.
+#[derive(Clone)]
+pub struct VerifyingState {
+    hasher: sha512::Hash,
+    signature: Signature,
+    pk: PublicKey,
+    a: GeP3,
+}
+
+impl VerifyingState {
+    /// Number of bytes in a serialized verification state.
+    pub const BYTES: usize = PublicKey::BYTES + Signature::BYTES + sha512::Hash::STATE_BYTES;
+
+    /// Serializes the verification state, so that it can be resumed with
+    /// `from_bytes()`, for example after a reset.
+    ///
+    /// The serialized state is not authenticated, and must be stored where
+    /// it cannot be modified by an adversary.
+    pub fn to_bytes(&self) -> [u8; VerifyingState::BYTES] {
+        let mut bytes = [0u8; VerifyingState::BYTES];
+        bytes[0..32].copy_from_slice(&self.pk[..]);
+        bytes[32..96].copy_from_slice(&self.signature[..]);
+        bytes[96..].copy_from_slice(&self.hasher.to_state());
+        bytes
+    }
+
+    /// Restores a verification state serialized with `to_bytes()`.
+    pub fn from_bytes(bytes: &[u8; VerifyingState::BYTES]) -> Result<Self, Error> {
+        let pk = PublicKey::from_slice(&bytes[0..32])?;
+        let signature = Signature::from_slice(&bytes[32..96])?;
+        let mut state = pk.verify_incremental(&signature)?;
+        state.hasher = sha512::Hash::from_state(bytes[96..].try_into().unwrap());
+        Ok(state)
+    }
+
+    /// Appends the content of `chunk` to the message to verify.
+    pub fn absorb(&mut self, chunk: impl AsRef<[u8]>) {
+        self.hasher.update(chunk);
+    }
+
+    /// Verifies the signature for the message absorbed so far.
+    pub fn verify(&self) -> Result<(), Error> {
+        let mut hash = self.hasher.finalize();
+        sc_reduce(&mut hash);
+        let s = &self.signature[32..64];
+        let r = GeP2::double_scalarmult_vartime(hash.as_ref(), self.a, s);
+        check_r(&r, &self.signature)
+    }
+}
+
+/// Checks that the point computed by the verification equation encodes to
+/// the `R` component of the signature.
+fn check_r(r: &GeP2, signature: &Signature) -> Result<(), Error> {
+    if r.to_bytes()
+        .as_ref()
+        .iter()
+        .zip(signature.iter())
+        .fold(0, |acc, (x, y)| acc | (x ^ y))
+        != 0
+    {
+        Err(Error::SignatureMismatch)
+    } else {
+        Ok(())
+    }
+}
+
+impl SecretKey {
+    /// Starts an incremental Ed25519ph (RFC 8032) signature, for messages
+    /// that are not entirely available in memory.
+    ///
+    /// The message can then be provided in multiple chunks using
+    /// `SigningState::absorb()`, and the signature computed with
+    /// `SigningState::sign()`.
+    ///
+    /// The message is hashed with SHA-512 and the hash is signed with an
+    /// empty context, so that the nonce depends on the whole message, like
+    /// `sign_reader()`. The signature can be verified with
+    /// `PublicKey::verify_prehashed()`.
+    pub fn sign_incremental(&self, noise: Option<Noise>) -> SigningState {
+        SigningState {
+            hasher: sha512::Hash::new(),
+            sk: *self,
+            noise,
+        }
+    }
+}
+
+/// The state of an incremental signature.
+pub struct SigningState {
+    hasher: sha512::Hash,
+    sk: SecretKey,
+    noise: Option<Noise>,
+}
+
+impl SigningState {
+    /// Appends the content of `chunk` to the message to sign.
+    pub fn absorb(&mut self, chunk: impl AsRef<[u8]>) {
+        self.hasher.update(chunk);
+    }
+
+    /// Computes the signature for the message absorbed so far.
+    ///
+    /// Returns `Error::FaultDetected` if a fault is detected.
+    pub fn sign(self) -> Result<Signature, Error> {
+        let mut prehashed = self.hasher.finalize();
+        let signature = self.sk.sign_prehashed(&prehashed, &[], self.noise);
+        wipe(&mut prehashed);
         signature
     }
 }
 
+impl Drop for SigningState {
+    fn drop(&mut self) {
+        wipe(&mut self.sk[..]);
+    }
+}
+
 impl KeyPair {
     /// Number of bytes in a key pair.
     pub const BYTES: usize = SecretKey::BYTES;
 
     /// Generates a new key pair.
-    #[cfg(feature = "random")]
+    #[cfg(any(feature = "random", feature = "entropy-source"))]
     pub fn generate() -> KeyPair {
         KeyPair::from_seed(Seed::default())
     }
 
+    /// Generates a new key pair, returning an error if the system RNG is not
+    /// available.
+    #[cfg(any(feature = "random", feature = "entropy-source"))]
+    pub fn try_generate() -> Result<KeyPair, Error> {
+        KeyPair::try_from_seed(Seed::try_generate()?)
+    }
+
+    /// Generates a new key pair using a custom random number generator.
+    ///
+    /// `rng` is called to fill a buffer with random bytes, and can be used on
+    /// platforms where `getrandom` isn't available.
+    pub fn generate_with_rng(
+        mut rng: impl FnMut(&mut [u8]) -> Result<(), Error>,
+    ) -> Result<KeyPair, Error> {
+        let mut seed = Seed::new([0u8; Seed::BYTES]);
+        rng(&mut seed[..])?;
+        let kp = KeyPair::try_from_seed(seed);
+        wipe(&mut seed[..]);
+        kp
+    }
+
     /// Generates a new key pair using a secret seed.
+    ///
+    /// Panics if the seed is all zeros. Use `try_from_seed()` to handle this
+    /// case.
     pub fn from_seed(seed: Seed) -> KeyPair {
+        KeyPair::try_from_seed(seed).expect("All-zero seed")
+    }
+
+    /// Generates a new key pair using a secret seed, returning
+    /// `Error::InvalidSeed` if the seed is all zeros.
+    pub fn try_from_seed(seed: Seed) -> Result<KeyPair, Error> {
         if seed.iter().fold(0, |acc, x| acc | x) == 0 {
-            panic!("All-zero seed");
+            return Err(Error::InvalidSeed);
         }
-        let (scalar, _) = {
-            let hash_output = sha512::Hash::hash(&seed[..]);
-            KeyPair::split(&hash_output, false, true)
+        Ok(KeyPair::derive(&seed))
+    }
+
+    fn derive(seed: &Seed) -> KeyPair {
+        let (mut scalar, mut prefix) = {
+            let mut hash_output = sha512::Hash::hash(&seed[..]);
+            let split = KeyPair::split(&hash_output, false, true);
+            wipe(&mut hash_output);
+            split
         };
         let pk = ge_scalarmult_base(&scalar).to_bytes();
+        wipe(&mut scalar);
+        wipe(&mut prefix);
         let mut sk = [0u8; 64];
-        sk[0..32].copy_from_slice(&*seed);
+        sk[0..32].copy_from_slice(&seed[..]);
         sk[32..64].copy_from_slice(&pk);
         KeyPair {
             pk: PublicKey(pk),
@@ -273,17 +1061,59 @@ impl KeyPair {
         }
     }
 
+    /// Reconstructs a key pair from a secret key.
+    ///
+    /// The public key embedded in the secret key is checked against the one
+    /// derived from its seed, so that a corrupted secret key is rejected
+    /// instead of being used to sign.
+    pub fn from_secret_key(sk: SecretKey) -> Result<KeyPair, Error> {
+        sk.validate()?;
+        Ok(KeyPair {
+            pk: sk.public_key(),
+            sk,
+        })
+    }
+
+    /// Checks that the secret key is consistent with its seed, and that the
+    /// public key is its public counterpart.
+    pub fn validate(&self) -> Result<(), Error> {
+        self.sk.validate()?;
+        if self.sk.public_key() != self.pk {
+            return Err(Error::InvalidPublicKey);
+        }
+        Ok(())
+    }
+
     /// Creates a key pair from a slice.
+    ///
+    /// The slice can either contain a full secret key, as returned by
+    /// `to_bytes()`, or only a seed. A full secret key is rejected if its
+    /// public key doesn't match its seed.
     pub fn from_slice(bytes: &[u8]) -> Result<Self, Error> {
-        let sk = SecretKey::from_slice(bytes)?;
-        let pk = sk.public_key();
-        Ok(KeyPair { pk, sk })
+        KeyPair::from_secret_key(SecretKey::from_slice(bytes)?)
+    }
+
+    /// Creates a key pair from the output of `to_bytes()`, checking that
+    /// the public key matches the seed.
+    pub fn from_bytes(bytes: &[u8; KeyPair::BYTES]) -> Result<Self, Error> {
+        KeyPair::from_secret_key(SecretKey::new(*bytes))
+    }
+
+    /// Returns the key pair as bytes: the seed followed by the public key,
+    /// like a secret key.
+    pub fn to_bytes(&self) -> [u8; KeyPair::BYTES] {
+        *self.sk
     }
 
-    pub fn clamp(scalar: &mut [u8]) {
-        scalar[0] &= 248;
-        scalar[31] &= 63;
-        scalar[31] |= 64;
+    /// Clamps a scalar, like the `clamp()` function.
+    pub fn clamp(scalar: &mut [u8; 32]) {
+        clamp(scalar)
+    }
+
+    /// Clamps the scalar stored in the first half of an expanded secret key.
+    pub(crate) fn clamp_expanded(az: &mut [u8; 64]) {
+        let (scalar, _) = az.split_at_mut(32);
+        clamp(scalar.try_into().unwrap());
     }
 
     pub fn split(bytes: &[u8; 64], reduce: bool, clamp: bool) -> ([u8; 32], [u8; 32]) {
@@ -311,15 +1141,21 @@ impl Deref for KeyPair {
 }
 
 /// Noise, for non-deterministic signatures.
-#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[derive(Copy, Clone, Eq, PartialEq, Hash)]
 pub struct Noise([u8; Noise::BYTES]);
 
+impl fmt::Debug for Noise {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_redacted(f, "Noise", &self.0)
+    }
+}
+
 impl Noise {
     /// Number of raw bytes for a noise component.
     pub const BYTES: usize = 16;
 
     /// Creates a new noise component from raw bytes.
-    pub fn new(noise: [u8; Noise::BYTES]) -> Self {
+    pub const fn new(noise: [u8; Noise::BYTES]) -> Self {
         Noise(noise)
     }
 
@@ -327,11 +1163,33 @@ impl Noise {
     pub fn from_slice(noise: &[u8]) -> Result<Self, Error> {
         let mut noise_ = [0u8; Noise::BYTES];
         if noise.len() != noise_.len() {
-            return Err(Error::InvalidSeed);
+            return Err(Error::InvalidNoise);
         }
         noise_.copy_from_slice(noise);
         Ok(Noise::new(noise_))
     }
+
+    /// Derives noise from a secret key, caller-supplied state and the
+    /// message to sign, using HMAC-SHA-512 keyed with the seed.
+    ///
+    /// This is meant for devices without an entropy source. The noise only
+    /// mitigates fault attacks if `state` never repeats for a given key:
+    /// use a persistent counter incremented before every signature, possibly
+    /// along with a device-unique value. With the same `state`, the noise is
+    /// a deterministic function of the key and the message, just like the
+    /// RFC 8032 nonce, and provides no protection against faults.
+    pub fn derive(sk: &SecretKey, state: &[u8], message: impl AsRef<[u8]>) -> Self {
+        let mut h = sha512::Hmac::new(&sk[0..Seed::BYTES]);
+        h.update(b"ed25519-compact noise");
+        h.update((state.len() as u64).to_le_bytes());
+        h.update(state);
+        h.update(message);
+        let mut hash_output = h.finalize();
+        let mut noise = [0u8; Noise::BYTES];
+        noise.copy_from_slice(&hash_output[..Noise::BYTES]);
+        wipe(&mut hash_output);
+        Noise(noise)
+    }
 }
 
 impl Deref for Noise {
@@ -343,22 +1201,86 @@ impl Deref for Noise {
     }
 }
 
-#[cfg(feature = "random")]
+#[cfg(any(feature = "random", feature = "entropy-source"))]
 impl Default for Noise {
     /// Generates random noise.
     fn default() -> Self {
-        let mut noise = [0u8; Noise::BYTES];
-        getrandom::getrandom(&mut noise).expect("RNG failure");
-        Noise(noise)
+        Noise::try_generate().expect("RNG failure")
     }
 }
 
-#[cfg(feature = "random")]
+#[cfg(any(feature = "random", feature = "entropy-source"))]
 impl Noise {
     /// Generates random noise.
     pub fn generate() -> Self {
         Noise::default()
     }
+
+    /// Generates random noise, returning an error if the system RNG is not
+    /// available.
+    pub fn try_generate() -> Result<Self, Error> {
+        let mut noise = [0u8; Noise::BYTES];
+        crate::entropy::fill_random(&mut noise)?;
+        Ok(Noise(noise))
+    }
+}
+
+/// Noise for hedged signatures, as defined in
+/// draft-irtf-cfrg-det-sigs-with-noise.
+#[cfg(feature = "hedged")]
+#[derive(Copy, Clone, Eq, PartialEq, Hash)]
+pub struct HedgedNoise([u8; HedgedNoise::BYTES]);
+
+#[cfg(feature = "hedged")]
+impl fmt::Debug for HedgedNoise {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_redacted(f, "HedgedNoise", &self.0)
+    }
+}
+
+#[cfg(feature = "hedged")]
+impl HedgedNoise {
+    /// Number of raw bytes for hedged signature noise.
+    pub const BYTES: usize = 32;
+
+    /// Creates hedged signature noise from raw bytes.
+    pub fn new(noise: [u8; HedgedNoise::BYTES]) -> Self {
+        HedgedNoise(noise)
+    }
+
+    /// Creates hedged signature noise from a slice.
+    pub fn from_slice(noise: &[u8]) -> Result<Self, Error> {
+        let mut noise_ = [0u8; HedgedNoise::BYTES];
+        if noise.len() != noise_.len() {
+            return Err(Error::InvalidNoise);
+        }
+        noise_.copy_from_slice(noise);
+        Ok(HedgedNoise::new(noise_))
+    }
+}
+
+#[cfg(feature = "hedged")]
+impl Deref for HedgedNoise {
+    type Target = [u8; HedgedNoise::BYTES];
+
+    /// Returns the noise as raw bytes.
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+#[cfg(all(
+    feature = "hedged",
+    any(feature = "random", feature = "entropy-source")
+))]
+impl HedgedNoise {
+    /// Generates random noise, returning an error if the system RNG is not
+    /// available.
+    pub fn try_generate() -> Result<Self, Error> {
+        let mut noise = [0u8; HedgedNoise::BYTES];
+        crate::entropy::fill_random(&mut noise)?;
+        Ok(HedgedNoise(noise))
+    }
 }
 
 #[cfg(feature = "traits")]
@@ -369,15 +1291,21 @@ mod ed25519_trait {
 
     impl ed25519_trait::Signature for Signature {
         fn from_bytes(bytes: &[u8]) -> Result<Self, ed25519_trait::Error> {
-            let mut bytes_ = [0u8; Signature::BYTES];
-            bytes_.copy_from_slice(bytes);
-            Ok(Signature::new(bytes_))
+            Signature::from_slice(bytes).map_err(|_| ed25519_trait::Error::new())
         }
     }
 
     impl ed25519_trait::Signer<Signature> for SecretKey {
         fn try_sign(&self, message: &[u8]) -> Result<Signature, ed25519_trait::Error> {
-            Ok(self.sign(message, None))
+            #[cfg(feature = "std")]
+            {
+                SecretKey::try_sign(self, message, None).map_err(ed25519_trait::Error::from_source)
+            }
+
+            #[cfg(not(feature = "std"))]
+            {
+                SecretKey::try_sign(self, message, None).map_err(|_| ed25519_trait::Error::new())
+            }
         }
     }
 
@@ -390,7 +1318,7 @@ mod ed25519_trait {
             #[cfg(feature = "std")]
             {
                 self.verify(message, signature)
-                    .map_err(|e| ed25519_trait::Error::from_source(e))
+                    .map_err(ed25519_trait::Error::from_source)
             }
 
             #[cfg(not(feature = "std"))]
@@ -408,7 +1336,148 @@ fn test_ed25519() {
     let message = b"Hello, World!";
     let signature = kp.sk.sign(message, None);
     assert!(kp.pk.verify(message, &signature).is_ok());
-    assert!(kp.pk.verify(b"Hello, world!", &signature).is_err());
+    assert_eq!(
+        kp.pk.verify(b"Hello, world!", &signature),
+        Err(Error::SignatureMismatch)
+    );
+    let mut non_canonical = *signature;
+    non_canonical[63] |= 0xf0;
+    assert_eq!(
+        kp.pk.verify(message, &Signature::new(non_canonical)),
+        Err(Error::NonCanonicalScalar)
+    );
+    assert!(signature.s_is_canonical());
+    let mut clamped = [0xffu8; 32];
+    clamp(&mut clamped);
+    assert_eq!((clamped[0], clamped[31]), (248, 127));
+    assert!(!Signature::new(non_canonical).s_is_canonical());
+    assert_eq!(
+        Scalar::from_canonical_bytes([0xff; Scalar::BYTES]),
+        Err(Error::NonCanonicalScalar)
+    );
+    assert_eq!(kp.sk.try_sign(message, None), Ok(signature));
+    assert_eq!(
+        Signature::from_parts(signature.r(), signature.s()),
+        signature
+    );
+    // RFC 8032, section 7.3
+    let ph_kp = KeyPair::from_seed(Seed::new([
+        0x83, 0x3f, 0xe6, 0x24, 0x09, 0x23, 0x7b, 0x9d, 0x62, 0xec, 0x77, 0x58, 0x75, 0x20, 0x91,
+        0x1e, 0x9a, 0x75, 0x9c, 0xec, 0x1d, 0x19, 0x75, 0x5b, 0x7d, 0xa9, 0x01, 0xb9, 0x6d, 0xca,
+        0x3d, 0x42,
+    ]));
+    let ph_signature = ph_kp
+        .sk
+        .sign_prehashed(&sha512::Hash::hash(b"abc"), &[], None)
+        .unwrap();
+    assert_eq!(
+        ph_signature.as_ref(),
+        [
+            0x98, 0xa7, 0x02, 0x22, 0xf0, 0xb8, 0x12, 0x1a, 0xa9, 0xd3, 0x0f, 0x81, 0x3d, 0x68,
+            0x3f, 0x80, 0x9e, 0x46, 0x2b, 0x46, 0x9c, 0x7f, 0xf8, 0x76, 0x39, 0x49, 0x9b, 0xb9,
+            0x4e, 0x6d, 0xae, 0x41, 0x31, 0xf8, 0x50, 0x42, 0x46, 0x3c, 0x2a, 0x35, 0x5a, 0x20,
+            0x03, 0xd0, 0x62, 0xad, 0xf5, 0xaa, 0xa1, 0x0b, 0x8c, 0x61, 0xe6, 0x36, 0x06, 0x2a,
+            0xaa, 0xd1, 0x1c, 0x2a, 0x26, 0x08, 0x34, 0x06,
+        ]
+    );
+    assert!(ph_kp
+        .pk
+        .verify_prehashed(&sha512::Hash::hash(b"abc"), &[], &ph_signature)
+        .is_ok());
+    assert_eq!(
+        ph_kp
+            .pk
+            .verify_prehashed(&sha512::Hash::hash(b"abc"), b"ctx", &ph_signature),
+        Err(Error::SignatureMismatch)
+    );
+    assert_eq!(
+        ph_kp
+            .sk
+            .sign_prehashed(&sha512::Hash::hash(b"abc"), &[0u8; 256], None),
+        Err(Error::InvalidContext)
+    );
+    let mut st = kp.sk.sign_incremental(None);
+    st.absorb(b"Hello, ");
+    st.absorb(b"World!");
+    let incremental_signature = st.sign().unwrap();
+    assert_eq!(
+        kp.sk
+            .sign_prehashed(&sha512::Hash::hash(message), &[], None),
+        Ok(incremental_signature)
+    );
+    assert!(kp
+        .pk
+        .verify_prehashed(&sha512::Hash::hash(message), &[], &incremental_signature)
+        .is_ok());
+    let mut st = kp.pk.verify_incremental(&signature).unwrap();
+    st.absorb(b"Hello, ");
+    st.absorb(b"World");
+    assert_eq!(st.verify(), Err(Error::SignatureMismatch));
+    st.absorb(b"!");
+    assert!(st.verify().is_ok());
+    let chunks: [&[u8]; 3] = [b"Hello", b", ", b"World!"];
+    assert_eq!(kp.sk.sign_vectored(&chunks, None), signature);
+    assert!(kp.pk.verify_vectored(&chunks, &signature).is_ok());
+    #[cfg(feature = "alloc")]
+    {
+        let mut signed_message = kp.sk.sign_attached(message, None);
+        assert_eq!(signed_message[..Signature::BYTES], signature[..]);
+        assert_eq!(kp.pk.open(&signed_message), Ok(&message[..]));
+        *signed_message.last_mut().unwrap() ^= 1;
+        assert_eq!(kp.pk.open(&signed_message), Err(Error::SignatureMismatch));
+        assert_eq!(kp.pk.open(&signature[..63]), Err(Error::InvalidSignature));
+    }
+    assert_eq!(
+        KeyPair::try_from_seed(Seed::new([0u8; Seed::BYTES])),
+        Err(Error::InvalidSeed)
+    );
+    assert!(kp.validate().is_ok());
+    let compact_sk = CompactSecretKey::from(kp.sk);
+    assert_eq!(compact_sk.to_secret_key(), kp.sk);
+    assert_eq!(compact_sk.public_key(), kp.pk);
+    assert_eq!(compact_sk.sign(message, None), signature);
+    #[cfg(feature = "alloc")]
+    {
+        let debug = alloc::format!("{:?}", kp);
+        assert!(debug.contains("SecretKey(fingerprint: "));
+        assert!(!debug.contains("42, 42"));
+    }
+    assert_eq!(SecretKey::from_seed(&kp.sk.seed()), Ok(kp.sk));
+    assert_eq!(SecretKey::from_slice(&kp.sk.seed()[..]), Ok(kp.sk));
+    assert_eq!(KeyPair::from_slice(&kp.sk.seed()[..]), Ok(kp));
+    assert_eq!(KeyPair::from_slice(&kp.to_bytes()), Ok(kp));
+    assert_eq!(KeyPair::from_bytes(&kp.to_bytes()), Ok(kp));
+    let kp2 = KeyPair::generate_with_rng(|buf| {
+        buf.fill(42);
+        Ok(())
+    })
+    .unwrap();
+    assert_eq!(kp, kp2);
+    let mut spliced_sk = kp.sk;
+    spliced_sk[63] ^= 1;
+    assert_eq!(spliced_sk.validate(), Err(Error::InvalidSecretKey));
+    assert_eq!(
+        KeyPair::from_bytes(&spliced_sk),
+        Err(Error::InvalidSecretKey)
+    );
+    assert_eq!(KeyPair::from_secret_key(kp.sk), Ok(kp));
+    assert_eq!(
+        KeyPair::from_secret_key(spliced_sk),
+        Err(Error::InvalidSecretKey)
+    );
+    let other_kp = KeyPair::from_seed([43u8; 32].into());
+    let spliced_kp = KeyPair {
+        pk: other_kp.pk,
+        sk: kp.sk,
+    };
+    assert_eq!(spliced_kp.validate(), Err(Error::InvalidPublicKey));
+    #[cfg(any(feature = "random", feature = "entropy-source"))]
+    {
+        let kp = KeyPair::from_seed(Seed::try_generate().unwrap());
+        let noise = Noise::try_generate().unwrap();
+        let signature = kp.sk.sign(message, Some(noise));
+        assert!(kp.pk.verify(message, &signature).is_ok());
+    }
     assert_eq!(
         signature.as_ref(),
         [
@@ -418,15 +1487,171 @@ fn test_ed25519() {
             216, 171, 15, 188, 181, 136, 7,
         ]
     );
+    let options = SignOptions::default()
+        .with_self_verify(true)
+        .with_sign_twice(true);
+    assert_eq!(
+        kp.sk.sign_with_options(message, &options),
+        Ok(kp.sk.sign(message, None))
+    );
+}
+
+#[test]
+fn test_sign_options() {
+    // RFC 8032, section 7.2
+    let kp = KeyPair::from_seed(Seed::new([
+        0x03, 0x05, 0x33, 0x4e, 0x38, 0x1a, 0xf7, 0x8f, 0x14, 0x1c, 0xb6, 0x66, 0xf6, 0x19, 0x9f,
+        0x57, 0xbc, 0x34, 0x95, 0x33, 0x5a, 0x25, 0x6a, 0x95, 0xbd, 0x2a, 0x55, 0xbf, 0x54, 0x66,
+        0x63, 0xf6,
+    ]));
+    let message = [
+        0xf7, 0x26, 0x93, 0x6d, 0x19, 0xc8, 0x00, 0x49, 0x4e, 0x3f, 0xda, 0xff, 0x20, 0xb2, 0x76,
+        0xa8,
+    ];
+    let options = SignOptions::default().with_context(b"foo");
+    let signature = kp.sk.sign_with_options(message, &options).unwrap();
+    assert_eq!(
+        signature.as_ref(),
+        [
+            0x55, 0xa4, 0xcc, 0x2f, 0x70, 0xa5, 0x4e, 0x04, 0x28, 0x8c, 0x5f, 0x4c, 0xd1, 0xe4,
+            0x5a, 0x7b, 0xb5, 0x20, 0xb3, 0x62, 0x92, 0x91, 0x18, 0x76, 0xca, 0xda, 0x73, 0x23,
+            0x19, 0x8d, 0xd8, 0x7a, 0x8b, 0x36, 0x95, 0x0b, 0x95, 0x13, 0x00, 0x22, 0x90, 0x7a,
+            0x7f, 0xb7, 0xc4, 0xe9, 0xb2, 0xd5, 0xf6, 0xcc, 0xa6, 0x85, 0xa5, 0x87, 0xb4, 0xb2,
+            0x1f, 0x4b, 0x88, 0x8e, 0x4e, 0x7e, 0xdb, 0x0d,
+        ]
+    );
+    assert!(kp
+        .pk
+        .verify_with_options(message, &signature, &options)
+        .is_ok());
+    assert!(kp.pk.verify(message, &signature).is_err());
+
+    let options = options.prehashed();
+    let signature = kp.sk.sign_with_options(message, &options).unwrap();
+    assert_eq!(
+        kp.sk
+            .sign_prehashed(&sha512::Hash::hash(message), b"foo", None),
+        Ok(signature)
+    );
+    assert!(kp
+        .pk
+        .verify_with_options(message, &signature, &options)
+        .is_ok());
+
+    let options = SignOptions::default().with_context(&[0u8; 256]);
+    assert_eq!(
+        kp.sk.sign_with_options(message, &options),
+        Err(Error::InvalidContext)
+    );
+
+    let options = SignOptions::default().with_context(&[]);
+    assert_eq!(
+        kp.sk.sign_with_options(message, &options),
+        Err(Error::InvalidContext)
+    );
+    assert_eq!(
+        kp.pk.verify_with_options(message, &signature, &options),
+        Err(Error::InvalidContext)
+    );
+    let options = options.prehashed();
+    let signature = kp.sk.sign_with_options(message, &options).unwrap();
+    assert_eq!(
+        kp.sk
+            .sign_prehashed(&sha512::Hash::hash(message), b"", None),
+        Ok(signature)
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_signature_eq() {
+    use std::collections::HashSet;
+
+    let kp = KeyPair::from_seed([42u8; 32].into());
+    let signature = kp.sk.sign(b"message", None);
+    let mut bytes = *signature;
+    assert_eq!(signature, Signature::new(bytes));
+    bytes[63] ^= 1;
+    let other = Signature::new(bytes);
+    assert_ne!(signature, other);
+    let signatures: HashSet<Signature> = [signature, other, signature].iter().copied().collect();
+    assert_eq!(signatures.len(), 2);
+}
+
+#[test]
+fn test_derived_noise() {
+    let kp = KeyPair::from_seed([42u8; 32].into());
+    let noise = Noise::derive(&kp.sk, &1u64.to_le_bytes(), b"message");
+    assert_eq!(
+        noise,
+        Noise::derive(&kp.sk, &1u64.to_le_bytes(), b"message")
+    );
+    assert_ne!(
+        noise,
+        Noise::derive(&kp.sk, &2u64.to_le_bytes(), b"message")
+    );
+    assert_ne!(
+        noise,
+        Noise::derive(&kp.sk, &1u64.to_le_bytes(), b"other message")
+    );
+    assert_ne!(
+        Noise::derive(&kp.sk, b"ab", b"c"),
+        Noise::derive(&kp.sk, b"a", b"bc")
+    );
+    let signature = kp.sk.sign(b"message", Some(noise));
+    assert_eq!(signature, kp.sk.sign(b"message", Some(noise)));
+    assert_ne!(signature, kp.sk.sign(b"message", None));
+    assert!(kp.pk.verify(b"message", &signature).is_ok());
+}
+
+#[test]
+fn test_const_keys() {
+    // RFC 8032, section 7.1, test 1
+    static PK: PublicKey = PublicKey::new([
+        0xd7, 0x5a, 0x98, 0x01, 0x82, 0xb1, 0x0a, 0xb7, 0xd5, 0x4b, 0xfe, 0xd3, 0xc9, 0x64, 0x07,
+        0x3a, 0x0e, 0xe1, 0x72, 0xf3, 0xda, 0xa6, 0x23, 0x25, 0xaf, 0x02, 0x1a, 0x68, 0xf7, 0x07,
+        0x51, 0x1a,
+    ]);
+    static SIGNATURE: Signature = Signature::new([
+        0xe5, 0x56, 0x43, 0x00, 0xc3, 0x60, 0xac, 0x72, 0x90, 0x86, 0xe2, 0xcc, 0x80, 0x6e, 0x82,
+        0x8a, 0x84, 0x87, 0x7f, 0x1e, 0xb8, 0xe5, 0xd9, 0x74, 0xd8, 0x73, 0xe0, 0x65, 0x22, 0x49,
+        0x01, 0x55, 0x5f, 0xb8, 0x82, 0x15, 0x90, 0xa3, 0x3b, 0xac, 0xc6, 0x1e, 0x39, 0x70, 0x1c,
+        0xf9, 0xb4, 0x6b, 0xd2, 0x5b, 0xf5, 0xf0, 0x59, 0x5b, 0xbe, 0x24, 0x65, 0x51, 0x41, 0x43,
+        0x8e, 0x7a, 0x10, 0x0b,
+    ]);
+    assert!(PK.verify(b"", &SIGNATURE).is_ok());
+}
+
+#[test]
+fn test_verifying_state_serialization() {
+    let kp = KeyPair::from_seed([42u8; 32].into());
+    let message = [0x5au8; 1000];
+    let signature = kp.sk.sign(message, None);
+    let mut st = kp.pk.verify_incremental(&signature).unwrap();
+    st.absorb(&message[..300]);
+    let bytes = st.to_bytes();
+    let mut st = VerifyingState::from_bytes(&bytes).unwrap();
+    st.absorb(&message[300..]);
+    assert!(st.verify().is_ok());
+
+    let mut st = VerifyingState::from_bytes(&bytes).unwrap();
+    st.absorb(&message[301..]);
+    assert_eq!(st.verify(), Err(Error::SignatureMismatch));
 }
 
 #[cfg(feature = "blind-keys")]
 mod blind_keys {
     use super::*;
 
-    #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+    #[derive(Copy, Clone, Eq, PartialEq, Hash)]
     pub struct Blind([u8; Blind::BYTES]);
 
+    impl fmt::Debug for Blind {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            fmt_redacted(f, "Blind", &self.0)
+        }
+    }
+
     impl From<[u8; 32]> for Blind {
         fn from(blind: [u8; 32]) -> Self {
             Blind(blind)
@@ -453,22 +1678,28 @@ mod blind_keys {
         }
     }
 
-    #[cfg(feature = "random")]
+    #[cfg(any(feature = "random", feature = "entropy-source"))]
     impl Default for Blind {
         /// Generates a random blind.
         fn default() -> Self {
-            let mut blind = [0u8; Blind::BYTES];
-            getrandom::getrandom(&mut blind).expect("RNG failure");
-            Blind(blind)
+            Blind::try_generate().expect("RNG failure")
         }
     }
 
-    #[cfg(feature = "random")]
+    #[cfg(any(feature = "random", feature = "entropy-source"))]
     impl Blind {
         /// Generates a random blind.
         pub fn generate() -> Self {
             Blind::default()
         }
+
+        /// Generates a random blind, returning an error if the system RNG
+        /// is not available.
+        pub fn try_generate() -> Result<Self, Error> {
+            let mut blind = [0u8; Blind::BYTES];
+            crate::entropy::fill_random(&mut blind)?;
+            Ok(Blind(blind))
+        }
     }
 
     impl Deref for Blind {
@@ -514,10 +1745,10 @@ mod blind_keys {
 
         /// Unblinds a public key.
         pub fn unblind(&self, blind: &Blind, ctx: impl AsRef<[u8]>) -> Result<PublicKey, Error> {
-            let pk_p3 = GeP3::from_bytes_vartime(&self.0).ok_or(Error::InvalidPublicKey)?;
+            let pk_p3 = GeP3::from_bytes_vartime(&self.0).ok_or(Error::PublicKeyNotOnCurve)?;
             let mut hx = sha512::Hash::new();
             hx.update(&blind[..]);
-            hx.update(&[0u8]);
+            hx.update([0u8]);
             hx.update(ctx.as_ref());
             let hash_output = hx.finalize();
             let (blind_factor, _) = KeyPair::split(&hash_output, true, false);
@@ -549,13 +1780,19 @@ mod blind_keys {
     }
 
     /// A blind secret key.
-    #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+    #[derive(Copy, Clone, Eq, PartialEq, Hash)]
     pub struct BlindSecretKey {
         pub prefix: [u8; 2 * Seed::BYTES],
         pub blind_scalar: [u8; 32],
         pub blind_pk: BlindPublicKey,
     }
 
+    impl fmt::Debug for BlindSecretKey {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            fmt_redacted(f, "BlindSecretKey", &self.blind_scalar)
+        }
+    }
+
     #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
     pub struct BlindKeyPair {
         /// Public key part of the blind key pair.
@@ -568,45 +1805,50 @@ mod blind_keys {
         /// Computes a signature for the message `message` using the blind
         /// secret key. The noise parameter is optional, but recommended
         /// in order to mitigate fault attacks.
+        ///
+        /// Panics if the `self-verify` feature is enabled and the newly
+        /// created signature cannot be verified. Use `try_sign()` to handle
+        /// this case.
         pub fn sign(&self, message: impl AsRef<[u8]>, noise: Option<Noise>) -> Signature {
-            let nonce = {
-                let mut hasher = sha512::Hash::new();
-                if let Some(noise) = noise {
-                    hasher.update(&noise[..]);
-                    hasher.update(&self.prefix);
-                } else {
-                    hasher.update(&self.prefix);
-                }
-                hasher.update(&message);
-                let mut hash_output = hasher.finalize();
-                sc_reduce(&mut hash_output[0..64]);
-                hash_output
-            };
-            let mut signature: [u8; 64] = [0; 64];
-            let r = ge_scalarmult_base(&nonce[0..32]);
-            signature[0..32].copy_from_slice(&r.to_bytes()[..]);
-            signature[32..64].copy_from_slice(&self.blind_pk.0);
+            self.try_sign(message, noise)
+                .expect("Newly created signature cannot be verified")
+        }
+
+        /// Computes a signature for the message `message` using the blind
+        /// secret key, returning `Error::FaultDetected` instead of panicking
+        /// if the `self-verify` or `sign-twice` feature is enabled and a
+        /// fault is detected.
+        pub fn try_sign(
+            &self,
+            message: impl AsRef<[u8]>,
+            noise: Option<Noise>,
+        ) -> Result<Signature, Error> {
+            sign_with_secrets(
+                &PublicKey::new(self.blind_pk.0),
+                None,
+                &[message.as_ref()],
+                || self.secrets(message.as_ref(), noise),
+                cfg!(feature = "self-verify"),
+                cfg!(feature = "sign-twice"),
+            )
+        }
+
+        /// Returns the blind secret scalar, and the nonce for a signature.
+        fn secrets(&self, message: &[u8], noise: Option<Noise>) -> ([u8; 32], [u8; 32]) {
             let mut hasher = sha512::Hash::new();
-            hasher.update(signature.as_ref());
-            hasher.update(&message);
-            let mut hram = hasher.finalize();
-            sc_reduce(&mut hram);
-            sc_muladd(
-                &mut signature[32..64],
-                &hram[0..32],
-                &self.blind_scalar,
-                &nonce[0..32],
-            );
-            let signature = Signature(signature);
-
-            #[cfg(feature = "self-verify")]
-            {
-                PublicKey::from_slice(&self.blind_pk.0)
-                    .expect("Key length changed")
-                    .verify(message, &signature)
-                    .expect("Newly created signature cannot be verified");
+            if let Some(noise) = noise {
+                hasher.update(&noise[..]);
+                hasher.update(self.prefix);
+            } else {
+                hasher.update(self.prefix);
             }
-            signature
+            hasher.update(message);
+            let mut hash_output = hasher.finalize();
+            sc_reduce(&mut hash_output[0..64]);
+            let mut nonce = [0u8; 32];
+            nonce.copy_from_slice(&hash_output[0..32]);
+            wipe(&mut hash_output);
+            (self.blind_scalar, nonce)
         }
     }
 
@@ -616,12 +1858,12 @@ mod blind_keys {
             let (blind_factor, _prefix2) = {
                 let mut hx = sha512::Hash::new();
                 hx.update(&blind[..]);
-                hx.update(&[0u8]);
+                hx.update([0u8]);
                 hx.update(ctx.as_ref());
                 let hash_output = hx.finalize();
                 KeyPair::split(&hash_output, true, false)
             };
-            let pk_p3 = GeP3::from_bytes_vartime(&self.0).ok_or(Error::InvalidPublicKey)?;
+            let pk_p3 = GeP3::from_bytes_vartime(&self.0).ok_or(Error::PublicKeyNotOnCurve)?;
             Ok(BlindPublicKey(
                 ge_scalarmult(&blind_factor, &pk_p3).to_bytes(),
             ))
@@ -632,15 +1874,17 @@ mod blind_keys {
         /// Returns a blind version of the key pair.
         pub fn blind(&self, blind: &Blind, ctx: impl AsRef<[u8]>) -> BlindKeyPair {
             let seed = self.sk.seed();
-            let (scalar, prefix1) = {
-                let hash_output = sha512::Hash::hash(&seed[..]);
-                KeyPair::split(&hash_output, false, true)
+            let (mut scalar, mut prefix1) = {
+                let mut hash_output = sha512::Hash::hash(&seed[..]);
+                let split = KeyPair::split(&hash_output, false, true);
+                wipe(&mut hash_output);
+                split
             };
 
             let (blind_factor, prefix2) = {
                 let mut hx = sha512::Hash::new();
                 hx.update(&blind[..]);
-                hx.update(&[0u8]);
+                hx.update([0u8]);
                 hx.update(ctx.as_ref());
                 let hash_output = hx.finalize();
                 KeyPair::split(&hash_output, true, false)
@@ -652,6 +1896,8 @@ mod blind_keys {
             let mut prefix = [0u8; 2 * Seed::BYTES];
             prefix[0..32].copy_from_slice(&prefix1);
             prefix[32..64].copy_from_slice(&prefix2);
+            wipe(&mut scalar);
+            wipe(&mut prefix1);
             let blind_pk = BlindPublicKey::new(blind_pk);
 
             BlindKeyPair {
@@ -725,3 +1971,35 @@ fn test_blind_ed25519() {
     assert_eq!(Hex::decode_to_vec("947bacfabc63448f8955dc20630e069e58f37b72bb433ae17f2fa904ea860b44deb761705a3cc2168a6673ee0b41ff7765c7a4896941eec6833c1689315acb0b",
         None).unwrap(), signature.as_ref());
 }
+
+#[test]
+#[cfg(feature = "hedged")]
+fn test_hedged_ed25519() {
+    use ct_codecs::{Decoder, Hex};
+
+    let kp = KeyPair::from_seed(Seed::new([1u8; Seed::BYTES]));
+    let noise = HedgedNoise::new([2u8; HedgedNoise::BYTES]);
+    let signature = kp.sk.sign_hedged(b"hedged", noise).unwrap();
+    // Computed with an independent implementation of the draft, on top of
+    // the RFC 8032 reference code.
+    let expected = Hex::decode_to_vec(
+        "845e68499581540c4b3790af37e364447190cbc9e2b78c316f98a86b6ce97fc1\
+         1859c34411f18dcaadf72ffd99a0474c8b4dc04e36ca61227836be7ca6a3d90c",
+        None,
+    )
+    .unwrap();
+    assert_eq!(&signature[..], &expected[..]);
+    assert!(kp.pk.verify(b"hedged", &signature).is_ok());
+    let signature2 = kp
+        .sk
+        .sign_hedged(b"", HedgedNoise::new([3u8; HedgedNoise::BYTES]))
+        .unwrap();
+    let expected2 = Hex::decode_to_vec(
+        "c5e3a982c3cfe1054de82736792c67f1ffe3c88f24e86f7527f176caca08268b\
+         eb518d1f0b3a9ff0bb8c059bdbf369ce5530fcca45ff4ad667e0dd57327df40d",
+        None,
+    )
+    .unwrap();
+    assert_eq!(&signature2[..], &expected2[..]);
+    assert_ne!(signature, signature2);
+}
@@ -0,0 +1,615 @@
+//! Ed448 signatures (RFC 8032).
+//!
+//! The API mirrors the Ed25519 API of the crate. Signatures are
+//! deterministic unless noise is provided, and always include a context,
+//! empty by default.
+
+#![allow(clippy::needless_range_loop)]
+
+use core::fmt;
+use core::ops::Deref;
+
+use super::common::{fmt_redacted, wipe};
+use super::error::Error;
+use super::field448::*;
+use super::shake256;
+use super::Noise;
+
+// d = -39081
+const D: Fe = Fe([
+    0xffffffffff6756,
+    0xffffffffffffff,
+    0xffffffffffffff,
+    0xffffffffffffff,
+    0xfffffffffffffe,
+    0xffffffffffffff,
+    0xffffffffffffff,
+    0xffffffffffffff,
+]);
+
+const BASE_POINT: [u8; 57] = [
+    0x14, 0xfa, 0x30, 0xf2, 0x5b, 0x79, 0x08, 0x98, 0xad, 0xc8, 0xd7, 0x4e, 0x2c, 0x13, 0xbd, 0xfd,
+    0xc4, 0x39, 0x7c, 0xe6, 0x1c, 0xff, 0xd3, 0x3a, 0xd7, 0xc2, 0xa0, 0x05, 0x1e, 0x9c, 0x78, 0x87,
+    0x40, 0x98, 0xa3, 0x6c, 0x73, 0x73, 0xea, 0x4b, 0x62, 0xc7, 0xc9, 0x56, 0x37, 0x20, 0x76, 0x88,
+    0x24, 0xbc, 0xb6, 0x6e, 0x71, 0x46, 0x3f, 0x69, 0x00,
+];
+
+// The order of the prime-order subgroup
+const L: [u8; 57] = [
+    0xf3, 0x44, 0x58, 0xab, 0x92, 0xc2, 0x78, 0x23, 0x55, 0x8f, 0xc5, 0x8d, 0x72, 0xc2, 0x6c, 0x21,
+    0x90, 0x36, 0xd6, 0xae, 0x49, 0xdb, 0x4e, 0xc4, 0xe9, 0x23, 0xca, 0x7c, 0xff, 0xff, 0xff, 0xff,
+    0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+    0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x3f, 0x00,
+];
+
+/// A point in projective coordinates.
+#[derive(Clone, Copy)]
+struct Point {
+    x: Fe,
+    y: Fe,
+    z: Fe,
+}
+
+impl Point {
+    fn identity() -> Point {
+        Point {
+            x: FE_ZERO,
+            y: FE_ONE,
+            z: FE_ONE,
+        }
+    }
+
+    fn add(&self, other: &Point) -> Point {
+        let a = self.z * other.z;
+        let b = a.square();
+        let c = self.x * other.x;
+        let d = self.y * other.y;
+        let e = D * c * d;
+        let f = b - e;
+        let g = b + e;
+        let h = (self.x + self.y) * (other.x + other.y);
+        Point {
+            x: a * f * (h - c - d),
+            y: a * g * (d - c),
+            z: f * g,
+        }
+    }
+
+    fn double(&self) -> Point {
+        let b = (self.x + self.y).square();
+        let c = self.x.square();
+        let d = self.y.square();
+        let e = c + d;
+        let h = self.z.square();
+        let j = e - (h + h);
+        Point {
+            x: (b - e) * j,
+            y: e * (c - d),
+            z: e * j,
+        }
+    }
+
+    fn neg(&self) -> Point {
+        Point {
+            x: self.x.neg(),
+            y: self.y,
+            z: self.z,
+        }
+    }
+
+    fn cswap(a: &mut Point, b: &mut Point, c: u8) {
+        Fe::cswap(&mut a.x, &mut b.x, c);
+        Fe::cswap(&mut a.y, &mut b.y, c);
+        Fe::cswap(&mut a.z, &mut b.z, c);
+    }
+
+    /// Computes `scalar·self` in constant time.
+    fn mul(&self, scalar: &[u8; 57]) -> Point {
+        let mut r0 = Point::identity();
+        let mut r1 = *self;
+        let mut swap = 0u8;
+        for i in (0..456).rev() {
+            let bit = (scalar[i >> 3] >> (i & 7)) & 1;
+            Point::cswap(&mut r0, &mut r1, swap ^ bit);
+            swap = bit;
+            r1 = r0.add(&r1);
+            r0 = r0.double();
+        }
+        Point::cswap(&mut r0, &mut r1, swap);
+        r0
+    }
+
+    fn base() -> Point {
+        Point::from_bytes(&BASE_POINT).expect("Invalid base point")
+    }
+
+    fn from_bytes(s: &[u8; 57]) -> Option<Point> {
+        if s[56] & 0x7f != 0 {
+            return None;
+        }
+        let mut y_bytes = [0u8; 56];
+        y_bytes.copy_from_slice(&s[0..56]);
+        Fe::reject_noncanonical(&y_bytes).ok()?;
+        let y = Fe::from_bytes(&y_bytes);
+        let yy = y.square();
+        let u = yy - FE_ONE;
+        let v = D * yy - FE_ONE;
+        // x = sqrt(u / v) = u^3 v (u^5 v^3)^((p - 3) / 4)
+        let u3v = u.square() * u * v;
+        let mut x = u3v * (u3v * u.square() * v.square()).pow_p34();
+        if (v * x.square() - u).is_nonzero() {
+            return None;
+        }
+        let sign = s[56] >> 7;
+        if !x.is_nonzero() && sign != 0 {
+            return None;
+        }
+        if x.is_negative() as u8 != sign {
+            x = x.neg();
+        }
+        Some(Point { x, y, z: FE_ONE })
+    }
+
+    fn to_bytes(&self) -> [u8; 57] {
+        let z_inv = self.z.invert();
+        let x = self.x * z_inv;
+        let y = self.y * z_inv;
+        let mut s = [0u8; 57];
+        s[0..56].copy_from_slice(&y.to_bytes());
+        s[56] = (x.is_negative() as u8) << 7;
+        s
+    }
+}
+
+/// Computes `input mod L`, `input` being a little-endian number.
+fn sc_reduce(input: &[u8]) -> [u8; 57] {
+    let mut l = [0u64; 8];
+    let mut acc = [0u64; 8];
+    for i in 0..57 {
+        l[i / 8] |= (L[i] as u64) << (8 * (i % 8));
+    }
+    for i in (0..input.len() * 8).rev() {
+        // acc = 2·acc + bit, then subtract L if acc >= L.
+        let mut carry = ((input[i >> 3] >> (i & 7)) & 1) as u64;
+        for limb in acc.iter_mut() {
+            let next = *limb >> 63;
+            *limb = (*limb << 1) | carry;
+            carry = next;
+        }
+        let mut t = [0u64; 8];
+        let mut borrow = 0u64;
+        for j in 0..8 {
+            let (x, b1) = acc[j].overflowing_sub(l[j]);
+            let (x, b2) = x.overflowing_sub(borrow);
+            t[j] = x;
+            borrow = (b1 | b2) as u64;
+        }
+        let keep = borrow.wrapping_neg();
+        for j in 0..8 {
+            acc[j] = (acc[j] & keep) | (t[j] & !keep);
+        }
+    }
+    let mut out = [0u8; 57];
+    for i in 0..57 {
+        out[i] = (acc[i / 8] >> (8 * (i % 8))) as u8;
+    }
+    out
+}
+
+/// Computes `(a·b + c) mod L`.
+fn sc_muladd(a: &[u8; 57], b: &[u8; 57], c: &[u8; 57]) -> [u8; 57] {
+    let mut t = [0u64; 29];
+    for i in 0..57 {
+        t[i / 4] += (c[i] as u64) << (8 * (i % 4));
+        for j in 0..57 {
+            t[(i + j) / 4] += ((a[i] as u64) * (b[j] as u64)) << (8 * ((i + j) % 4));
+        }
+    }
+    let mut wide = [0u8; 116];
+    let mut carry = 0u64;
+    for i in 0..29 {
+        let x = t[i] + carry;
+        wide[i * 4..i * 4 + 4].copy_from_slice(&(x as u32).to_le_bytes());
+        carry = x >> 32;
+    }
+    sc_reduce(&wide)
+}
+
+fn sc_is_canonical(s: &[u8; 57]) -> bool {
+    let mut borrow = 0i16;
+    for i in 0..57 {
+        borrow = (s[i] as i16 - L[i] as i16 + borrow) >> 8;
+    }
+    borrow != 0
+}
+
+fn dom4(hasher: &mut shake256::Hash, context: &[u8]) {
+    hasher.update(b"SigEd448");
+    hasher.update([0u8, context.len() as u8]);
+    hasher.update(context);
+}
+
+/// A public key.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct PublicKey([u8; PublicKey::BYTES]);
+
+impl PublicKey {
+    /// Number of raw bytes in a public key.
+    pub const BYTES: usize = 57;
+
+    /// Creates a public key from raw bytes.
+    pub fn new(pk: [u8; PublicKey::BYTES]) -> Self {
+        PublicKey(pk)
+    }
+
+    /// Creates a public key from a slice.
+    pub fn from_slice(pk: &[u8]) -> Result<Self, Error> {
+        let mut pk_ = [0u8; PublicKey::BYTES];
+        if pk.len() != pk_.len() {
+            return Err(Error::InvalidPublicKey);
+        }
+        pk_.copy_from_slice(pk);
+        Ok(PublicKey::new(pk_))
+    }
+
+    /// Verifies that the signature `signature` is valid for the message
+    /// `message`.
+    pub fn verify(&self, message: impl AsRef<[u8]>, signature: &Signature) -> Result<(), Error> {
+        self.verify_with_context(message, b"", signature)
+    }
+
+    /// Verifies that the signature `signature` is valid for the message
+    /// `message` and the context `context`.
+    pub fn verify_with_context(
+        &self,
+        message: impl AsRef<[u8]>,
+        context: &[u8],
+        signature: &Signature,
+    ) -> Result<(), Error> {
+        if context.len() > 255 {
+            return Err(Error::InvalidContext);
+        }
+        let mut s = [0u8; 57];
+        s.copy_from_slice(&signature[57..114]);
+        if !sc_is_canonical(&s) {
+            return Err(Error::NonCanonicalScalar);
+        }
+        let a = Point::from_bytes(&self.0).ok_or(Error::PublicKeyNotOnCurve)?;
+        if !(a.x.is_nonzero() || (a.y - FE_ONE).is_nonzero()) {
+            return Err(Error::WeakPublicKey);
+        }
+        let mut hasher = shake256::Hash::new();
+        dom4(&mut hasher, context);
+        hasher.update(&signature[0..57]);
+        hasher.update(self.0);
+        hasher.update(message);
+        let k = sc_reduce(&hasher.finalize());
+        let r = Point::base().mul(&s).add(&a.neg().mul(&k)).to_bytes();
+        if r[..] != signature[0..57] {
+            return Err(Error::SignatureMismatch);
+        }
+        Ok(())
+    }
+}
+
+impl Deref for PublicKey {
+    type Target = [u8; PublicKey::BYTES];
+
+    /// Returns a public key as bytes.
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+/// A seed, from which a key pair can be derived.
+#[derive(Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Seed([u8; Seed::BYTES]);
+
+impl fmt::Debug for Seed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_redacted(f, "Seed", &self.0)
+    }
+}
+
+impl Seed {
+    /// Number of raw bytes in a seed.
+    pub const BYTES: usize = 57;
+
+    /// Creates a seed from raw bytes.
+    pub fn new(seed: [u8; Seed::BYTES]) -> Self {
+        Seed(seed)
+    }
+
+    /// Creates a seed from a slice.
+    pub fn from_slice(seed: &[u8]) -> Result<Self, Error> {
+        let mut seed_ = [0u8; Seed::BYTES];
+        if seed.len() != seed_.len() {
+            return Err(Error::InvalidSeed);
+        }
+        seed_.copy_from_slice(seed);
+        Ok(Seed::new(seed_))
+    }
+
+    /// Generates a random seed.
+    #[cfg(any(feature = "random", feature = "entropy-source"))]
+    pub fn generate() -> Self {
+        Seed::try_generate().expect("RNG failure")
+    }
+
+    /// Generates a random seed, returning an error if the system RNG is not
+    /// available.
+    #[cfg(any(feature = "random", feature = "entropy-source"))]
+    pub fn try_generate() -> Result<Self, Error> {
+        let mut seed = [0u8; Seed::BYTES];
+        crate::entropy::fill_random(&mut seed)?;
+        Ok(Seed(seed))
+    }
+}
+
+impl Deref for Seed {
+    type Target = [u8; Seed::BYTES];
+
+    /// Returns a seed as raw bytes.
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+/// A secret key: the seed, followed by the public key.
+#[derive(Copy, Clone, Eq, PartialEq, Hash)]
+pub struct SecretKey([u8; SecretKey::BYTES]);
+
+impl fmt::Debug for SecretKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_redacted(f, "SecretKey", &self.0)
+    }
+}
+
+impl SecretKey {
+    /// Number of raw bytes in a secret key.
+    pub const BYTES: usize = Seed::BYTES + PublicKey::BYTES;
+
+    /// Creates a secret key from raw bytes.
+    pub fn new(sk: [u8; SecretKey::BYTES]) -> Self {
+        SecretKey(sk)
+    }
+
+    /// Creates a secret key from a slice.
+    pub fn from_slice(sk: &[u8]) -> Result<Self, Error> {
+        let mut sk_ = [0u8; SecretKey::BYTES];
+        if sk.len() != sk_.len() {
+            return Err(Error::InvalidSecretKey);
+        }
+        sk_.copy_from_slice(sk);
+        Ok(SecretKey::new(sk_))
+    }
+
+    /// Returns the seed of the secret key.
+    pub fn seed(&self) -> Seed {
+        Seed::from_slice(&self.0[0..Seed::BYTES]).unwrap()
+    }
+
+    /// Returns the public key corresponding to the secret key.
+    pub fn public_key(&self) -> PublicKey {
+        PublicKey::from_slice(&self.0[Seed::BYTES..]).unwrap()
+    }
+
+    /// Computes a signature for the message `message`.
+    ///
+    /// The noise parameter is optional, but recommended in order to mitigate
+    /// fault attacks, like with Ed25519.
+    pub fn sign(&self, message: impl AsRef<[u8]>, noise: Option<Noise>) -> Signature {
+        self.sign_with_context(message, b"", noise)
+            .expect("Empty context")
+    }
+
+    /// Computes a signature for the message `message` and the context
+    /// `context`, which can be up to 255 bytes long.
+    pub fn sign_with_context(
+        &self,
+        message: impl AsRef<[u8]>,
+        context: &[u8],
+        noise: Option<Noise>,
+    ) -> Result<Signature, Error> {
+        if context.len() > 255 {
+            return Err(Error::InvalidContext);
+        }
+        let message = message.as_ref();
+        let (mut scalar, mut h) = expand(&self.0[0..Seed::BYTES]);
+        let mut hasher = shake256::Hash::new();
+        dom4(&mut hasher, context);
+        match noise {
+            None => hasher.update(&h[57..114]),
+            Some(noise) => {
+                hasher.update(&noise[..]);
+                hasher.update(&h[..]);
+            }
+        }
+        hasher.update(message);
+        let mut nonce_hash = hasher.finalize();
+        let mut nonce = sc_reduce(&nonce_hash);
+        let r = Point::base().mul(&nonce).to_bytes();
+        let mut hasher = shake256::Hash::new();
+        dom4(&mut hasher, context);
+        hasher.update(r);
+        hasher.update(&self.0[Seed::BYTES..]);
+        hasher.update(message);
+        let k = sc_reduce(&hasher.finalize());
+        let s = sc_muladd(&k, &scalar, &nonce);
+        wipe(&mut scalar);
+        wipe(&mut h);
+        wipe(&mut nonce_hash);
+        wipe(&mut nonce);
+        let mut signature = [0u8; Signature::BYTES];
+        signature[0..57].copy_from_slice(&r);
+        signature[57..114].copy_from_slice(&s);
+        Ok(Signature(signature))
+    }
+}
+
+impl Deref for SecretKey {
+    type Target = [u8; SecretKey::BYTES];
+
+    /// Returns a secret key as raw bytes.
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+/// Hashes a seed into a clamped secret scalar and a nonce prefix.
+fn expand(seed: &[u8]) -> ([u8; 57], [u8; 114]) {
+    let mut hasher = shake256::Hash::new();
+    hasher.update(seed);
+    let h = hasher.finalize();
+    let mut scalar = [0u8; 57];
+    scalar.copy_from_slice(&h[0..57]);
+    scalar[0] &= 252;
+    scalar[55] |= 128;
+    scalar[56] = 0;
+    (scalar, h)
+}
+
+/// A key pair.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct KeyPair {
+    /// Public key part of the key pair.
+    pub pk: PublicKey,
+    /// Secret key part of the key pair.
+    pub sk: SecretKey,
+}
+
+impl KeyPair {
+    /// Number of bytes in a key pair.
+    pub const BYTES: usize = SecretKey::BYTES;
+
+    /// Derives a key pair from a seed.
+    pub fn from_seed(seed: Seed) -> KeyPair {
+        let (mut scalar, mut h) = expand(&seed[..]);
+        let pk = Point::base().mul(&scalar).to_bytes();
+        wipe(&mut scalar);
+        wipe(&mut h);
+        let mut sk = [0u8; SecretKey::BYTES];
+        sk[0..Seed::BYTES].copy_from_slice(&seed[..]);
+        sk[Seed::BYTES..].copy_from_slice(&pk);
+        KeyPair {
+            pk: PublicKey(pk),
+            sk: SecretKey(sk),
+        }
+    }
+
+    /// Generates a new key pair.
+    #[cfg(any(feature = "random", feature = "entropy-source"))]
+    pub fn generate() -> KeyPair {
+        KeyPair::from_seed(Seed::generate())
+    }
+
+    /// Creates a key pair from a secret key, checking that the public key
+    /// matches the seed.
+    pub fn from_secret_key(sk: SecretKey) -> Result<KeyPair, Error> {
+        let kp = KeyPair::from_seed(sk.seed());
+        if kp.pk != sk.public_key() {
+            return Err(Error::InvalidPublicKey);
+        }
+        Ok(kp)
+    }
+}
+
+impl Deref for KeyPair {
+    type Target = [u8; KeyPair::BYTES];
+
+    /// Returns a key pair as bytes.
+    fn deref(&self) -> &Self::Target {
+        &self.sk
+    }
+}
+
+/// An Ed448 signature.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Signature([u8; Signature::BYTES]);
+
+impl Signature {
+    /// Number of raw bytes in a signature.
+    pub const BYTES: usize = 114;
+
+    /// Creates a signature from raw bytes.
+    pub fn new(bytes: [u8; Signature::BYTES]) -> Self {
+        Signature(bytes)
+    }
+
+    /// Creates a signature from a slice.
+    pub fn from_slice(signature: &[u8]) -> Result<Self, Error> {
+        let mut signature_ = [0u8; Signature::BYTES];
+        if signature.len() != signature_.len() {
+            return Err(Error::InvalidSignature);
+        }
+        signature_.copy_from_slice(signature);
+        Ok(Signature::new(signature_))
+    }
+}
+
+impl Deref for Signature {
+    type Target = [u8; Signature::BYTES];
+
+    /// Returns a signature as bytes.
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+#[test]
+fn test_ed448() {
+    use ct_codecs::{Decoder, Hex};
+
+    // RFC 8032, section 7.4
+    let seed = Hex::decode_to_vec(
+        "c4eab05d357007c632f3dbb48489924d552b08fe0c353a0d4a1f00acda2c463a\
+         fbea67c5e8d2877c5e3bc397a659949ef8021e954e0a12274e",
+        None,
+    )
+    .unwrap();
+    let kp = KeyPair::from_seed(Seed::from_slice(&seed).unwrap());
+    assert_eq!(
+        kp.pk,
+        PublicKey::from_slice(
+            &Hex::decode_to_vec(
+                "43ba28f430cdff456ae531545f7ecd0ac834a55d9358c0372bfa0c6c6798c086\
+                 6aea01eb00742802b8438ea4cb82169c235160627b4c3a9480",
+                None,
+            )
+            .unwrap()
+        )
+        .unwrap()
+    );
+    let expected = Hex::decode_to_vec(
+        "26b8f91727bd62897af15e41eb43c377efb9c610d48f2335cb0bd0087810f435\
+         2541b143c4b981b7e18f62de8ccdf633fc1bf037ab7cd779805e0dbcc0aae1cb\
+         cee1afb2e027df36bc04dcecbf154336c19f0af7e0a6472905e799f1953d2a0f\
+         f3348ab21aa4adafd1d234441cf807c03a00",
+        None,
+    )
+    .unwrap();
+    let signature = kp.sk.sign([0x03], None);
+    assert_eq!(&signature[..], &expected[..]);
+    let noisy_signature = kp.sk.sign([0x03], Some(Noise::new([1u8; Noise::BYTES])));
+    assert_ne!(noisy_signature, signature);
+    assert!(kp.pk.verify([0x03], &noisy_signature).is_ok());
+    assert!(kp.pk.verify([0x03], &signature).is_ok());
+    assert!(kp.pk.verify([0x04], &signature).is_err());
+
+    let expected = Hex::decode_to_vec(
+        "d4f8f6131770dd46f40867d6fd5d5055de43541f8c5e35abbcd001b32a89f7d2\
+         151f7647f11d8ca2ae279fb842d607217fce6e042f6815ea000c85741de5c8da\
+         1144a6a1aba7f96de42505d7a7298524fda538fccbbb754f578c1cad10d54d0d\
+         5428407e85dcbc98a49155c13764e66c3c00",
+        None,
+    )
+    .unwrap();
+    let signature = kp.sk.sign_with_context([0x03], b"foo", None).unwrap();
+    assert_eq!(&signature[..], &expected[..]);
+    assert!(kp
+        .pk
+        .verify_with_context([0x03], b"foo", &signature)
+        .is_ok());
+    assert!(kp.pk.verify([0x03], &signature).is_err());
+    assert_eq!(KeyPair::from_secret_key(kp.sk), Ok(kp));
+}
@@ -1,3 +1,4 @@
+#[cfg(not(feature = "low-stack"))]
 use core::cmp::min;
 use core::ops::{Add, Sub};
 
@@ -107,6 +108,7 @@ impl GeP2 {
         }
     }
 
+    #[cfg(not(feature = "low-stack"))]
     fn slide(a: &[u8]) -> [i8; 256] {
         let mut r = [0i8; 256];
         for i in 0..256 {
@@ -139,27 +141,25 @@ impl GeP2 {
         r
     }
 
+    #[cfg(not(feature = "low-stack"))]
     #[allow(clippy::comparison_chain)]
     pub fn double_scalarmult_vartime(a_scalar: &[u8], a_point: GeP3, b_scalar: &[u8]) -> GeP2 {
+        GeP2::double_scalarmult_multiples_vartime(a_scalar, &a_point.odd_multiples(), b_scalar)
+    }
+
+    /// Same as `double_scalarmult_vartime()`, with the odd multiples of the
+    /// point computed by `GeP3::odd_multiples()`, so that they can be reused
+    /// across computations with the same point.
+    #[cfg(not(feature = "low-stack"))]
+    #[allow(clippy::comparison_chain)]
+    pub fn double_scalarmult_multiples_vartime(
+        a_scalar: &[u8],
+        ai: &[GeCached; 8],
+        b_scalar: &[u8],
+    ) -> GeP2 {
         let aslide = GeP2::slide(a_scalar);
         let bslide = GeP2::slide(b_scalar);
 
-        let mut ai = [GeCached {
-            y_plus_x: FE_ZERO,
-            y_minus_x: FE_ZERO,
-            z: FE_ZERO,
-            t2d: FE_ZERO,
-        }; 8]; // A,3A,5A,7A,9A,11A,13A,15A
-        ai[0] = a_point.to_cached();
-        let a2 = a_point.dbl().to_p3();
-        ai[1] = (a2 + ai[0]).to_p3().to_cached();
-        ai[2] = (a2 + ai[1]).to_p3().to_cached();
-        ai[3] = (a2 + ai[2]).to_p3().to_cached();
-        ai[4] = (a2 + ai[3]).to_p3().to_cached();
-        ai[5] = (a2 + ai[4]).to_p3().to_cached();
-        ai[6] = (a2 + ai[5]).to_p3().to_cached();
-        ai[7] = (a2 + ai[6]).to_p3().to_cached();
-
         let mut r = GeP2::zero();
 
         let mut i: usize = 255;
@@ -195,9 +195,50 @@ impl GeP2 {
             i -= 1;
         }
     }
+
+    /// Computes `a_scalar·a_point + b_scalar·B` with a plain double-and-add
+    /// loop. This is slower than the sliding window method, but doesn't
+    /// require any tables on the stack.
+    #[cfg(feature = "low-stack")]
+    pub fn double_scalarmult_vartime(a_scalar: &[u8], a_point: GeP3, b_scalar: &[u8]) -> GeP2 {
+        let a = a_point.to_cached();
+        let mut r = GeP2::zero();
+        for i in (0..256).rev() {
+            let mut t = r.dbl();
+            if (a_scalar[i >> 3] >> (i & 7)) & 1 != 0 {
+                t = t.to_p3() + a;
+            }
+            if (b_scalar[i >> 3] >> (i & 7)) & 1 != 0 {
+                t = t.to_p3() + BI[0];
+            }
+            r = t.to_p2();
+        }
+        r
+    }
 }
 
 impl GeP3 {
+    /// Returns A,3A,5A,7A,9A,11A,13A,15A.
+    #[cfg(not(feature = "low-stack"))]
+    pub fn odd_multiples(&self) -> [GeCached; 8] {
+        let mut ai = [GeCached {
+            y_plus_x: FE_ZERO,
+            y_minus_x: FE_ZERO,
+            z: FE_ZERO,
+            t2d: FE_ZERO,
+        }; 8];
+        ai[0] = self.to_cached();
+        let a2 = self.dbl().to_p3();
+        ai[1] = (a2 + ai[0]).to_p3().to_cached();
+        ai[2] = (a2 + ai[1]).to_p3().to_cached();
+        ai[3] = (a2 + ai[2]).to_p3().to_cached();
+        ai[4] = (a2 + ai[3]).to_p3().to_cached();
+        ai[5] = (a2 + ai[4]).to_p3().to_cached();
+        ai[6] = (a2 + ai[5]).to_p3().to_cached();
+        ai[7] = (a2 + ai[6]).to_p3().to_cached();
+        ai
+    }
+
     pub fn from_bytes_negate_vartime(s: &[u8; 32]) -> Option<GeP3> {
         let y = Fe::from_bytes(s);
         let z = FE_ONE;
@@ -225,7 +266,7 @@ impl GeP3 {
         Some(GeP3 { x, y, z, t })
     }
 
-    #[cfg(feature = "blind-keys")]
+    #[cfg(any(feature = "blind-keys", feature = "hazmat", feature = "anti-exfil"))]
     pub fn from_bytes_vartime(s: &[u8; 32]) -> Option<GeP3> {
         Self::from_bytes_negate_vartime(s).map(|p| GeP3 {
             x: p.x.neg(),
@@ -265,6 +306,14 @@ impl GeP3 {
         self.to_p2().dbl()
     }
 
+    /// Returns `true` if the point is in the small-order subgroup, without
+    /// any field inversion.
+    #[cfg(any(all(feature = "x25519", feature = "alloc"), feature = "diagnostics"))]
+    pub fn has_small_order(&self) -> bool {
+        let p8 = self.dbl().to_p2().dbl().to_p2().dbl().to_p2();
+        !p8.x.is_nonzero() && !(p8.y - p8.z).is_nonzero()
+    }
+
     pub fn to_bytes(&self) -> [u8; 32] {
         let recip = self.z.invert();
         let x = self.x * recip;
@@ -381,7 +430,7 @@ fn ge_precompute(base: &GeP3) -> [GeCached; 16] {
         pc[i] = if i % 2 == 0 {
             pc[i / 2].dbl().to_p3()
         } else {
-            pc[(i - 1)].add(base_cached).to_p3()
+            pc[i - 1].add(base_cached).to_p3()
         }
     }
     let mut pc_cached: [GeCached; 16] = Default::default();
@@ -411,6 +460,11 @@ pub fn ge_scalarmult(scalar: &[u8], base: &GeP3) -> GeP3 {
     q
 }
 
+#[cfg(any(feature = "hazmat", feature = "anti-exfil", feature = "diagnostics"))]
+pub fn ge_add(a: &GeP3, b: &GeP3) -> GeP3 {
+    (*a + b.to_cached()).to_p3()
+}
+
 pub fn ge_scalarmult_base(scalar: &[u8]) -> GeP3 {
     const BXP: [u8; 32] = [
         0x1a, 0xd5, 0x25, 0x8f, 0x60, 0x2d, 0x56, 0xc9, 0xb2, 0xa7, 0x25, 0x95, 0x60, 0xc7, 0x2c,
@@ -767,6 +821,7 @@ pub fn sc_sqmul(s: &[u8], n: usize, a: &[u8]) -> [u8; 32] {
 }
 
 #[cfg(feature = "blind-keys")]
+#[allow(clippy::just_underscores_and_digits)]
 pub fn sc_invert(s: &[u8; 32]) -> [u8; 32] {
     let _10 = sc_sq(s);
     let _11 = sc_mul(s, &_10);
@@ -1294,7 +1349,7 @@ pub fn sc_reject_noncanonical(s: &[u8]) -> Result<(), Error> {
         0x00, 0x10,
     ];
     if s.len() != 32 {
-        panic!("Invalid compressed length")
+        return Err(Error::NonCanonicalScalar);
     }
     let mut c: u8 = 0;
     let mut n: u8 = 1;
@@ -1311,7 +1366,7 @@ pub fn sc_reject_noncanonical(s: &[u8]) -> Result<(), Error> {
     if c != 0 {
         Ok(())
     } else {
-        Err(Error::NonCanonical)
+        Err(Error::NonCanonicalScalar)
     }
 }
 
@@ -1324,6 +1379,17 @@ pub fn is_identity(s: &[u8; 32]) -> bool {
     c == 0
 }
 
+/// Odd multiples of the base point, used by signature verification.
+///
+/// The section name is only valid for ELF. Mach-O requires a
+/// `segment,section` name, and COFF and WebAssembly have no use for it.
+#[cfg_attr(
+    all(
+        feature = "tables-link-section",
+        not(any(target_vendor = "apple", target_os = "windows", target_family = "wasm"))
+    ),
+    link_section = ".rodata.ed25519_compact_tables"
+)]
 static BI: [GePrecomp; 8] = [
     GePrecomp {
         y_plus_x: Fe([
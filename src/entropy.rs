@@ -0,0 +1,86 @@
+#[cfg(feature = "entropy-source")]
+use core::mem;
+#[cfg(feature = "entropy-source")]
+use core::ptr;
+#[cfg(feature = "entropy-source")]
+use core::sync::atomic::{AtomicPtr, Ordering};
+
+use super::error::Error;
+
+/// A source of random bytes, such as a hardware TRNG peripheral.
+///
+/// Once registered with `set_entropy_source()`, it is used by all the
+/// functions generating keys, seeds and noise, instead of `getrandom`.
+#[cfg(feature = "entropy-source")]
+pub trait EntropySource {
+    /// Fills `buf` with random bytes, or returns `Error::RngFailure`.
+    fn fill_bytes(buf: &mut [u8]) -> Result<(), Error>;
+}
+
+#[cfg(feature = "entropy-source")]
+type FillBytes = fn(&mut [u8]) -> Result<(), Error>;
+
+#[cfg(feature = "entropy-source")]
+static ENTROPY_SOURCE: AtomicPtr<()> = AtomicPtr::new(ptr::null_mut());
+
+/// Registers `E` as the source of random bytes, replacing the previously
+/// registered one, if any.
+///
+/// Without a registered source, `getrandom` is used if the `random` feature
+/// is enabled, and random generation fails otherwise.
+#[cfg(feature = "entropy-source")]
+pub fn set_entropy_source<E: EntropySource>() {
+    let fill_bytes: FillBytes = E::fill_bytes;
+    ENTROPY_SOURCE.store(fill_bytes as *mut (), Ordering::Release);
+}
+
+/// Unregisters the source of random bytes.
+#[cfg(feature = "entropy-source")]
+pub fn clear_entropy_source() {
+    ENTROPY_SOURCE.store(ptr::null_mut(), Ordering::Release);
+}
+
+pub(crate) fn fill_random(buf: &mut [u8]) -> Result<(), Error> {
+    #[cfg(feature = "entropy-source")]
+    {
+        let fill_bytes = ENTROPY_SOURCE.load(Ordering::Acquire);
+        if !fill_bytes.is_null() {
+            let fill_bytes: FillBytes = unsafe { mem::transmute(fill_bytes) };
+            return fill_bytes(buf);
+        }
+    }
+    #[cfg(feature = "random")]
+    {
+        getrandom::getrandom(buf).map_err(|_| Error::RngFailure)
+    }
+    #[cfg(not(feature = "random"))]
+    {
+        let _ = buf;
+        Err(Error::RngFailure)
+    }
+}
+
+#[cfg(feature = "entropy-source")]
+#[test]
+fn test_entropy_source() {
+    use core::sync::atomic::AtomicBool;
+
+    use super::Seed;
+
+    static USED: AtomicBool = AtomicBool::new(false);
+
+    struct Trng;
+
+    impl EntropySource for Trng {
+        fn fill_bytes(buf: &mut [u8]) -> Result<(), Error> {
+            USED.store(true, Ordering::Relaxed);
+            getrandom::getrandom(buf).map_err(|_| Error::RngFailure)
+        }
+    }
+
+    set_entropy_source::<Trng>();
+    let seed = Seed::try_generate().unwrap();
+    clear_entropy_source();
+    assert!(USED.load(Ordering::Relaxed));
+    assert_ne!(seed, Seed::try_generate().unwrap());
+}
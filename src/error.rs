@@ -1,45 +1,226 @@
 use core::fmt::{self, Display};
+use core::mem;
 
+/// Errors returned by this crate.
+///
+/// Signature verification reports each failed check with a distinct variant
+/// (`NonCanonicalScalar`, `WeakPublicKey`, `PublicKeyNotOnCurve`,
+/// `SignatureMismatch`), and PEM/DER parsing errors carry the lengths or the
+/// position that caused them.
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Error), uniffi(flat_error))]
+#[repr(u8)]
+#[non_exhaustive]
 pub enum Error {
     /// The signature doesn't verify.
-    SignatureMismatch,
+    SignatureMismatch = 1,
     /// A weak public key was used.
-    WeakPublicKey,
+    WeakPublicKey = 2,
     /// The public key is invalid.
-    InvalidPublicKey,
+    InvalidPublicKey = 3,
     /// The secret key is invalid.
-    InvalidSecretKey,
+    InvalidSecretKey = 4,
     /// The signature is invalid.
-    InvalidSignature,
-    /// The seed doesn't have the expected length.
-    InvalidSeed,
+    InvalidSignature = 5,
+    /// The seed doesn't have the expected length, or can't be used to derive
+    /// a key (for example, it is all zeros).
+    InvalidSeed = 6,
     /// The blind doesn't have the expected length.
-    InvalidBlind,
+    InvalidBlind = 7,
     /// The noise doesn't have the expected length.
-    InvalidNoise,
+    InvalidNoise = 8,
     /// Parse error
-    ParseError,
+    ParseError = 9,
     /// Non-canonical encoding
-    NonCanonical,
+    NonCanonical = 10,
+    /// The signature scalar is not reduced modulo the group order
+    NonCanonicalScalar = 11,
+    /// The public key doesn't encode a point on the curve
+    PublicKeyNotOnCurve = 12,
+    /// The PEM document doesn't contain the expected begin marker
+    PemMissingHeader = 13,
+    /// The PEM document doesn't contain the expected end marker
+    PemMissingFooter = 14,
+    /// The PEM document contains invalid Base64 data
+    InvalidBase64 = 15,
+    /// The DER document doesn't have the expected length
+    DerInvalidLength {
+        /// The expected length, in bytes.
+        expected: usize,
+        /// The actual length, in bytes.
+        actual: usize,
+    } = 16,
+    /// The DER document doesn't have the expected structure or algorithm
+    DerUnexpectedHeader {
+        /// The offset of the first unexpected byte.
+        offset: usize,
+    } = 17,
+    /// A fault was detected while computing a signature
+    FaultDetected = 18,
+    /// The random number generator failed
+    RngFailure = 19,
+    /// A known-answer self test failed
+    SelfTestFailed = 20,
+    /// Secret memory couldn't be allocated or locked
+    MemoryLockFailed = 21,
+    /// Invalid context: empty or longer than 255 bytes.
+    InvalidContext = 22,
+    /// An external signer failed to compute a signature.
+    SignerFailure = 23,
+    /// The certificate or its parameters are invalid.
+    InvalidCertificate = 24,
+    /// The token or certificate has expired.
+    Expired = 25,
+    /// The key has been revoked.
+    KeyRevoked = 26,
+    /// The operation is not allowed by the signing policy.
+    PolicyViolation = 27,
+    /// The output buffer is too small.
+    BufferTooSmall = 28,
+    /// A line of a PEM document doesn't follow the strict RFC 7468 syntax.
+    PemInvalidLine {
+        /// The line number, starting at 1 for the line following the begin
+        /// marker.
+        line: usize,
+    } = 29,
 }
 
 #[cfg(feature = "std")]
 impl std::error::Error for Error {}
 
+#[cfg(all(feature = "core-error", not(feature = "std")))]
+impl core::error::Error for Error {}
+
+static MESSAGES: [(Error, &str); 29] = [
+    (Error::SignatureMismatch, "Signature doesn't verify"),
+    (Error::WeakPublicKey, "Weak public key"),
+    (Error::InvalidPublicKey, "Invalid public key"),
+    (Error::InvalidSecretKey, "Invalid secret key"),
+    (Error::InvalidSignature, "Invalid signature"),
+    (Error::InvalidSeed, "Invalid seed"),
+    (Error::InvalidBlind, "Invalid blind length"),
+    (Error::InvalidNoise, "Invalid noise length"),
+    (Error::ParseError, "Parse error"),
+    (Error::NonCanonical, "Non-canonical encoding"),
+    (Error::NonCanonicalScalar, "Non-canonical signature scalar"),
+    (Error::PublicKeyNotOnCurve, "Public key is not on the curve"),
+    (Error::PemMissingHeader, "Missing PEM header"),
+    (Error::PemMissingFooter, "Missing PEM footer"),
+    (Error::InvalidBase64, "Invalid Base64 encoding"),
+    (
+        Error::DerInvalidLength {
+            expected: 0,
+            actual: 0,
+        },
+        "Unexpected DER length",
+    ),
+    (
+        Error::DerUnexpectedHeader { offset: 0 },
+        "Unexpected DER header",
+    ),
+    (Error::FaultDetected, "Fault detected"),
+    (Error::RngFailure, "RNG failure"),
+    (Error::SelfTestFailed, "Self test failed"),
+    (Error::MemoryLockFailed, "Unable to lock memory"),
+    (
+        Error::InvalidContext,
+        "Invalid context: empty or longer than 255 bytes",
+    ),
+    (Error::SignerFailure, "External signer failure"),
+    (Error::InvalidCertificate, "Invalid certificate"),
+    (Error::Expired, "Expired"),
+    (Error::KeyRevoked, "Key has been revoked"),
+    (Error::PolicyViolation, "Signing policy violation"),
+    (Error::BufferTooSmall, "Buffer too small"),
+    (Error::PemInvalidLine { line: 0 }, "Invalid PEM line"),
+];
+
+impl Error {
+    /// Returns a stable numeric code for the error, between `1` and `255`.
+    ///
+    /// The C API returns the negated code, and the C header defines the
+    /// matching `ED25519_COMPACT_ERR_*` constants.
+    pub fn code(&self) -> u8 {
+        MESSAGES
+            .iter()
+            .position(|(err, _)| mem::discriminant(err) == mem::discriminant(self))
+            .map_or(0, |i| i as u8 + 1)
+    }
+
+    /// Returns the error matching a code returned by `code()`, if any.
+    ///
+    /// Errors carrying context are returned with their fields set to `0`.
+    pub fn from_code(code: u8) -> Option<Error> {
+        MESSAGES
+            .get((code as usize).wrapping_sub(1))
+            .map(|(err, _)| *err)
+    }
+
+    /// Returns a static description of the error.
+    pub fn message(&self) -> &'static str {
+        MESSAGES[self.code() as usize - 1].1
+    }
+}
+
 impl Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            Error::SignatureMismatch => write!(f, "Signature doesn't verify"),
-            Error::WeakPublicKey => write!(f, "Weak public key"),
-            Error::InvalidPublicKey => write!(f, "Invalid public key"),
-            Error::InvalidSecretKey => write!(f, "Invalid secret key"),
-            Error::InvalidSignature => write!(f, "Invalid signature"),
-            Error::InvalidSeed => write!(f, "Invalid seed length"),
-            Error::InvalidBlind => write!(f, "Invalid blind length"),
-            Error::InvalidNoise => write!(f, "Invalid noise length"),
-            Error::ParseError => write!(f, "Parse error"),
-            Error::NonCanonical => write!(f, "Non-canonical encoding"),
+        match *self {
+            Error::DerInvalidLength { expected, actual } => write!(
+                f,
+                "{}: expected {} bytes, got {}",
+                self.message(),
+                expected,
+                actual
+            ),
+            Error::DerUnexpectedHeader { offset } => {
+                write!(f, "{} at offset {}", self.message(), offset)
+            }
+            Error::PemInvalidLine { line } => write!(f, "{} {}", self.message(), line),
+            _ => f.write_str(self.message()),
         }
     }
 }
+
+#[test]
+fn test_error_codes() {
+    for (i, (err, _)) in MESSAGES.iter().enumerate() {
+        assert_eq!(err.code() as usize, i + 1);
+        assert_eq!(Error::from_code(err.code()), Some(*err));
+    }
+    assert_eq!(Error::from_code(0), None);
+    assert_eq!(Error::from_code(255), None);
+    assert_eq!(Error::SignatureMismatch.code(), 1);
+    assert_eq!(Error::PolicyViolation.code(), 27);
+    assert_eq!(Error::BufferTooSmall.code(), 28);
+    assert_eq!(Error::PemInvalidLine { line: 3 }.code(), 29);
+    assert_eq!(
+        Error::from_code(16),
+        Some(Error::DerInvalidLength {
+            expected: 0,
+            actual: 0
+        })
+    );
+    assert_eq!(Error::InvalidSeed.message(), "Invalid seed");
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_error_display() {
+    let err = Error::DerInvalidLength {
+        expected: 48,
+        actual: 80,
+    };
+    assert_eq!(err.message(), "Unexpected DER length");
+    assert_eq!(
+        err.to_string(),
+        "Unexpected DER length: expected 48 bytes, got 80"
+    );
+    assert_eq!(
+        Error::DerUnexpectedHeader { offset: 7 }.to_string(),
+        "Unexpected DER header at offset 7"
+    );
+    assert_eq!(
+        Error::SignatureMismatch.to_string(),
+        "Signature doesn't verify"
+    );
+}
@@ -1,4 +1,5 @@
 #![allow(unused_parens)]
+#![allow(clippy::double_parens)]
 #![allow(non_camel_case_types)]
 
 use core::cmp::{Eq, PartialEq};
@@ -522,10 +523,7 @@ impl Mul for Fe {
 }
 
 impl Fe {
-    pub fn from_bytes(s: &[u8]) -> Fe {
-        if s.len() != 32 {
-            panic!("Invalid compressed length")
-        }
+    pub fn from_bytes(s: &[u8; 32]) -> Fe {
         let mut h = Fe::default();
         let mask = 0x7ffffffffffff;
         h.0[0] = load_8u(&s[0..]) & mask;
@@ -676,7 +674,7 @@ impl Fe {
 
     pub fn reject_noncanonical(s: &[u8]) -> Result<(), Error> {
         if s.len() != 32 {
-            panic!("Invalid compressed length")
+            return Err(Error::NonCanonical);
         }
         let mut c = s[31];
         c ^= 0x7f;
@@ -0,0 +1,216 @@
+//! Arithmetic in GF(2^448 - 2^224 - 1), using 8 limbs of 56 bits.
+
+#![allow(clippy::unreadable_literal, clippy::needless_range_loop)]
+
+use core::ops::{Add, Mul, Sub};
+
+use super::error::Error;
+
+const MASK: u64 = (1 << 56) - 1;
+
+#[derive(Clone, Default, Copy)]
+pub struct Fe(pub [u64; 8]);
+
+pub static FE_ZERO: Fe = Fe([0, 0, 0, 0, 0, 0, 0, 0]);
+pub static FE_ONE: Fe = Fe([1, 0, 0, 0, 0, 0, 0, 0]);
+
+// 2p, added before subtracting to avoid underflows.
+const TWO_P: [u64; 8] = [
+    0x1fffffffffffffe,
+    0x1fffffffffffffe,
+    0x1fffffffffffffe,
+    0x1fffffffffffffe,
+    0x1fffffffffffffc,
+    0x1fffffffffffffe,
+    0x1fffffffffffffe,
+    0x1fffffffffffffe,
+];
+
+// p - 2
+const P_MINUS_2: [u8; 56] = [
+    0xfd, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+    0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xfe, 0xff, 0xff, 0xff,
+    0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+    0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+];
+
+// (p - 3) / 4
+const P_MINUS_3_DIV_4: [u8; 56] = [
+    0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+    0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xbf, 0xff, 0xff, 0xff, 0xff,
+    0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+    0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x3f,
+];
+
+impl Fe {
+    fn reduce(mut t: [u128; 15]) -> Fe {
+        // 2^448 = 2^224 + 1
+        for k in (8..15).rev() {
+            t[k - 8] += t[k];
+            t[k - 4] += t[k];
+        }
+        let mut h = [0u128; 8];
+        h.copy_from_slice(&t[0..8]);
+        for _ in 0..2 {
+            let mut c = 0u128;
+            for x in h.iter_mut() {
+                *x += c;
+                c = *x >> 56;
+                *x &= MASK as u128;
+            }
+            h[0] += c;
+            h[4] += c;
+        }
+        let mut fe = Fe::default();
+        for i in 0..8 {
+            fe.0[i] = h[i] as u64;
+        }
+        fe
+    }
+
+    fn carry(&self) -> Fe {
+        let mut t = [0u128; 15];
+        for i in 0..8 {
+            t[i] = self.0[i] as u128;
+        }
+        Fe::reduce(t)
+    }
+
+    pub fn from_bytes(s: &[u8; 56]) -> Fe {
+        let mut fe = Fe::default();
+        for i in 0..8 {
+            let mut limb = [0u8; 8];
+            limb[0..7].copy_from_slice(&s[i * 7..i * 7 + 7]);
+            fe.0[i] = u64::from_le_bytes(limb);
+        }
+        fe
+    }
+
+    pub fn to_bytes(&self) -> [u8; 56] {
+        let mut h = self.carry().0;
+        let mut top = 0u64;
+        for x in h.iter_mut() {
+            *x += top;
+            top = *x >> 56;
+            *x &= MASK;
+        }
+        // The value is now below 2p: subtract p once if it is not below p.
+        let mut t = [0u64; 8];
+        let mut borrow = 0u64;
+        for i in 0..8 {
+            let p_limb = if i == 4 { MASK - 1 } else { MASK };
+            let x = h[i].wrapping_sub(p_limb).wrapping_sub(borrow);
+            borrow = x >> 63;
+            t[i] = x & MASK;
+        }
+        let sub = (top | (borrow ^ 1)).wrapping_neg();
+        for i in 0..8 {
+            h[i] = (t[i] & sub) | (h[i] & !sub);
+        }
+        let mut s = [0u8; 56];
+        for i in 0..8 {
+            s[i * 7..i * 7 + 7].copy_from_slice(&h[i].to_le_bytes()[0..7]);
+        }
+        s
+    }
+
+    /// Rejects encodings of values that are not below p.
+    pub fn reject_noncanonical(s: &[u8; 56]) -> Result<(), Error> {
+        if &Fe::from_bytes(s).to_bytes() != s {
+            return Err(Error::NonCanonical);
+        }
+        Ok(())
+    }
+
+    pub fn square(&self) -> Fe {
+        *self * *self
+    }
+
+    pub fn mul32(&self, n: u32) -> Fe {
+        let mut t = [0u128; 15];
+        for i in 0..8 {
+            t[i] = self.0[i] as u128 * n as u128;
+        }
+        Fe::reduce(t)
+    }
+
+    pub fn neg(&self) -> Fe {
+        FE_ZERO - *self
+    }
+
+    fn pow(&self, e: &[u8; 56]) -> Fe {
+        let mut acc = FE_ONE;
+        for i in (0..448).rev() {
+            acc = acc.square();
+            if (e[i >> 3] >> (i & 7)) & 1 != 0 {
+                acc = acc * *self;
+            }
+        }
+        acc
+    }
+
+    pub fn invert(&self) -> Fe {
+        self.pow(&P_MINUS_2)
+    }
+
+    /// Returns `x^((p - 3) / 4)`.
+    pub fn pow_p34(&self) -> Fe {
+        self.pow(&P_MINUS_3_DIV_4)
+    }
+
+    pub fn is_nonzero(&self) -> bool {
+        self.to_bytes().iter().fold(0, |acc, x| acc | x) != 0
+    }
+
+    pub fn is_negative(&self) -> bool {
+        (self.to_bytes()[0] & 1) != 0
+    }
+
+    pub fn cswap(a: &mut Fe, b: &mut Fe, c: u8) {
+        let mask = (c as u64).wrapping_neg();
+        for i in 0..8 {
+            let x = (a.0[i] ^ b.0[i]) & mask;
+            a.0[i] ^= x;
+            b.0[i] ^= x;
+        }
+    }
+}
+
+impl Add for Fe {
+    type Output = Fe;
+
+    fn add(self, rhs: Fe) -> Fe {
+        let mut t = [0u128; 15];
+        for i in 0..8 {
+            t[i] = self.0[i] as u128 + rhs.0[i] as u128;
+        }
+        Fe::reduce(t)
+    }
+}
+
+impl Sub for Fe {
+    type Output = Fe;
+
+    fn sub(self, rhs: Fe) -> Fe {
+        let rhs = rhs.carry();
+        let mut t = [0u128; 15];
+        for i in 0..8 {
+            t[i] = (self.0[i] as u128 + TWO_P[i] as u128) - rhs.0[i] as u128;
+        }
+        Fe::reduce(t)
+    }
+}
+
+impl Mul for Fe {
+    type Output = Fe;
+
+    fn mul(self, rhs: Fe) -> Fe {
+        let mut t = [0u128; 15];
+        for i in 0..8 {
+            for j in 0..8 {
+                t[i + j] += self.0[i] as u128 * rhs.0[j] as u128;
+            }
+        }
+        Fe::reduce(t)
+    }
+}
@@ -0,0 +1,168 @@
+#[cfg(feature = "alloc")]
+use alloc::{format, string::String};
+#[cfg(any(feature = "alloc", not(feature = "disable-signatures")))]
+use core::fmt::{self, Write};
+
+use super::sha256;
+#[cfg(feature = "x25519")]
+use super::x25519;
+#[cfg(not(feature = "disable-signatures"))]
+use super::{KeyPair, PublicKey};
+
+/// Format of a public key fingerprint.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum FingerprintFormat {
+    /// `SHA256:` followed by the unpadded Base64 encoding of the hash, like
+    /// `ssh-keygen -l`.
+    Ssh,
+    /// Hex encoding of the SHA-256 hash of the raw public key.
+    Hex,
+    /// Hex encoding of the key ID.
+    KeyId,
+}
+
+/// Length of a key ID, in bytes.
+pub const KEY_ID_BYTES: usize = 8;
+
+fn key_id(pk: &[u8; 32]) -> [u8; KEY_ID_BYTES] {
+    let mut key_id = [0u8; KEY_ID_BYTES];
+    key_id.copy_from_slice(&sha256::Hash::hash(pk)[..KEY_ID_BYTES]);
+    key_id
+}
+
+#[cfg(feature = "alloc")]
+fn fingerprint(pk: &[u8; 32], ssh_hash: [u8; 32], format: FingerprintFormat) -> String {
+    match format {
+        FingerprintFormat::Ssh => format!("SHA256:{}", base64_unpadded(&ssh_hash)),
+        FingerprintFormat::Hex => hex(&sha256::Hash::hash(pk)),
+        FingerprintFormat::KeyId => hex(&key_id(pk)),
+    }
+}
+
+#[cfg(feature = "alloc")]
+fn hex(bin: &[u8]) -> String {
+    let mut hex = String::with_capacity(bin.len() * 2);
+    for x in bin {
+        write!(hex, "{:02x}", x).unwrap();
+    }
+    hex
+}
+
+#[cfg(feature = "alloc")]
+fn base64_unpadded(bin: &[u8]) -> String {
+    let mut b64 = String::with_capacity((bin.len() * 4).div_ceil(3));
+    write_base64_unpadded(&mut b64, bin).unwrap();
+    b64
+}
+
+#[cfg(any(feature = "alloc", not(feature = "disable-signatures")))]
+fn write_base64_unpadded(w: &mut impl Write, bin: &[u8]) -> fmt::Result {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    for chunk in bin.chunks(3) {
+        let x = (chunk[0] as u32) << 16
+            | (*chunk.get(1).unwrap_or(&0) as u32) << 8
+            | *chunk.get(2).unwrap_or(&0) as u32;
+        for i in 0..=chunk.len() {
+            w.write_char(ALPHABET[(x >> (18 - 6 * i) & 63) as usize] as char)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(not(feature = "disable-signatures"))]
+impl PublicKey {
+    /// Returns a short identifier for the key: the first 8 bytes of the
+    /// SHA-256 hash of the raw public key.
+    pub fn key_id(&self) -> [u8; KEY_ID_BYTES] {
+        key_id(self)
+    }
+
+    /// Returns the fingerprint of the key. `FingerprintFormat::Ssh` matches
+    /// the output of `ssh-keygen -l`.
+    #[cfg(feature = "alloc")]
+    pub fn fingerprint(&self, format: FingerprintFormat) -> String {
+        fingerprint(self, self.ssh_hash(), format)
+    }
+
+    fn ssh_hash(&self) -> [u8; 32] {
+        let mut h = sha256::Hash::new();
+        h.update([0, 0, 0, 11]);
+        h.update(b"ssh-ed25519");
+        h.update([0, 0, 0, 32]);
+        h.update(&self[..]);
+        h.finalize()
+    }
+}
+
+#[cfg(not(feature = "disable-signatures"))]
+impl fmt::Display for PublicKey {
+    /// Formats the key as `ed25519:` followed by its SSH fingerprint.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ed25519:SHA256:")?;
+        write_base64_unpadded(f, &self.ssh_hash())
+    }
+}
+
+#[cfg(not(feature = "disable-signatures"))]
+impl fmt::Display for KeyPair {
+    /// Formats the public key of the key pair. Secret bytes are never
+    /// printed.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.pk, f)
+    }
+}
+
+#[cfg(feature = "x25519")]
+impl x25519::PublicKey {
+    /// Returns a short identifier for the key: the first 8 bytes of the
+    /// SHA-256 hash of the raw public key.
+    pub fn key_id(&self) -> [u8; KEY_ID_BYTES] {
+        key_id(self)
+    }
+
+    /// Returns the fingerprint of the key.
+    ///
+    /// SSH has no X25519 keys, so `FingerprintFormat::Ssh` encodes the
+    /// SHA-256 hash of the raw public key.
+    #[cfg(feature = "alloc")]
+    pub fn fingerprint(&self, format: FingerprintFormat) -> String {
+        fingerprint(self, sha256::Hash::hash(&self[..]), format)
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[cfg(not(feature = "disable-signatures"))]
+#[test]
+fn test_fingerprint() {
+    // `ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIDRjhd2XJKCLK+IEMvNwf6EQtY6Z7umCDhw9Mu0vdaDv`
+    let pk = PublicKey::new([
+        0x34, 0x63, 0x85, 0xdd, 0x97, 0x24, 0xa0, 0x8b, 0x2b, 0xe2, 0x04, 0x32, 0xf3, 0x70, 0x7f,
+        0xa1, 0x10, 0xb5, 0x8e, 0x99, 0xee, 0xe9, 0x82, 0x0e, 0x1c, 0x3d, 0x32, 0xed, 0x2f, 0x75,
+        0xa0, 0xef,
+    ]);
+    assert_eq!(
+        pk.fingerprint(FingerprintFormat::Ssh),
+        "SHA256:krvpylefzQcJy6Ubg5QB+QrR9TANAm0+FW0oULOpOH8"
+    );
+    assert_eq!(
+        format!("{}", pk),
+        "ed25519:SHA256:krvpylefzQcJy6Ubg5QB+QrR9TANAm0+FW0oULOpOH8"
+    );
+    let kp = KeyPair::from_seed([42u8; 32].into());
+    assert_eq!(format!("{}", kp), format!("{}", kp.pk));
+    let hex = pk.fingerprint(FingerprintFormat::Hex);
+    assert_eq!(hex.len(), 64);
+    assert_eq!(pk.fingerprint(FingerprintFormat::KeyId), hex[..16]);
+    assert_eq!(pk.key_id()[0], u8::from_str_radix(&hex[..2], 16).unwrap());
+
+    #[cfg(feature = "x25519")]
+    {
+        let x_pk = x25519::PublicKey::new(*pk);
+        assert_eq!(x_pk.fingerprint(FingerprintFormat::Hex), hex);
+        assert_eq!(x_pk.key_id(), pk.key_id());
+        assert_ne!(
+            x_pk.fingerprint(FingerprintFormat::Ssh),
+            pk.fingerprint(FingerprintFormat::Ssh)
+        );
+    }
+}
@@ -0,0 +1,475 @@
+//! Low-level operations for building custom constructions.
+//!
+//! These functions are easy to misuse, and offer none of the guarantees of
+//! the high-level API. Only use them to implement a protocol that requires
+//! them.
+
+use core::fmt;
+use core::ops::{Add, Mul, Neg, Sub};
+
+use super::common::*;
+use super::ed25519::sign_with_secrets;
+use super::edwards25519::{
+    ge_add, ge_scalarmult_base, is_identity, sc_muladd, sc_reduce, sc_reject_noncanonical, GeP3,
+};
+use super::error::Error;
+use super::field25519::{Fe, FE_SQRTM1, FE_ZERO};
+use super::sha512;
+use super::{KeyPair, Noise, PublicKey, SecretKey, Signature};
+
+/// An expanded secret key: a secret scalar, and the prefix used to derive
+/// nonces.
+#[derive(Copy, Clone, Eq, PartialEq, Hash)]
+pub struct ExpandedSecretKey {
+    /// The secret scalar.
+    pub scalar: [u8; 32],
+    /// The prefix used to derive nonces.
+    pub prefix: [u8; 32],
+}
+
+impl fmt::Debug for ExpandedSecretKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_redacted(f, "ExpandedSecretKey", &self.scalar)
+    }
+}
+
+impl ExpandedSecretKey {
+    /// Expands a secret key into a clamped scalar and a prefix.
+    pub fn from_secret_key(sk: &SecretKey) -> Self {
+        let mut az = sha512::Hash::hash(&sk[0..32]);
+        KeyPair::clamp_expanded(&mut az);
+        let mut esk = ExpandedSecretKey {
+            scalar: [0u8; 32],
+            prefix: [0u8; 32],
+        };
+        esk.scalar.copy_from_slice(&az[0..32]);
+        esk.prefix.copy_from_slice(&az[32..64]);
+        wipe(&mut az);
+        esk
+    }
+
+    /// Returns the public key corresponding to the secret scalar.
+    pub fn public_key(&self) -> PublicKey {
+        PublicKey::new(ge_scalarmult_base(&self.scalar).to_bytes())
+    }
+
+    /// Adds the canonical scalar `tweak` to the secret scalar.
+    ///
+    /// The public key of the result is `PublicKey::tweak_add(tweak)` applied
+    /// to the public key of the original key. The prefix is re-derived from
+    /// the original prefix and the tweak, so that tweaked keys never share
+    /// nonces.
+    pub fn tweak_add(&self, tweak: &[u8; 32]) -> Result<ExpandedSecretKey, Error> {
+        sc_reject_noncanonical(tweak)?;
+        let mut one = [0u8; 32];
+        one[0] = 1;
+        let mut esk = ExpandedSecretKey {
+            scalar: [0u8; 32],
+            prefix: [0u8; 32],
+        };
+        sc_muladd(&mut esk.scalar, &one, tweak, &self.scalar);
+        let mut hasher = sha512::Hash::new();
+        hasher.update(b"ed25519-compact tweak");
+        hasher.update(self.prefix);
+        hasher.update(tweak);
+        let mut prefix = hasher.finalize();
+        esk.prefix.copy_from_slice(&prefix[0..32]);
+        wipe(&mut prefix);
+        Ok(esk)
+    }
+}
+
+impl ExpandedSecretKey {
+    /// Computes a signature for the message `message` using the expanded
+    /// secret key. The signature can be verified with `PublicKey::verify()`,
+    /// using the output of `public_key()`.
+    ///
+    /// Panics if a fault is detected. Use `try_sign()` to handle this case.
+    pub fn sign(&self, message: impl AsRef<[u8]>, noise: Option<Noise>) -> Signature {
+        self.try_sign(message, noise)
+            .expect("Newly created signature cannot be verified")
+    }
+
+    /// Computes a signature for the message `message` using the expanded
+    /// secret key, returning `Error::FaultDetected` instead of panicking if
+    /// the `self-verify` or `sign-twice` feature is enabled and a fault is
+    /// detected.
+    pub fn try_sign(
+        &self,
+        message: impl AsRef<[u8]>,
+        noise: Option<Noise>,
+    ) -> Result<Signature, Error> {
+        let message = message.as_ref();
+        sign_with_secrets(
+            &self.public_key(),
+            None,
+            &[message],
+            || (self.scalar, self.nonce(message, noise)),
+            cfg!(feature = "self-verify"),
+            cfg!(feature = "sign-twice"),
+        )
+    }
+
+    /// Starts a two-phase signature of `message`, by computing a nonce and
+    /// its commitment `R`.
+    ///
+    /// Other parties can be involved between this step and `respond()`.
+    /// `noise` must never be reused: the other parties control the group
+    /// commitment, and the same nonce used with two different group
+    /// commitments reveals the secret scalar, so the nonce cannot be
+    /// deterministic.
+    pub fn commit(&self, message: impl AsRef<[u8]>, noise: Noise) -> SigningNonce {
+        let nonce = self.nonce(message.as_ref(), Some(noise));
+        SigningNonce {
+            nonce,
+            r: ge_scalarmult_base(&nonce).to_bytes(),
+        }
+    }
+
+    fn nonce(&self, message: &[u8], noise: Option<Noise>) -> [u8; 32] {
+        let mut hasher = sha512::Hash::new();
+        if let Some(noise) = noise {
+            hasher.update(&noise[..]);
+            hasher.update(self.scalar);
+        }
+        hasher.update(self.prefix);
+        hasher.update(message);
+        let mut hash_output = hasher.finalize();
+        sc_reduce(&mut hash_output[0..64]);
+        let mut nonce = [0u8; 32];
+        nonce.copy_from_slice(&hash_output[0..32]);
+        wipe(&mut hash_output);
+        nonce
+    }
+
+    /// Completes a two-phase signature, returning the response
+    /// `s = nonce + H(R || A || message)·scalar`.
+    ///
+    /// `group_commitment` (`R`) and `group_key` (`A`) are the commitment
+    /// and the public key the signature will be verified with. For a
+    /// single signer, they are `nonce.commitment()` and `public_key()`.
+    /// The nonce is consumed, so that it can't be used twice.
+    pub fn respond(
+        &self,
+        nonce: SigningNonce,
+        group_commitment: &[u8; 32],
+        group_key: &PublicKey,
+        message: impl AsRef<[u8]>,
+    ) -> [u8; 32] {
+        self.response(&nonce.nonce, group_commitment, group_key, message.as_ref())
+    }
+
+    /// Completes a two-phase signature by a single signer.
+    pub fn finalize(&self, nonce: SigningNonce, message: impl AsRef<[u8]>) -> Signature {
+        let r = nonce.r;
+        let s = self.respond(nonce, &r, &self.public_key(), message);
+        let mut signature: [u8; 64] = [0; 64];
+        signature[0..32].copy_from_slice(&r);
+        signature[32..64].copy_from_slice(&s);
+        Signature::new(signature)
+    }
+
+    fn response(&self, nonce: &[u8; 32], r: &[u8; 32], pk: &PublicKey, message: &[u8]) -> [u8; 32] {
+        let mut hasher = sha512::Hash::new();
+        hasher.update(r);
+        hasher.update(&pk[..]);
+        hasher.update(message);
+        let mut hram = hasher.finalize();
+        sc_reduce(&mut hram);
+        let mut s = [0u8; 32];
+        sc_muladd(&mut s, &hram[0..32], &self.scalar, nonce);
+        wipe(&mut hram);
+        s
+    }
+}
+
+/// The secret nonce of a two-phase signature, returned by
+/// `ExpandedSecretKey::commit()`.
+///
+/// This type is intentionally not `Clone`: responding twice with the same
+/// nonce for different messages or commitments would reveal the secret key.
+pub struct SigningNonce {
+    nonce: [u8; 32],
+    r: [u8; 32],
+}
+
+impl SigningNonce {
+    /// Returns the commitment `R` to the nonce, to be sent to the other
+    /// parties.
+    pub fn commitment(&self) -> [u8; 32] {
+        self.r
+    }
+}
+
+impl fmt::Debug for SigningNonce {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_redacted(f, "SigningNonce", &self.nonce)
+    }
+}
+
+impl Drop for SigningNonce {
+    fn drop(&mut self) {
+        wipe(&mut self.nonce);
+    }
+}
+
+/// Adds nonce commitments or public keys, to compute the group commitment
+/// or the group key of a multi-party signature.
+///
+/// Public keys are added as-is, without MuSig-style coefficients, so a
+/// party could choose its key as a function of the others' and control the
+/// group key (rogue-key attack). Every public key must first be checked
+/// with a proof of possession, using `PublicKey::verify_possession()`.
+pub fn combine_points(points: &[[u8; 32]]) -> Result<[u8; 32], Error> {
+    let mut acc: Option<GeP3> = None;
+    for point in points {
+        let p = GeP3::from_bytes_vartime(point).ok_or(Error::PublicKeyNotOnCurve)?;
+        acc = Some(match acc {
+            None => p,
+            Some(acc) => ge_add(&acc, &p),
+        });
+    }
+    let sum = acc.ok_or(Error::InvalidPublicKey)?.to_bytes();
+    if is_identity(&sum) {
+        return Err(Error::WeakPublicKey);
+    }
+    Ok(sum)
+}
+
+/// Builds a signature from the group commitment and the sum of the
+/// responses of all the parties.
+pub fn combine_responses(group_commitment: &[u8; 32], responses: &[[u8; 32]]) -> Signature {
+    let mut one = [0u8; 32];
+    one[0] = 1;
+    let mut s = [0u8; 32];
+    for response in responses {
+        let acc = s;
+        sc_muladd(&mut s, &one, response, &acc);
+    }
+    let mut signature: [u8; 64] = [0; 64];
+    signature[0..32].copy_from_slice(group_commitment);
+    signature[32..64].copy_from_slice(&s);
+    Signature::new(signature)
+}
+
+/// Computes a signature for the message `message` using a raw secret scalar
+/// and nonce prefix, as required by derivation schemes such as BIP32-Ed25519.
+///
+/// With no noise, this is identical to a regular Ed25519 signature whenever
+/// `scalar` and `prefix` are the halves of an expanded seed. The signature
+/// verifies against `scalar·B`, returned by `ExpandedSecretKey::public_key()`.
+pub fn sign_with_expanded_key(
+    scalar: &[u8; 32],
+    prefix: &[u8; 32],
+    message: impl AsRef<[u8]>,
+    noise: Option<Noise>,
+) -> Result<Signature, Error> {
+    ExpandedSecretKey {
+        scalar: *scalar,
+        prefix: *prefix,
+    }
+    .try_sign(message, noise)
+}
+
+impl From<&SecretKey> for ExpandedSecretKey {
+    fn from(sk: &SecretKey) -> Self {
+        ExpandedSecretKey::from_secret_key(sk)
+    }
+}
+
+impl PublicKey {
+    /// Returns `A + tweak·B`, `A` being the public key, `tweak` a canonical
+    /// scalar and `B` the base point.
+    pub fn tweak_add(&self, tweak: &[u8; 32]) -> Result<PublicKey, Error> {
+        sc_reject_noncanonical(tweak)?;
+        let a = GeP3::from_bytes_vartime(self).ok_or(Error::PublicKeyNotOnCurve)?;
+        let tweaked = ge_add(&a, &ge_scalarmult_base(tweak)).to_bytes();
+        if is_identity(&tweaked) {
+            return Err(Error::WeakPublicKey);
+        }
+        Ok(PublicKey::new(tweaked))
+    }
+}
+
+/// An element of the field GF(2^255-19), backed by the formally-verified
+/// arithmetic used by the rest of the crate.
+#[derive(Copy, Clone)]
+pub struct FieldElement(Fe);
+
+impl FieldElement {
+    /// The additive identity.
+    pub const ZERO: FieldElement = FieldElement(Fe([0, 0, 0, 0, 0]));
+
+    /// The multiplicative identity.
+    pub const ONE: FieldElement = FieldElement(Fe([1, 0, 0, 0, 0]));
+
+    /// Decodes a canonical little-endian encoding of a field element.
+    ///
+    /// Returns `Error::NonCanonical` if the value is not fully reduced, or if
+    /// the top bit is set.
+    pub fn from_bytes(bytes: &[u8; 32]) -> Result<Self, Error> {
+        if bytes[31] & 0x80 != 0 {
+            return Err(Error::NonCanonical);
+        }
+        Fe::reject_noncanonical(bytes)?;
+        Ok(FieldElement(Fe::from_bytes(bytes)))
+    }
+
+    /// Returns the canonical little-endian encoding of the element.
+    pub fn to_bytes(&self) -> [u8; 32] {
+        self.0.to_bytes()
+    }
+
+    /// Returns the square of the element.
+    pub fn square(&self) -> Self {
+        FieldElement(self.0.square())
+    }
+
+    /// Returns the multiplicative inverse of the element, or zero if the
+    /// element is zero.
+    pub fn invert(&self) -> Self {
+        FieldElement(self.0.invert())
+    }
+
+    /// Returns the non-negative square root of the element, or `None` if the
+    /// element is not a square.
+    pub fn sqrt(&self) -> Option<Self> {
+        let x = self.0;
+        let mut root = x * x.pow25523();
+        let root_squared = root.square();
+        if (root_squared - x).is_nonzero() {
+            if (root_squared + x).is_nonzero() {
+                return None;
+            }
+            root = root * FE_SQRTM1;
+        }
+        if root.is_negative() {
+            root = root.neg();
+        }
+        Some(FieldElement(root))
+    }
+
+    /// Returns `true` if the element is zero.
+    pub fn is_zero(&self) -> bool {
+        !self.0.is_nonzero()
+    }
+
+    /// Returns `true` if the canonical encoding of the element is odd.
+    pub fn is_negative(&self) -> bool {
+        self.0.is_negative()
+    }
+}
+
+impl fmt::Debug for FieldElement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "FieldElement({:?})", self.to_bytes())
+    }
+}
+
+impl PartialEq for FieldElement {
+    fn eq(&self, other: &FieldElement) -> bool {
+        ct_eq(&self.to_bytes(), &other.to_bytes())
+    }
+}
+
+impl Eq for FieldElement {}
+
+impl Default for FieldElement {
+    fn default() -> Self {
+        FieldElement(FE_ZERO)
+    }
+}
+
+impl Add for FieldElement {
+    type Output = FieldElement;
+
+    fn add(self, rhs: FieldElement) -> FieldElement {
+        FieldElement(self.0 + rhs.0)
+    }
+}
+
+impl Sub for FieldElement {
+    type Output = FieldElement;
+
+    fn sub(self, rhs: FieldElement) -> FieldElement {
+        FieldElement(self.0 - rhs.0)
+    }
+}
+
+impl Mul for FieldElement {
+    type Output = FieldElement;
+
+    fn mul(self, rhs: FieldElement) -> FieldElement {
+        FieldElement(self.0 * rhs.0)
+    }
+}
+
+impl Neg for FieldElement {
+    type Output = FieldElement;
+
+    fn neg(self) -> FieldElement {
+        FieldElement(self.0.neg())
+    }
+}
+
+#[test]
+fn test_hazmat() {
+    use super::Seed;
+
+    let kp = KeyPair::from_seed(Seed::new([42u8; 32]));
+    let esk = ExpandedSecretKey::from_secret_key(&kp.sk);
+    assert_eq!(esk.public_key(), kp.pk);
+
+    let tweak = [7u8; 32];
+    let tweaked_esk = esk.tweak_add(&tweak).unwrap();
+    let tweaked_pk = kp.pk.tweak_add(&tweak).unwrap();
+    assert_eq!(tweaked_esk.public_key(), tweaked_pk);
+    let signature = tweaked_esk.sign(b"message", None);
+    assert!(tweaked_pk.verify(b"message", &signature).is_ok());
+    assert!(kp.pk.verify(b"message", &signature).is_err());
+    assert_eq!(
+        sign_with_expanded_key(&esk.scalar, &esk.prefix, b"message", None),
+        Ok(kp.sk.sign(b"message", None))
+    );
+    assert_ne!(tweaked_esk.prefix, esk.prefix);
+    assert_eq!(
+        kp.pk.tweak_add(&[0xffu8; 32]),
+        Err(Error::NonCanonicalScalar)
+    );
+
+    let mut nine = [0u8; 32];
+    nine[0] = 9;
+    let x = FieldElement::from_bytes(&nine).unwrap();
+    let root = x.sqrt().unwrap();
+    assert!(!root.is_negative());
+    assert_eq!(root * root, x);
+    assert_eq!(x * x.invert(), FieldElement::ONE);
+    assert_eq!(x - x, FieldElement::ZERO);
+    assert_eq!(-x + x, FieldElement::ZERO);
+    assert!((FieldElement::ONE + FieldElement::ONE).sqrt().is_none());
+
+    let nonce = esk.commit(b"message", Noise::new([1u8; Noise::BYTES]));
+    let signature = esk.finalize(nonce, b"message");
+    assert!(kp.pk.verify(b"message", &signature).is_ok());
+    assert_ne!(signature, kp.sk.sign(b"message", None));
+
+    let kp2 = KeyPair::from_seed(Seed::new([43u8; 32]));
+    let esk2 = ExpandedSecretKey::from_secret_key(&kp2.sk);
+    for kp in [&kp, &kp2] {
+        let proof = kp.sk.prove_possession(b"group").unwrap();
+        assert!(kp.pk.verify_possession(&proof, b"group").is_ok());
+    }
+    let group_key =
+        PublicKey::new(combine_points(&[*esk.public_key(), *esk2.public_key()]).unwrap());
+    let nonce1 = esk.commit(b"message", Noise::new([1u8; Noise::BYTES]));
+    let nonce2 = esk2.commit(b"message", Noise::new([2u8; Noise::BYTES]));
+    let group_commitment = combine_points(&[nonce1.commitment(), nonce2.commitment()]).unwrap();
+    let s1 = esk.respond(nonce1, &group_commitment, &group_key, b"message");
+    let s2 = esk2.respond(nonce2, &group_commitment, &group_key, b"message");
+    let signature = combine_responses(&group_commitment, &[s1, s2]);
+    assert!(group_key.verify(b"message", &signature).is_ok());
+    assert_eq!(
+        FieldElement::from_bytes(&[0xffu8; 32]).err(),
+        Some(Error::NonCanonical)
+    );
+}
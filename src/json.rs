@@ -0,0 +1,95 @@
+//! Canonical JSON encoding, for signature schemes that sign JSON objects.
+//!
+//! Shared by several features, which don't all use every flavor.
+
+#![allow(dead_code)]
+
+use core::fmt::Write;
+
+use serde_json::Value;
+
+use super::Error;
+
+/// Largest integer allowed in canonical JSON (2^53 - 1).
+const MAX_SAFE_INTEGER: i64 = (1 << 53) - 1;
+
+/// Canonical JSON variants.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub(crate) enum Flavor {
+    /// Matrix: shortest escapes, and integers in the IEEE 754 safe range.
+    Matrix,
+    /// OLPC (TUF, in-toto): only `"` and `\` are escaped, and integers
+    /// of any size.
+    Olpc,
+}
+
+/// Encodes a value as canonical JSON: object keys sorted by code point, no
+/// insignificant whitespace, and no floating point numbers.
+///
+/// Keys are sorted here rather than relying on `serde_json::Map`, whose
+/// ordering depends on the `preserve_order` feature.
+pub(crate) fn canonical_json(value: &Value, flavor: Flavor) -> Result<String, Error> {
+    let mut out = String::new();
+    write_value(&mut out, value, flavor)?;
+    Ok(out)
+}
+
+fn write_value(out: &mut String, value: &Value, flavor: Flavor) -> Result<(), Error> {
+    match value {
+        Value::Null => out.push_str("null"),
+        Value::Bool(b) => out.push_str(if *b { "true" } else { "false" }),
+        Value::Number(n) => match (n.as_i64(), n.as_u64(), flavor) {
+            (Some(n), _, Flavor::Matrix) if (-MAX_SAFE_INTEGER..=MAX_SAFE_INTEGER).contains(&n) => {
+                write!(out, "{}", n).unwrap()
+            }
+            (Some(n), _, Flavor::Olpc) => write!(out, "{}", n).unwrap(),
+            (None, Some(n), Flavor::Olpc) => write!(out, "{}", n).unwrap(),
+            _ => return Err(Error::ParseError),
+        },
+        Value::String(s) => write_string(out, s, flavor),
+        Value::Array(values) => {
+            out.push('[');
+            for (i, value) in values.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_value(out, value, flavor)?;
+            }
+            out.push(']');
+        }
+        Value::Object(map) => {
+            let mut keys: Vec<&String> = map.keys().collect();
+            keys.sort_unstable();
+            out.push('{');
+            for (i, key) in keys.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_string(out, key, flavor);
+                out.push(':');
+                write_value(out, &map[key], flavor)?;
+            }
+            out.push('}');
+        }
+    }
+    Ok(())
+}
+
+fn write_string(out: &mut String, s: &str, flavor: Flavor) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            c if flavor == Flavor::Olpc => out.push(c),
+            '\u{8}' => out.push_str("\\b"),
+            '\u{c}' => out.push_str("\\f"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => write!(out, "\\u{:04x}", c as u32).unwrap(),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
@@ -0,0 +1,75 @@
+//! Key storage in the platform credential store (macOS Keychain, Windows
+//! Credential Manager, or the Secret Service on Linux and BSD).
+//!
+//! Only the seed is stored. Entries are created by the application:
+//!
+//! ```rust,ignore
+//! let entry = keyring::Entry::new("my-app", "signing-key")?;
+//! let key_pair = KeyPair::load_or_generate_in_keychain(&entry)?;
+//! ```
+
+use keyring::{Entry, Error as KeyringError};
+
+use super::common::wipe;
+use super::{KeyPair, Seed};
+
+impl KeyPair {
+    /// Stores the seed of the key pair in the credential store entry
+    /// `entry`, replacing any previous secret.
+    pub fn store_in_keychain(&self, entry: &Entry) -> Result<(), KeyringError> {
+        let mut seed = self.sk.seed();
+        let res = entry.set_secret(&seed[..]);
+        wipe(&mut seed[..]);
+        res
+    }
+
+    /// Loads a key pair from a seed stored in the credential store entry
+    /// `entry`.
+    ///
+    /// Returns `keyring::Error::NoEntry` if the entry doesn't exist, and
+    /// `keyring::Error::Invalid` if it doesn't contain a valid seed.
+    pub fn load_from_keychain(entry: &Entry) -> Result<KeyPair, KeyringError> {
+        let mut secret = entry.get_secret()?;
+        let kp = Seed::from_slice(&secret).and_then(KeyPair::try_from_seed);
+        wipe(&mut secret);
+        kp.map_err(|e| KeyringError::Invalid("seed".to_string(), e.to_string()))
+    }
+
+    /// Loads a key pair from the credential store entry `entry`, or
+    /// generates a new one and stores it there if the entry doesn't exist.
+    pub fn load_or_generate_in_keychain(entry: &Entry) -> Result<KeyPair, KeyringError> {
+        match Self::load_from_keychain(entry) {
+            Err(KeyringError::NoEntry) => {
+                let kp = KeyPair::try_generate()
+                    .map_err(|e| KeyringError::PlatformFailure(Box::new(e)))?;
+                kp.store_in_keychain(entry)?;
+                Ok(kp)
+            }
+            res => res,
+        }
+    }
+}
+
+#[test]
+fn test_keychain() {
+    keyring::set_default_credential_builder(keyring::mock::default_credential_builder());
+    let entry = Entry::new("ed25519-compact", "test").unwrap();
+
+    assert!(matches!(
+        KeyPair::load_from_keychain(&entry),
+        Err(KeyringError::NoEntry)
+    ));
+    let kp = KeyPair::load_or_generate_in_keychain(&entry).unwrap();
+    assert_eq!(KeyPair::load_from_keychain(&entry).unwrap(), kp);
+    assert_eq!(KeyPair::load_or_generate_in_keychain(&entry).unwrap(), kp);
+
+    let kp = KeyPair::from_seed([42u8; 32].into());
+    kp.store_in_keychain(&entry).unwrap();
+    assert_eq!(KeyPair::load_from_keychain(&entry).unwrap(), kp);
+
+    entry.set_secret(&[0u8; 16]).unwrap();
+    assert!(matches!(
+        KeyPair::load_from_keychain(&entry),
+        Err(KeyringError::Invalid(_, _))
+    ));
+}
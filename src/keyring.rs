@@ -0,0 +1,570 @@
+use std::collections::BTreeMap;
+use std::time::SystemTime;
+
+use ct_codecs::{Base64, Decoder, Encoder};
+
+use super::common::{u64_at, unix_time};
+use super::{Error, KeyPair, PublicKey, SecretKey, Signature, KEY_ID_BYTES};
+
+/// A key stored in a `Keyring`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct KeyringEntry {
+    /// The public key.
+    pub public_key: PublicKey,
+    /// The secret key, if known.
+    pub secret_key: Option<SecretKey>,
+    /// An optional label.
+    pub label: Option<String>,
+}
+
+const REVOCATION_LIST_CONTEXT: &[u8] = b"ed25519-compact revocation list v1";
+
+/// A list of revoked keys, valid for a period of time.
+///
+/// Revocation lists are signed by a root key, and can be loaded into a
+/// `Keyring` with `Keyring::set_revocation_list()`.
+///
+/// The signed encoding is `valid_from (u64 BE) || valid_until (u64 BE) ||
+/// count (u32 BE) || key IDs || signature`.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct RevocationList {
+    /// Key IDs of the revoked keys.
+    pub revoked: Vec<[u8; KEY_ID_BYTES]>,
+    /// Start of the validity period, in seconds since the Unix epoch.
+    pub valid_from: u64,
+    /// End of the validity period, in seconds since the Unix epoch.
+    pub valid_until: u64,
+}
+
+impl RevocationList {
+    fn encode(&self) -> Vec<u8> {
+        let mut bin = Vec::with_capacity(20 + self.revoked.len() * KEY_ID_BYTES + Signature::BYTES);
+        bin.extend_from_slice(&self.valid_from.to_be_bytes());
+        bin.extend_from_slice(&self.valid_until.to_be_bytes());
+        bin.extend_from_slice(&(self.revoked.len() as u32).to_be_bytes());
+        for key_id in &self.revoked {
+            bin.extend_from_slice(key_id);
+        }
+        bin
+    }
+
+    /// Signs the revocation list with a root key, and returns its signed
+    /// encoding.
+    ///
+    /// Returns `Error::FaultDetected` if a fault is detected while signing.
+    pub fn sign(&self, root: &KeyPair) -> Result<Vec<u8>, Error> {
+        let mut bin = self.encode();
+        let signature = root
+            .sk
+            .try_sign_vectored(&[REVOCATION_LIST_CONTEXT, &bin], None)?;
+        bin.extend_from_slice(&signature[..]);
+        Ok(bin)
+    }
+
+    /// Verifies a signed revocation list, and returns it.
+    ///
+    /// Returns `Error::Expired` if `now` is not within its validity period.
+    pub fn verify(signed: &[u8], root: &PublicKey, now: SystemTime) -> Result<Self, Error> {
+        if signed.len() < 20 + Signature::BYTES {
+            return Err(Error::ParseError);
+        }
+        let (bin, signature) = signed.split_at(signed.len() - Signature::BYTES);
+        let count = u32::from_be_bytes([bin[16], bin[17], bin[18], bin[19]]) as usize;
+        if count.checked_mul(KEY_ID_BYTES) != Some(bin.len() - 20) {
+            return Err(Error::ParseError);
+        }
+        let mut message = Vec::with_capacity(REVOCATION_LIST_CONTEXT.len() + bin.len());
+        message.extend_from_slice(REVOCATION_LIST_CONTEXT);
+        message.extend_from_slice(bin);
+        root.verify(message, &Signature::from_slice(signature)?)?;
+        let list = RevocationList {
+            revoked: bin[20..]
+                .chunks(KEY_ID_BYTES)
+                .map(|c| {
+                    let mut key_id = [0u8; KEY_ID_BYTES];
+                    key_id.copy_from_slice(c);
+                    key_id
+                })
+                .collect(),
+            valid_from: u64_at(bin, 0),
+            valid_until: u64_at(bin, 8),
+        };
+        list.check_validity(now)?;
+        Ok(list)
+    }
+
+    /// Returns `Error::Expired` if `now` is not within the validity period.
+    pub fn check_validity(&self, now: SystemTime) -> Result<(), Error> {
+        let now = unix_time(now);
+        if now < self.valid_from || now >= self.valid_until {
+            return Err(Error::Expired);
+        }
+        Ok(())
+    }
+
+    /// Returns `true` if the key with the given key ID is revoked.
+    pub fn is_revoked(&self, key_id: &[u8; KEY_ID_BYTES]) -> bool {
+        self.revoked.contains(key_id)
+    }
+}
+
+/// A set of keys, indexed by key ID.
+///
+/// A keyring can be used to verify signatures made by any of a set of
+/// trusted keys, and to sign with one of them if its secret key is present.
+///
+/// If a revocation list has been loaded, revoked keys are ignored by
+/// `verify()`, `verify_any()` and `sign()`. These functions return
+/// `Error::Expired` once the revocation list is no longer valid, until a
+/// fresh one is loaded.
+///
+/// The text serialization has one key per line:
+/// `public <base64 public key> [label]` or `secret <base64 secret key> [label]`.
+/// Empty lines and lines starting with `#` are ignored.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Keyring {
+    entries: BTreeMap<[u8; KEY_ID_BYTES], KeyringEntry>,
+    revocation_list: Option<RevocationList>,
+}
+
+impl Keyring {
+    /// Creates an empty keyring.
+    pub fn new() -> Self {
+        Keyring::default()
+    }
+
+    /// Adds a public key, and returns its key ID.
+    ///
+    /// If the key was already present, its label is replaced.
+    ///
+    /// Returns `Error::ParseError` if the label contains whitespace other
+    /// than inner spaces, or control characters, since it couldn't be
+    /// serialized, and `Error::InvalidPublicKey` if a different key with the
+    /// same key ID is already present.
+    pub fn add(
+        &mut self,
+        public_key: PublicKey,
+        label: Option<&str>,
+    ) -> Result<[u8; KEY_ID_BYTES], Error> {
+        if let Some(label) = label {
+            check_label(label)?;
+        }
+        let key_id = public_key.key_id();
+        let secret_key = match self.entries.get(&key_id) {
+            Some(entry) if entry.public_key != public_key => {
+                return Err(Error::InvalidPublicKey);
+            }
+            Some(entry) => entry.secret_key,
+            None => None,
+        };
+        self.entries.insert(
+            key_id,
+            KeyringEntry {
+                public_key,
+                secret_key,
+                label: label.map(str::to_string),
+            },
+        );
+        Ok(key_id)
+    }
+
+    /// Adds a key pair, and returns its key ID.
+    ///
+    /// Fails for the same reasons as `add()`.
+    pub fn add_key_pair(
+        &mut self,
+        kp: &KeyPair,
+        label: Option<&str>,
+    ) -> Result<[u8; KEY_ID_BYTES], Error> {
+        let key_id = self.add(kp.pk, label)?;
+        if let Some(entry) = self.entries.get_mut(&key_id) {
+            entry.secret_key = Some(kp.sk);
+        }
+        Ok(key_id)
+    }
+
+    /// Removes a key, and returns it.
+    pub fn remove(&mut self, key_id: &[u8; KEY_ID_BYTES]) -> Option<KeyringEntry> {
+        self.entries.remove(key_id)
+    }
+
+    /// Returns the key with the given key ID.
+    pub fn get(&self, key_id: &[u8; KEY_ID_BYTES]) -> Option<&KeyringEntry> {
+        self.entries.get(key_id)
+    }
+
+    /// Returns the first key with the given label, along with its key ID.
+    pub fn find_by_label(&self, label: &str) -> Option<(&[u8; KEY_ID_BYTES], &KeyringEntry)> {
+        self.entries
+            .iter()
+            .find(|(_, e)| e.label.as_deref() == Some(label))
+    }
+
+    /// Returns an iterator over the keys and their key IDs.
+    pub fn iter(&self) -> impl Iterator<Item = (&[u8; KEY_ID_BYTES], &KeyringEntry)> {
+        self.entries.iter()
+    }
+
+    /// Returns the number of keys.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the keyring is empty.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Verifies and loads a revocation list signed by `root`, replacing the
+    /// current one.
+    ///
+    /// Returns `Error::Expired` if `now` is not within its validity period,
+    /// or if it is older than the current revocation list.
+    pub fn set_revocation_list(
+        &mut self,
+        signed: &[u8],
+        root: &PublicKey,
+        now: SystemTime,
+    ) -> Result<(), Error> {
+        let list = RevocationList::verify(signed, root, now)?;
+        if let Some(current) = &self.revocation_list {
+            if list.valid_from < current.valid_from {
+                return Err(Error::Expired);
+            }
+        }
+        self.revocation_list = Some(list);
+        Ok(())
+    }
+
+    /// Returns the current revocation list.
+    pub fn revocation_list(&self) -> Option<&RevocationList> {
+        self.revocation_list.as_ref()
+    }
+
+    /// Returns `true` if the key with the given key ID is revoked.
+    ///
+    /// Returns `Error::Expired` if the revocation list is not valid at time
+    /// `now`.
+    pub fn is_revoked(&self, key_id: &[u8; KEY_ID_BYTES], now: SystemTime) -> Result<bool, Error> {
+        match &self.revocation_list {
+            Some(list) => {
+                list.check_validity(now)?;
+                Ok(list.is_revoked(key_id))
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// Verifies a signature made by the key with the given key ID, at time
+    /// `now`.
+    ///
+    /// Returns `Error::InvalidPublicKey` if the key is not in the keyring,
+    /// `Error::KeyRevoked` if it has been revoked, and `Error::Expired` if
+    /// the revocation list is not valid at time `now`.
+    pub fn verify(
+        &self,
+        key_id: &[u8; KEY_ID_BYTES],
+        message: impl AsRef<[u8]>,
+        signature: &Signature,
+        now: SystemTime,
+    ) -> Result<(), Error> {
+        let entry = self.get(key_id).ok_or(Error::InvalidPublicKey)?;
+        if self.is_revoked(key_id, now)? {
+            return Err(Error::KeyRevoked);
+        }
+        entry.public_key.verify(message, signature)
+    }
+
+    /// Verifies a signature made by any key of the keyring at time `now`,
+    /// and returns the key ID of the key that made it.
+    ///
+    /// Returns `Error::Expired` if the revocation list is not valid at time
+    /// `now`.
+    pub fn verify_any(
+        &self,
+        message: impl AsRef<[u8]>,
+        signature: &Signature,
+        now: SystemTime,
+    ) -> Result<[u8; KEY_ID_BYTES], Error> {
+        let message = message.as_ref();
+        for (key_id, entry) in &self.entries {
+            if !self.is_revoked(key_id, now)? && entry.public_key.verify(message, signature).is_ok()
+            {
+                return Ok(*key_id);
+            }
+        }
+        Err(Error::SignatureMismatch)
+    }
+
+    /// Signs a message with the key with the given key ID, at time `now`.
+    ///
+    /// Returns `Error::InvalidSecretKey` if the key is not in the keyring,
+    /// or if its secret key is unknown, `Error::KeyRevoked` if it has been
+    /// revoked, `Error::Expired` if the revocation list is not valid at time
+    /// `now`, and `Error::FaultDetected` if a fault is detected while
+    /// signing.
+    pub fn sign(
+        &self,
+        key_id: &[u8; KEY_ID_BYTES],
+        message: impl AsRef<[u8]>,
+        now: SystemTime,
+    ) -> Result<Signature, Error> {
+        if self.is_revoked(key_id, now)? {
+            return Err(Error::KeyRevoked);
+        }
+        let sk = self
+            .get(key_id)
+            .and_then(|e| e.secret_key)
+            .ok_or(Error::InvalidSecretKey)?;
+        sk.try_sign(message, None)
+    }
+
+    /// Serializes the public keys of the keyring.
+    ///
+    /// The revocation list is not included.
+    pub fn to_public_string(&self) -> String {
+        self.serialize(false)
+    }
+
+    /// Serializes the keyring, including secret keys.
+    pub fn to_secret_string(&self) -> String {
+        self.serialize(true)
+    }
+
+    fn serialize(&self, with_secrets: bool) -> String {
+        let mut out = String::new();
+        for entry in self.entries.values() {
+            match entry.secret_key {
+                Some(sk) if with_secrets => {
+                    out.push_str("secret ");
+                    out.push_str(&Base64::encode_to_string(&sk[..]).unwrap());
+                }
+                _ => {
+                    out.push_str("public ");
+                    out.push_str(&Base64::encode_to_string(&entry.public_key[..]).unwrap());
+                }
+            }
+            if let Some(label) = &entry.label {
+                out.push(' ');
+                out.push_str(label);
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Parses a serialized keyring.
+    ///
+    /// The revocation list is not part of the serialization, and has to be
+    /// loaded separately.
+    ///
+    /// Secret keys are validated.
+    pub fn parse(s: &str) -> Result<Self, Error> {
+        let mut keyring = Keyring::new();
+        for line in s.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut parts = line.splitn(3, ' ');
+            let kind = parts.next().ok_or(Error::ParseError)?;
+            let b64 = parts.next().ok_or(Error::ParseError)?;
+            let label = parts.next().map(str::trim).filter(|l| !l.is_empty());
+            match kind {
+                "public" => {
+                    let mut pk = [0u8; PublicKey::BYTES];
+                    let pk =
+                        Base64::decode(&mut pk, b64, None).map_err(|_| Error::InvalidBase64)?;
+                    keyring.add(PublicKey::from_slice(pk)?, label)?;
+                }
+                "secret" => {
+                    let mut sk = [0u8; SecretKey::BYTES];
+                    let len = Base64::decode(&mut sk, b64, None)
+                        .map_err(|_| Error::InvalidBase64)?
+                        .len();
+                    let parsed = if len == SecretKey::BYTES {
+                        Ok(SecretKey::new(sk))
+                    } else {
+                        Err(Error::InvalidSecretKey)
+                    };
+                    super::common::wipe(&mut sk);
+                    let sk = parsed?;
+                    sk.validate()?;
+                    keyring.add_key_pair(
+                        &KeyPair {
+                            pk: sk.public_key(),
+                            sk,
+                        },
+                        label,
+                    )?;
+                }
+                _ => return Err(Error::ParseError),
+            }
+        }
+        Ok(keyring)
+    }
+}
+
+/// Checks that a label can be serialized on a single line, and parsed back
+/// unchanged.
+fn check_label(label: &str) -> Result<(), Error> {
+    if label.is_empty()
+        || label.starts_with(' ')
+        || label.ends_with(' ')
+        || label
+            .chars()
+            .any(|c| c.is_control() || (c.is_whitespace() && c != ' '))
+    {
+        return Err(Error::ParseError);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_keyring() {
+    use super::Seed;
+
+    let now = SystemTime::now();
+    let kp1 = KeyPair::from_seed(Seed::new([1u8; Seed::BYTES]));
+    let kp2 = KeyPair::from_seed(Seed::new([2u8; Seed::BYTES]));
+    let kp3 = KeyPair::from_seed(Seed::new([3u8; Seed::BYTES]));
+
+    let mut keyring = Keyring::new();
+    let id1 = keyring.add(kp1.pk, Some("release key 2024")).unwrap();
+    let id2 = keyring.add_key_pair(&kp2, Some("ci")).unwrap();
+    assert_eq!(keyring.len(), 2);
+    let injected = format!(
+        "x\npublic {}",
+        Base64::encode_to_string(&kp3.pk[..]).unwrap()
+    );
+    for label in ["", " ci", "ci ", "c\ti", &injected] {
+        assert_eq!(keyring.add(kp3.pk, Some(label)), Err(Error::ParseError));
+    }
+    assert_eq!(keyring.len(), 2);
+
+    // A different key with the same key ID.
+    let mut colliding = keyring.get(&id2).unwrap().clone();
+    colliding.secret_key = None;
+    keyring.entries.insert(kp3.pk.key_id(), colliding);
+    assert_eq!(
+        keyring.add_key_pair(&kp3, None),
+        Err(Error::InvalidPublicKey)
+    );
+    assert_eq!(
+        keyring.sign(&kp3.pk.key_id(), b"", now),
+        Err(Error::InvalidSecretKey)
+    );
+    keyring.remove(&kp3.pk.key_id());
+    assert_eq!(keyring.find_by_label("ci").unwrap().0, &id2);
+
+    let message = b"document";
+    let signature = kp1.sk.sign(message, None);
+    assert!(keyring.verify(&id1, message, &signature, now).is_ok());
+    assert!(keyring.verify(&id2, message, &signature, now).is_err());
+    assert_eq!(
+        keyring.verify(&kp3.pk.key_id(), message, &signature, now),
+        Err(Error::InvalidPublicKey)
+    );
+    assert_eq!(keyring.verify_any(message, &signature, now).unwrap(), id1);
+    let signature3 = kp3.sk.sign(message, None);
+    assert!(keyring.verify_any(message, &signature3, now).is_err());
+
+    let signature2 = keyring.sign(&id2, message, now).unwrap();
+    assert_eq!(keyring.verify_any(message, &signature2, now).unwrap(), id2);
+    assert_eq!(
+        keyring.sign(&id1, message, now),
+        Err(Error::InvalidSecretKey)
+    );
+
+    let public = keyring.to_public_string();
+    assert!(!public.contains("secret "));
+    let parsed = Keyring::parse(&public).unwrap();
+    assert_eq!(parsed.len(), 2);
+    assert_eq!(parsed.get(&id2).unwrap().secret_key, None);
+    assert_eq!(
+        parsed.get(&id1).unwrap().label.as_deref(),
+        Some("release key 2024")
+    );
+    let parsed = Keyring::parse(&keyring.to_secret_string()).unwrap();
+    assert_eq!(parsed, keyring);
+
+    assert!(Keyring::parse("private AAAA").is_err());
+    assert!(keyring.remove(&id1).is_some());
+    assert!(keyring.verify_any(message, &signature, now).is_err());
+}
+
+#[test]
+fn test_keyring_revocation() {
+    use super::Seed;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    let root = KeyPair::from_seed(Seed::new([9u8; Seed::BYTES]));
+    let kp1 = KeyPair::from_seed(Seed::new([1u8; Seed::BYTES]));
+    let kp2 = KeyPair::from_seed(Seed::new([2u8; Seed::BYTES]));
+    let mut keyring = Keyring::new();
+    let id1 = keyring.add(kp1.pk, None).unwrap();
+    let id2 = keyring.add_key_pair(&kp2, None).unwrap();
+
+    let now = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+    let list = RevocationList {
+        revoked: vec![id1],
+        valid_from: 1_699_000_000,
+        valid_until: 1_701_000_000,
+    };
+    let signed = list.sign(&root).unwrap();
+    assert_eq!(
+        RevocationList::verify(&signed, &root.pk, now).unwrap(),
+        list
+    );
+    assert_eq!(
+        RevocationList::verify(&signed, &kp1.pk, now),
+        Err(Error::SignatureMismatch)
+    );
+    assert_eq!(
+        RevocationList::verify(
+            &signed,
+            &root.pk,
+            UNIX_EPOCH + Duration::from_secs(1_701_000_000)
+        ),
+        Err(Error::Expired)
+    );
+    let mut tampered = signed.clone();
+    tampered[20] ^= 1;
+    assert!(RevocationList::verify(&tampered, &root.pk, now).is_err());
+
+    let message = b"document";
+    let signature1 = kp1.sk.sign(message, None);
+    assert!(keyring.verify(&id1, message, &signature1, now).is_ok());
+    keyring.set_revocation_list(&signed, &root.pk, now).unwrap();
+    assert_eq!(keyring.is_revoked(&id1, now), Ok(true));
+    assert_eq!(
+        keyring.verify(&id1, message, &signature1, now),
+        Err(Error::KeyRevoked)
+    );
+    assert!(keyring.verify_any(message, &signature1, now).is_err());
+    assert!(keyring.sign(&id2, message, now).is_ok());
+
+    let older = RevocationList {
+        revoked: vec![],
+        valid_from: 1_698_000_000,
+        valid_until: 1_701_000_000,
+    };
+    assert_eq!(
+        keyring.set_revocation_list(&older.sign(&root).unwrap(), &root.pk, now),
+        Err(Error::Expired)
+    );
+    assert_eq!(keyring.is_revoked(&id1, now), Ok(true));
+
+    // The installed list goes stale once its validity period is over.
+    let later = UNIX_EPOCH + Duration::from_secs(1_701_000_001);
+    assert_eq!(keyring.is_revoked(&id1, later), Err(Error::Expired));
+    assert_eq!(
+        keyring.verify(&id1, message, &signature1, later),
+        Err(Error::Expired)
+    );
+    assert_eq!(
+        keyring.verify_any(message, &signature1, later),
+        Err(Error::Expired)
+    );
+    assert_eq!(keyring.sign(&id2, message, later), Err(Error::Expired));
+}
@@ -0,0 +1,267 @@
+use ct_codecs::{Base64, Decoder};
+
+use super::common::ct_eq;
+use super::sha1;
+use super::ssh;
+use super::{Error, PublicKey};
+
+/// Marker at the beginning of a `known_hosts` entry.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum KnownHostMarker {
+    /// `@cert-authority`: the key is a CA key for certificates of the hosts.
+    CertAuthority,
+    /// `@revoked`: the key must not be accepted.
+    Revoked,
+}
+
+/// Host names of a `known_hosts` entry.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum KnownHostNames {
+    /// A list of patterns, possibly with `*` and `?` wildcards, and negated
+    /// with a `!` prefix.
+    Patterns(Vec<String>),
+    /// A hashed host name (`|1|salt|hash`), where `hash` is HMAC-SHA1(salt,
+    /// host name).
+    Hashed {
+        /// The HMAC key.
+        salt: Vec<u8>,
+        /// The HMAC of the host name.
+        hash: [u8; 20],
+    },
+}
+
+/// Result of looking up a host key in a list of `known_hosts` entries.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum HostKeyStatus {
+    /// The key is known for that host.
+    Known,
+    /// Other keys are known for that host, but not that one.
+    Changed,
+    /// The key has been revoked.
+    Revoked,
+    /// No keys are known for that host.
+    Unknown,
+}
+
+/// An entry of an OpenSSH `known_hosts` file.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct KnownHost {
+    /// Optional marker.
+    pub marker: Option<KnownHostMarker>,
+    /// Host names the entry applies to.
+    pub names: KnownHostNames,
+    /// The host public key.
+    pub public_key: PublicKey,
+    /// The comment following the key, if any.
+    pub comment: Option<String>,
+}
+
+impl KnownHost {
+    /// Parses a line of a `known_hosts` file.
+    ///
+    /// Returns `Ok(None)` for empty lines, comments, and keys of other types
+    /// than Ed25519.
+    pub fn parse(line: &str) -> Result<Option<Self>, Error> {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            return Ok(None);
+        }
+        let mut tokens = line.split([' ', '\t']).filter(|t| !t.is_empty());
+        let mut token = tokens.next().ok_or(Error::ParseError)?;
+        let marker = match token {
+            "@cert-authority" => Some(KnownHostMarker::CertAuthority),
+            "@revoked" => Some(KnownHostMarker::Revoked),
+            _ if token.starts_with('@') => return Err(Error::ParseError),
+            _ => None,
+        };
+        if marker.is_some() {
+            token = tokens.next().ok_or(Error::ParseError)?;
+        }
+        let names = parse_names(token)?;
+        let key_type = tokens.next().ok_or(Error::ParseError)?;
+        if key_type.as_bytes() != ssh::ED25519_TYPE {
+            return Ok(None);
+        }
+        let b64 = tokens.next().ok_or(Error::ParseError)?;
+        let blob = Base64::decode_to_vec(b64, None).map_err(|_| Error::InvalidBase64)?;
+        let public_key = ssh::parse_public_key_blob(&blob)?.ok_or(Error::ParseError)?;
+        let comment: Vec<&str> = tokens.collect();
+        let comment = if comment.is_empty() {
+            None
+        } else {
+            Some(comment.join(" "))
+        };
+        Ok(Some(KnownHost {
+            marker,
+            names,
+            public_key,
+            comment,
+        }))
+    }
+
+    /// Parses the content of a `known_hosts` file, and returns its Ed25519
+    /// entries.
+    pub fn parse_all(content: &str) -> Result<Vec<Self>, Error> {
+        let mut entries = Vec::new();
+        for line in content.lines() {
+            if let Some(entry) = Self::parse(line)? {
+                entries.push(entry);
+            }
+        }
+        Ok(entries)
+    }
+
+    /// Returns `true` if the entry applies to `host` on port `port`.
+    ///
+    /// Host names are compared case-insensitively, and hosts on a port other
+    /// than 22 are looked up as `[host]:port`, like OpenSSH does.
+    pub fn matches(&self, host: &str, port: u16) -> bool {
+        let host = host.to_ascii_lowercase();
+        let name = if port == 22 {
+            host
+        } else {
+            format!("[{}]:{}", host, port)
+        };
+        match &self.names {
+            KnownHostNames::Hashed { salt, hash } => {
+                ct_eq(&sha1::hmac(salt, name.as_bytes()), hash)
+            }
+            KnownHostNames::Patterns(patterns) => {
+                let mut matched = false;
+                for pattern in patterns {
+                    let pattern = pattern.to_ascii_lowercase();
+                    if let Some(negated) = pattern.strip_prefix('!') {
+                        if wildcard_match(negated.as_bytes(), name.as_bytes()) {
+                            return false;
+                        }
+                    } else if wildcard_match(pattern.as_bytes(), name.as_bytes()) {
+                        matched = true;
+                    }
+                }
+                matched
+            }
+        }
+    }
+
+    /// Checks a host key against a list of entries.
+    ///
+    /// `@cert-authority` entries are ignored, and `@revoked` entries take
+    /// precedence over any other entry.
+    pub fn check(entries: &[KnownHost], host: &str, port: u16, pk: &PublicKey) -> HostKeyStatus {
+        if entries
+            .iter()
+            .any(|e| e.marker == Some(KnownHostMarker::Revoked) && e.public_key == *pk)
+        {
+            return HostKeyStatus::Revoked;
+        }
+        let mut status = HostKeyStatus::Unknown;
+        for entry in entries {
+            if entry.marker.is_some() || !entry.matches(host, port) {
+                continue;
+            }
+            if entry.public_key == *pk {
+                return HostKeyStatus::Known;
+            }
+            status = HostKeyStatus::Changed;
+        }
+        status
+    }
+}
+
+fn parse_names(token: &str) -> Result<KnownHostNames, Error> {
+    if let Some(hashed) = token.strip_prefix("|1|") {
+        let (salt, hash) = hashed.split_once('|').ok_or(Error::ParseError)?;
+        let salt = Base64::decode_to_vec(salt, None).map_err(|_| Error::InvalidBase64)?;
+        let mut hash_ = [0u8; 20];
+        let len = Base64::decode(&mut hash_, hash, None)
+            .map_err(|_| Error::InvalidBase64)?
+            .len();
+        if len != hash_.len() {
+            return Err(Error::ParseError);
+        }
+        return Ok(KnownHostNames::Hashed { salt, hash: hash_ });
+    }
+    if token.starts_with('|') {
+        return Err(Error::ParseError);
+    }
+    Ok(KnownHostNames::Patterns(
+        token.split(',').map(str::to_string).collect(),
+    ))
+}
+
+/// Matches `name` against a pattern with `*` and `?` wildcards.
+fn wildcard_match(pattern: &[u8], name: &[u8]) -> bool {
+    match (pattern.first(), name.first()) {
+        (None, None) => true,
+        (Some(b'*'), _) => {
+            wildcard_match(&pattern[1..], name)
+                || (!name.is_empty() && wildcard_match(pattern, &name[1..]))
+        }
+        (Some(b'?'), Some(_)) => wildcard_match(&pattern[1..], &name[1..]),
+        (Some(p), Some(n)) if p == n => wildcard_match(&pattern[1..], &name[1..]),
+        _ => false,
+    }
+}
+
+#[test]
+fn test_known_hosts() {
+    let ca = "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIB3bin3lAsOqntz9OzKpQKzEn1XiGpso4xnQ5S/0mZbe";
+    let user = "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIL7b/acZitc9GH8PMugP92R0O4eUH5CDh8hQ+CR2BMu7";
+    let pk1 = PublicKey::from_any_str(ca).unwrap();
+    let pk2 = PublicKey::from_any_str(user).unwrap();
+
+    // `ssh-keygen -H` output for "example.com,192.0.2.1" and "[git.example.org]:2222"
+    let hashed = format!(
+        "# hashed\n\
+         |1|PVSC9s1WwjbPv/LWERXK76bRe/M=|EZe5ZAupltM7/Ov6a7ovfGevTTs= {ca}\n\
+         |1|XlqVVSSKxG+by9pKVzLrp+mdduQ=|5zG/jqJlQpUqLQVCx+v6n6M4HIE= {ca}\n\
+         |1|guYTnENVMpiopa08se6z4btcTuM=|/AyNlm/Wtga4ANRFHi+PxdSKgSo= {user}\n",
+        ca = ca,
+        user = user
+    );
+    let entries = KnownHost::parse_all(&hashed).unwrap();
+    assert_eq!(entries.len(), 3);
+    assert!(entries[0].matches("example.com", 22));
+    assert!(entries[0].matches("Example.COM", 22));
+    assert!(!entries[0].matches("example.com", 2222));
+    assert!(entries[1].matches("192.0.2.1", 22));
+    assert!(entries[2].matches("git.example.org", 2222));
+    assert_eq!(
+        KnownHost::check(&entries, "example.com", 22, &pk1),
+        HostKeyStatus::Known
+    );
+    assert_eq!(
+        KnownHost::check(&entries, "example.com", 22, &pk2),
+        HostKeyStatus::Changed
+    );
+    assert_eq!(
+        KnownHost::check(&entries, "example.net", 22, &pk1),
+        HostKeyStatus::Unknown
+    );
+
+    let plain = format!(
+        "*.example.com,!bad.example.com {ca} comment with spaces\n\
+         ssh-rsa-host ssh-rsa AAAAB3NzaC1yc2EAAAADAQABAAABAQ==\n\
+         @revoked * {user}\n",
+        ca = ca,
+        user = user
+    );
+    let entries = KnownHost::parse_all(&plain).unwrap();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0].comment.as_deref(), Some("comment with spaces"));
+    assert!(entries[0].matches("www.example.com", 22));
+    assert!(!entries[0].matches("bad.example.com", 22));
+    assert!(!entries[0].matches("example.com", 22));
+    assert_eq!(entries[1].marker, Some(KnownHostMarker::Revoked));
+    assert_eq!(
+        KnownHost::check(&entries, "www.example.com", 22, &pk1),
+        HostKeyStatus::Known
+    );
+    assert_eq!(
+        KnownHost::check(&entries, "www.example.com", 22, &pk2),
+        HostKeyStatus::Revoked
+    );
+
+    assert!(KnownHost::parse("|1|!!|!! ssh-ed25519 AAAA").is_err());
+    assert!(KnownHost::parse("@bogus host ssh-ed25519 AAAA").is_err());
+}
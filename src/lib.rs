@@ -17,9 +17,10 @@
 //! // A message to sign and verify.
 //! let message = b"test";
 //!
-//! // Generates a new key pair using a random seed.
-//! // A given seed will always produce the same key pair.
-//! let key_pair = KeyPair::from_seed(Seed::generate());
+//! // Generates a new random key pair.
+//! // `KeyPair::from_seed()` can be used instead to derive a key pair from a
+//! // seed. A given seed will always produce the same key pair.
+//! let key_pair = KeyPair::generate();
 //!
 //! // Computes a signature for this message using the secret part of the key pair.
 //! let signature = key_pair.sk.sign(message, Some(Noise::generate()));
@@ -41,11 +42,19 @@
 //! println!("Signature as bytes: {:?}", signature_as_bytes);
 //! ```
 //!
+//! Functions that can panic, such as `sign()`, `KeyPair::from_seed()` or
+//! `Seed::generate()`, have non-panicking counterparts (`try_sign()`,
+//! `KeyPair::try_from_seed()`, `Seed::try_generate()`) returning a `Result`
+//! instead. Applications that must never panic should only use these.
+//!
 //! Cargo features:
 //!
 //! * `self-verify`: after having computed a new signature, verify that is it
 //!   valid. This is slower, but improves resilience against fault attacks. It
-//!   is enabled by default on WebAssembly targets.
+//!   is enabled by default on WebAssembly targets, and can be overridden for
+//!   individual signatures with `SecretKey::sign_with_options()`.
+//! * `sign-twice`: compute every signature twice and compare the results.
+//!   This is a cheaper alternative to `self-verify` to detect fault attacks.
 //! * `std`: disables `no_std` compatibility in order to make errors implement
 //!   the standard `Error` trait.
 //! * `random` (enabled by default): adds `Default` and `generate`
@@ -54,13 +63,102 @@
 //! * `traits`: add support for the traits from the ed25519 and signature
 //!   crates.
 //! * `pem`: add support for importing/exporting keys as OpenSSL-compatible PEM
-//!   files.
-//! * `blind-keys`: add support for key blinding.
+//!   files, using caller-provided buffers if no allocator is available. With
+//!   `std`, OpenSSH private keys are also supported, and secret keys can be
+//!   saved to and loaded from files with safe permissions. Keys can also be
+//!   imported from hex or Base64 strings with `from_any_str()`.
+//! * `blind-keys`: add support for key blinding, for Ed25519 and X25519 keys.
 //! * `opt_size`: Enable size optimizations (based on benchmarks, 8-15% size
 //!   reduction at the cost of 6.5-7% performance).
 //! * `x25519`: Enable support for the X25519 key exchange system.
 //! * `disable-signatures`: Disable support for signatures, and only compile
 //!   support for X25519.
+//! * `locked-memory`: add a `LockedSecretKey` type, that keeps a secret key in
+//!   memory that cannot be swapped out, and wipes it on drop.
+//! * `sodium-compat`: add a `sodium_compat` module mirroring libsodium's
+//!   `crypto_sign_*()` and `crypto_kdf_*()` functions.
+//! * `hazmat`: add a `hazmat` module exposing low-level operations that are
+//!   easy to misuse, for building custom constructions, as well as
+//!   `x25519::scalarmult()`.
+//! * `async`: add `AsyncSigner` and `AsyncVerifier` traits, for remote
+//!   signing backends.
+//! * `agent`: add an ssh-agent client, to sign with keys held by an agent
+//!   (Unix only).
+//! * `rustls`: implement `rustls::sign::SigningKey` for `KeyPair`, to serve
+//!   Ed25519 certificates with rustls.
+//! * `pkcs11`: add a `Pkcs11Signer` type, to sign with keys stored in a
+//!   PKCS#11 token.
+//! * `keychain`: store and load key pairs in the platform credential store
+//!   (macOS Keychain, Windows Credential Manager, Secret Service).
+//! * `ssh`: parse and verify OpenSSH certificates, parse and format
+//!   `authorized_keys` entries, check host keys against `known_hosts`
+//!   entries, and generate SSHFP DNS records.
+//! * `dnssec`: encode DNSKEY records, and sign and verify RRsets with
+//!   DNSSEC algorithm 15 (RFC 8080).
+//! * `dkim`: sign and verify DKIM signatures with the `ed25519-sha256`
+//!   algorithm (RFC 8463), and encode DKIM key records.
+//! * `paseto`: create and verify PASETO `v4.public` tokens.
+//! * `token`: issue and verify compact signed tokens with an optional
+//!   expiration time, for license keys and activation codes.
+//! * `matrix`: sign and verify JSON objects following the Matrix signing
+//!   scheme, using `serde_json` values.
+//! * `tuf`: sign and verify TUF and in-toto metadata, using `serde_json`
+//!   values.
+//! * `solana`: import and export key pairs in the `solana-keygen` JSON
+//!   format, and encode public keys as Base58 addresses.
+//! * `ss58`: encode and decode public keys as Substrate SS58 addresses.
+//! * `near`: import and export keys in the NEAR `ed25519:<base58>` format.
+//! * `tezos`: import and export keys and signatures in the Tezos
+//!   Base58Check encodings, and compute `tz1` addresses.
+//! * `keyring`: add a `Keyring` type, to verify signatures made by any of
+//!   a set of trusted keys, indexed by key ID.
+//! * `delegation`: add a `Delegation` type, for key rotation with
+//!   verifiable chains of delegations between keys.
+//! * `challenge`: challenge-response authentication, with responses bound
+//!   to both parties, and replay protection.
+//! * `anti-exfil`: anti-exfiltration signing, where the host contributes
+//!   randomness to the nonce and verifies that the signer used it.
+//! * `hedged`: hedged signatures, mixing noise into the nonce as specified
+//!   in draft-irtf-cfrg-det-sigs-with-noise.
+//! * `policy`: add a `RateLimitedSigner` wrapper, restricting the number
+//!   of signatures, the messages that can be signed, and when.
+//! * `audit`: call an application-defined hook after every signature, with
+//!   the key ID and a digest of the message, to keep a log of signatures.
+//! * `ed448`: add the `ed448` and `x448` modules, for Ed448 signatures
+//!   (RFC 8032) and X448 key exchange (RFC 7748).
+//! * `child-keys`: non-hardened child key derivation, so that child public
+//!   keys can be derived from a parent public key and a chain code alone.
+//! * `entropy-source`: add an `EntropySource` trait, so that random keys,
+//!   seeds and noise can be generated using a hardware RNG registered with
+//!   `set_entropy_source()`, with or without `getrandom`.
+//! * `alloc`: enable the functions returning a `Vec` or a `String`, such as
+//!   DER and PEM export, attached signatures and batch X25519 operations,
+//!   on `no_std` targets with an allocator. Implied by `std`.
+//! * `core-error`: implement `core::error::Error` for `Error` without
+//!   `std`. Requires Rust 1.81 or later.
+//! * `low-stack`: verify signatures without any precomputed tables on the
+//!   stack, for RTOS tasks with small stacks. Verification is about 40%
+//!   slower.
+//! * `diagnostics`: add `PublicKey::verify_explain()`, reporting why a
+//!   signature was accepted or rejected, to debug interoperability issues.
+//! * `conformance`: add a `conformance` module embedding Wycheproof-style
+//!   Ed25519 and X25519 test vectors, and a `conformance::run()` function to
+//!   check them against the current build.
+//! * `test-vectors`: add a `test_vectors` module exporting the RFC 8032 and
+//!   RFC 7748 test vectors as typed constants, for downstream tests.
+//! * `tables-link-section`: place the precomputed base point table used for
+//!   verification in the `.rodata.ed25519_compact_tables` ELF section, so
+//!   that a linker script can move it to external flash. Signing computes
+//!   its tables on the stack, and is not affected. The feature has no effect
+//!   on targets that don't use ELF (Apple platforms, Windows and
+//!   WebAssembly).
+//! * `x509`: create self-signed X.509 certificates and certificate signing
+//!   requests, and extract public keys from certificates.
+//! * `capi`: export a C API, described in `include/ed25519_compact.h`.
+//! * `component`: implement the WebAssembly component interface described in
+//!   `wit/ed25519-compact.wit`.
+//! * `uniffi`: add a `mobile` module exporting key generation, signatures
+//!   and X25519 to Swift and Kotlin through UniFFI.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 #![allow(
@@ -71,28 +169,359 @@
     clippy::needless_lifetimes,
     clippy::cast_lossless,
     clippy::suspicious_arithmetic_impl,
-    clippy::identity_op
+    clippy::identity_op,
+    clippy::wrong_self_convention
 )]
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(all(
+    any(feature = "ss58", feature = "tezos", feature = "sodium-compat"),
+    not(feature = "disable-signatures")
+))]
+mod blake2b;
 mod common;
+#[cfg(any(feature = "random", feature = "entropy-source"))]
+mod entropy;
 mod error;
 mod field25519;
+#[cfg(any(not(feature = "disable-signatures"), feature = "x25519"))]
+mod fingerprint;
+mod selftest;
+#[cfg(all(feature = "ssh", not(feature = "disable-signatures")))]
+mod sha1;
+#[cfg(any(not(feature = "disable-signatures"), feature = "x25519"))]
+mod sha256;
 mod sha512;
 
 pub use crate::common::*;
+#[cfg(feature = "entropy-source")]
+pub use crate::entropy::*;
 pub use crate::error::*;
+#[cfg(any(not(feature = "disable-signatures"), feature = "x25519"))]
+pub use crate::fingerprint::*;
+pub use crate::selftest::*;
 
+#[cfg(not(feature = "disable-signatures"))]
+mod batch;
 #[cfg(not(feature = "disable-signatures"))]
 mod ed25519;
 #[cfg(not(feature = "disable-signatures"))]
 mod edwards25519;
+#[cfg(not(feature = "disable-signatures"))]
+mod possession;
 
+#[cfg(not(feature = "disable-signatures"))]
+pub use crate::batch::*;
 #[cfg(not(feature = "disable-signatures"))]
 pub use crate::ed25519::*;
 
+#[cfg(not(feature = "disable-signatures"))]
+mod signer;
+
+#[cfg(not(feature = "disable-signatures"))]
+pub use crate::signer::*;
+
 #[cfg(feature = "x25519")]
 pub mod x25519;
 
+#[cfg(feature = "conformance")]
+pub mod conformance;
+
+#[cfg(feature = "test-vectors")]
+pub mod test_vectors;
+
+#[cfg(feature = "ed448")]
+mod field448;
+#[cfg(feature = "ed448")]
+mod shake256;
+
+#[cfg(feature = "ed448")]
+pub mod ed448;
+#[cfg(feature = "ed448")]
+pub mod x448;
+
 #[cfg(not(feature = "disable-signatures"))]
 #[cfg(feature = "pem")]
 mod pem;
+
+#[cfg(not(feature = "disable-signatures"))]
+#[cfg(feature = "pem")]
+pub use crate::pem::*;
+
+#[cfg(not(feature = "disable-signatures"))]
+#[cfg(feature = "pem")]
+mod autodetect;
+
+#[cfg(not(feature = "disable-signatures"))]
+#[cfg(all(feature = "pem", feature = "std"))]
+mod openssh;
+
+#[cfg(not(feature = "disable-signatures"))]
+#[cfg(all(feature = "pem", feature = "std"))]
+mod keyfile;
+
+#[cfg(not(feature = "disable-signatures"))]
+#[cfg(all(feature = "pem", feature = "std"))]
+pub use crate::keyfile::*;
+
+#[cfg(not(feature = "disable-signatures"))]
+#[cfg(feature = "locked-memory")]
+mod locked;
+
+#[cfg(not(feature = "disable-signatures"))]
+#[cfg(feature = "locked-memory")]
+pub use crate::locked::*;
+
+#[cfg(not(feature = "disable-signatures"))]
+#[cfg(feature = "std")]
+mod stream;
+
+#[cfg(not(feature = "disable-signatures"))]
+#[cfg(feature = "std")]
+pub use crate::stream::*;
+
+#[cfg(not(feature = "disable-signatures"))]
+#[cfg(any(all(feature = "agent", unix), all(feature = "pem", feature = "std")))]
+mod ssh;
+
+#[cfg(not(feature = "disable-signatures"))]
+#[cfg(feature = "ssh")]
+mod ssh_cert;
+
+#[cfg(not(feature = "disable-signatures"))]
+#[cfg(feature = "ssh")]
+pub use crate::ssh_cert::*;
+
+#[cfg(not(feature = "disable-signatures"))]
+#[cfg(feature = "ssh")]
+mod authorized_keys;
+
+#[cfg(not(feature = "disable-signatures"))]
+#[cfg(feature = "ssh")]
+pub use crate::authorized_keys::*;
+
+#[cfg(not(feature = "disable-signatures"))]
+#[cfg(feature = "ssh")]
+mod known_hosts;
+
+#[cfg(not(feature = "disable-signatures"))]
+#[cfg(feature = "ssh")]
+pub use crate::known_hosts::*;
+
+#[cfg(not(feature = "disable-signatures"))]
+#[cfg(feature = "dnssec")]
+mod dnssec;
+
+#[cfg(not(feature = "disable-signatures"))]
+#[cfg(feature = "dnssec")]
+pub use crate::dnssec::*;
+
+#[cfg(not(feature = "disable-signatures"))]
+#[cfg(feature = "dkim")]
+mod dkim;
+
+#[cfg(not(feature = "disable-signatures"))]
+#[cfg(feature = "dkim")]
+pub use crate::dkim::*;
+
+#[cfg(not(feature = "disable-signatures"))]
+#[cfg(feature = "paseto")]
+mod paseto;
+
+#[cfg(not(feature = "disable-signatures"))]
+#[cfg(feature = "paseto")]
+pub use crate::paseto::*;
+
+#[cfg(not(feature = "disable-signatures"))]
+#[cfg(feature = "token")]
+mod token;
+
+#[cfg(not(feature = "disable-signatures"))]
+#[cfg(feature = "token")]
+pub use crate::token::*;
+
+#[cfg(not(feature = "disable-signatures"))]
+#[cfg(any(feature = "matrix", feature = "tuf"))]
+mod json;
+
+#[cfg(not(feature = "disable-signatures"))]
+#[cfg(feature = "matrix")]
+mod matrix;
+
+#[cfg(not(feature = "disable-signatures"))]
+#[cfg(feature = "matrix")]
+pub use crate::matrix::*;
+
+#[cfg(not(feature = "disable-signatures"))]
+#[cfg(feature = "tuf")]
+mod tuf;
+
+#[cfg(not(feature = "disable-signatures"))]
+#[cfg(feature = "tuf")]
+pub use crate::tuf::*;
+
+#[cfg(not(feature = "disable-signatures"))]
+#[cfg(any(
+    feature = "solana",
+    feature = "ss58",
+    feature = "near",
+    feature = "tezos"
+))]
+mod base58;
+
+#[cfg(not(feature = "disable-signatures"))]
+#[cfg(feature = "solana")]
+mod solana;
+
+#[cfg(not(feature = "disable-signatures"))]
+#[cfg(feature = "ss58")]
+mod ss58;
+
+#[cfg(not(feature = "disable-signatures"))]
+#[cfg(feature = "ss58")]
+pub use crate::ss58::*;
+
+#[cfg(not(feature = "disable-signatures"))]
+#[cfg(feature = "near")]
+mod near;
+
+#[cfg(not(feature = "disable-signatures"))]
+#[cfg(feature = "tezos")]
+mod tezos;
+
+#[cfg(not(feature = "disable-signatures"))]
+#[cfg(feature = "tezos")]
+pub use crate::tezos::*;
+
+#[cfg(not(feature = "disable-signatures"))]
+#[cfg(feature = "keyring")]
+mod keyring;
+
+#[cfg(not(feature = "disable-signatures"))]
+#[cfg(feature = "keyring")]
+pub use crate::keyring::*;
+
+#[cfg(not(feature = "disable-signatures"))]
+#[cfg(feature = "delegation")]
+mod delegation;
+
+#[cfg(not(feature = "disable-signatures"))]
+#[cfg(feature = "delegation")]
+pub use crate::delegation::*;
+
+#[cfg(not(feature = "disable-signatures"))]
+#[cfg(feature = "challenge")]
+mod challenge;
+
+#[cfg(not(feature = "disable-signatures"))]
+#[cfg(feature = "challenge")]
+pub use crate::challenge::*;
+
+#[cfg(not(feature = "disable-signatures"))]
+#[cfg(feature = "anti-exfil")]
+mod anti_exfil;
+
+#[cfg(not(feature = "disable-signatures"))]
+#[cfg(feature = "anti-exfil")]
+pub use crate::anti_exfil::*;
+
+#[cfg(not(feature = "disable-signatures"))]
+#[cfg(feature = "policy")]
+mod policy;
+
+#[cfg(not(feature = "disable-signatures"))]
+#[cfg(feature = "policy")]
+pub use crate::policy::*;
+
+#[cfg(not(feature = "disable-signatures"))]
+#[cfg(feature = "audit")]
+mod audit;
+
+#[cfg(not(feature = "disable-signatures"))]
+#[cfg(feature = "audit")]
+pub use crate::audit::*;
+
+#[cfg(not(feature = "disable-signatures"))]
+#[cfg(feature = "child-keys")]
+mod child;
+
+#[cfg(not(feature = "disable-signatures"))]
+#[cfg(feature = "child-keys")]
+pub use crate::child::*;
+
+#[cfg(not(feature = "disable-signatures"))]
+#[cfg(feature = "diagnostics")]
+mod diagnostics;
+
+#[cfg(not(feature = "disable-signatures"))]
+#[cfg(feature = "diagnostics")]
+pub use crate::diagnostics::*;
+
+#[cfg(not(feature = "disable-signatures"))]
+#[cfg(feature = "ssh")]
+mod sshfp;
+
+#[cfg(not(feature = "disable-signatures"))]
+#[cfg(feature = "ssh")]
+pub use crate::sshfp::*;
+
+#[cfg(not(feature = "disable-signatures"))]
+#[cfg(all(feature = "agent", unix))]
+mod agent;
+
+#[cfg(not(feature = "disable-signatures"))]
+#[cfg(all(feature = "agent", unix))]
+pub use crate::agent::*;
+
+#[cfg(not(feature = "disable-signatures"))]
+#[cfg(feature = "rustls")]
+mod tls;
+
+#[cfg(not(feature = "disable-signatures"))]
+#[cfg(feature = "pkcs11")]
+mod pkcs11;
+
+#[cfg(not(feature = "disable-signatures"))]
+#[cfg(feature = "pkcs11")]
+pub use crate::pkcs11::*;
+
+#[cfg(not(feature = "disable-signatures"))]
+#[cfg(feature = "keychain")]
+mod keychain;
+
+#[cfg(not(feature = "disable-signatures"))]
+#[cfg(feature = "x509")]
+mod der;
+
+#[cfg(not(feature = "disable-signatures"))]
+#[cfg(feature = "x509")]
+mod x509;
+
+#[cfg(not(feature = "disable-signatures"))]
+#[cfg(feature = "x509")]
+pub use crate::x509::*;
+
+#[cfg(not(feature = "disable-signatures"))]
+#[cfg(feature = "sodium-compat")]
+pub mod sodium_compat;
+
+#[cfg(not(feature = "disable-signatures"))]
+#[cfg(feature = "hazmat")]
+pub mod hazmat;
+
+#[cfg(not(feature = "disable-signatures"))]
+#[cfg(feature = "capi")]
+pub mod capi;
+
+#[cfg(not(feature = "disable-signatures"))]
+#[cfg(feature = "component")]
+mod component;
+
+#[cfg(not(feature = "disable-signatures"))]
+#[cfg(feature = "uniffi")]
+pub mod mobile;
+
+#[cfg(feature = "uniffi")]
+uniffi::setup_scaffolding!();
@@ -0,0 +1,154 @@
+use std::alloc::{self, Layout};
+use std::fmt;
+use std::ops::Deref;
+use std::ptr::{self, NonNull};
+
+use super::common::wipe;
+use super::{Error, Noise, PublicKey, SecretKey, Signature, Signer};
+
+/// A secret key stored in a dedicated memory page that is locked into RAM,
+/// excluded from core dumps where the platform supports it, and wiped when
+/// the key is dropped.
+///
+/// This is meant for long-lived signing keys, that must never be written to
+/// swap space. On platforms without `mlock()`, the key is only wiped on drop.
+pub struct LockedSecretKey {
+    ptr: NonNull<SecretKey>,
+    layout: Layout,
+}
+
+unsafe impl Send for LockedSecretKey {}
+unsafe impl Sync for LockedSecretKey {}
+
+impl LockedSecretKey {
+    /// Moves a secret key into locked memory.
+    ///
+    /// `SecretKey` is `Copy`: the caller remains responsible for wiping any
+    /// other copy of the key it may still hold.
+    pub fn new(sk: SecretKey) -> Result<Self, Error> {
+        let page_size = page_size();
+        let layout =
+            Layout::from_size_align(page_size, page_size).map_err(|_| Error::MemoryLockFailed)?;
+        let ptr = unsafe { alloc::alloc_zeroed(layout) } as *mut SecretKey;
+        let ptr = NonNull::new(ptr).ok_or(Error::MemoryLockFailed)?;
+        if let Err(e) = lock(ptr.as_ptr() as *mut u8, page_size) {
+            unsafe { alloc::dealloc(ptr.as_ptr() as *mut u8, layout) };
+            return Err(e);
+        }
+        unsafe { ptr::write(ptr.as_ptr(), sk) };
+        Ok(LockedSecretKey { ptr, layout })
+    }
+
+    /// Returns the public counterpart of the secret key.
+    pub fn public_key(&self) -> PublicKey {
+        self.deref().public_key()
+    }
+
+    /// Computes a signature for the message `message` using the secret key.
+    pub fn sign(&self, message: impl AsRef<[u8]>, noise: Option<Noise>) -> Signature {
+        self.deref().sign(message, noise)
+    }
+
+    /// Computes a signature for the message `message` using the secret key,
+    /// returning an error instead of panicking if a fault is detected.
+    pub fn try_sign(
+        &self,
+        message: impl AsRef<[u8]>,
+        noise: Option<Noise>,
+    ) -> Result<Signature, Error> {
+        self.deref().try_sign(message, noise)
+    }
+}
+
+impl Deref for LockedSecretKey {
+    type Target = SecretKey;
+
+    /// Returns the secret key.
+    fn deref(&self) -> &Self::Target {
+        unsafe { self.ptr.as_ref() }
+    }
+}
+
+impl Drop for LockedSecretKey {
+    fn drop(&mut self) {
+        let base = self.ptr.as_ptr() as *mut u8;
+        unsafe {
+            wipe(&mut self.ptr.as_mut()[..]);
+            unlock(base, self.layout.size());
+            alloc::dealloc(base, self.layout);
+        }
+    }
+}
+
+impl Signer for LockedSecretKey {
+    fn public_key(&self) -> PublicKey {
+        LockedSecretKey::public_key(self)
+    }
+
+    fn sign(&self, message: &[u8]) -> Result<Signature, Error> {
+        self.try_sign(message, None)
+    }
+}
+
+impl fmt::Debug for LockedSecretKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("LockedSecretKey")
+    }
+}
+
+#[cfg(unix)]
+fn page_size() -> usize {
+    let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) };
+    if page_size <= 0 {
+        4096
+    } else {
+        page_size as usize
+    }
+}
+
+#[cfg(not(unix))]
+fn page_size() -> usize {
+    4096
+}
+
+#[cfg(unix)]
+fn lock(ptr: *mut u8, len: usize) -> Result<(), Error> {
+    if unsafe { libc::mlock(ptr as *const libc::c_void, len) } != 0 {
+        return Err(Error::MemoryLockFailed);
+    }
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    unsafe {
+        libc::madvise(ptr as *mut libc::c_void, len, libc::MADV_DONTDUMP);
+    }
+    #[cfg(any(target_os = "freebsd", target_os = "dragonfly"))]
+    unsafe {
+        libc::madvise(ptr as *mut libc::c_void, len, libc::MADV_NOCORE);
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn lock(_ptr: *mut u8, _len: usize) -> Result<(), Error> {
+    Ok(())
+}
+
+#[cfg(unix)]
+unsafe fn unlock(ptr: *mut u8, len: usize) {
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    libc::madvise(ptr as *mut libc::c_void, len, libc::MADV_DODUMP);
+    libc::munlock(ptr as *const libc::c_void, len);
+}
+
+#[cfg(not(unix))]
+unsafe fn unlock(_ptr: *mut u8, _len: usize) {}
+
+#[test]
+fn test_locked_secret_key() {
+    let kp = super::KeyPair::from_seed([42u8; 32].into());
+    let locked_sk = LockedSecretKey::new(kp.sk).unwrap();
+    assert_eq!(locked_sk.public_key(), kp.pk);
+    let signature = locked_sk.sign(b"message", None);
+    assert_eq!(signature, kp.sk.sign(b"message", None));
+    assert!(kp.pk.verify(b"message", &signature).is_ok());
+    assert_eq!(Signer::sign(&locked_sk, b"message"), Ok(signature));
+}
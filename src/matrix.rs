@@ -0,0 +1,120 @@
+use ct_codecs::{Base64NoPadding, Decoder, Encoder};
+use serde_json::{Map, Value};
+
+use super::json::{canonical_json, Flavor};
+use super::{Error, KeyPair, PublicKey, Signature};
+
+/// Returns the bytes to sign for a JSON object: its canonical encoding,
+/// without the `signatures` and `unsigned` properties.
+fn signed_bytes(object: &Value) -> Result<String, Error> {
+    let mut object = object.as_object().ok_or(Error::ParseError)?.clone();
+    object.remove("signatures");
+    object.remove("unsigned");
+    canonical_json(&Value::Object(object), Flavor::Matrix)
+}
+
+/// Encodes a JSON value as Matrix canonical JSON.
+pub fn matrix_canonical_json(value: &Value) -> Result<String, Error> {
+    canonical_json(value, Flavor::Matrix)
+}
+
+impl KeyPair {
+    /// Signs a JSON object following the Matrix signing scheme, and adds the
+    /// signature to its `signatures` property, as
+    /// `signatures.<entity>."ed25519:<key_version>"`.
+    ///
+    /// Existing signatures are kept.
+    pub fn sign_matrix_json(
+        &self,
+        object: &mut Value,
+        entity: &str,
+        key_version: &str,
+    ) -> Result<(), Error> {
+        let signature = self.sk.sign(signed_bytes(object)?, None);
+        let signature = Base64NoPadding::encode_to_string(&signature[..]).unwrap();
+        let object = object.as_object_mut().ok_or(Error::ParseError)?;
+        let signatures = object
+            .entry("signatures")
+            .or_insert_with(|| Value::Object(Map::new()))
+            .as_object_mut()
+            .ok_or(Error::ParseError)?;
+        let entity_signatures = signatures
+            .entry(entity)
+            .or_insert_with(|| Value::Object(Map::new()))
+            .as_object_mut()
+            .ok_or(Error::ParseError)?;
+        entity_signatures.insert(format!("ed25519:{}", key_version), Value::String(signature));
+        Ok(())
+    }
+}
+
+impl PublicKey {
+    /// Verifies the signature of a JSON object made by `entity` with key
+    /// `ed25519:<key_version>`, following the Matrix signing scheme.
+    pub fn verify_matrix_json(
+        &self,
+        object: &Value,
+        entity: &str,
+        key_version: &str,
+    ) -> Result<(), Error> {
+        let signature = object
+            .get("signatures")
+            .and_then(|s| s.get(entity))
+            .and_then(|s| s.get(format!("ed25519:{}", key_version)))
+            .and_then(Value::as_str)
+            .ok_or(Error::SignatureMismatch)?;
+        let mut bin = [0u8; Signature::BYTES];
+        let bin =
+            Base64NoPadding::decode(&mut bin, signature, None).map_err(|_| Error::InvalidBase64)?;
+        let signature = Signature::from_slice(bin)?;
+        self.verify(signed_bytes(object)?, &signature)
+    }
+}
+
+#[test]
+fn test_matrix() {
+    use super::Seed;
+    use serde_json::json;
+
+    // Matrix specification, signing key test vectors
+    // (seed: YJDBA9Xnr2sVqXD9Vj7XVUnmFZcZrlw8Md7kMW+3XA1)
+    let seed = [
+        0x60, 0x90, 0xc1, 0x03, 0xd5, 0xe7, 0xaf, 0x6b, 0x15, 0xa9, 0x70, 0xfd, 0x56, 0x3e, 0xd7,
+        0x55, 0x49, 0xe6, 0x15, 0x97, 0x19, 0xae, 0x5c, 0x3c, 0x31, 0xde, 0xe4, 0x31, 0x6f, 0xb7,
+        0x5c, 0x0d,
+    ];
+    let kp = KeyPair::from_seed(Seed::new(seed));
+
+    let mut object = json!({});
+    kp.sign_matrix_json(&mut object, "domain", "1").unwrap();
+    assert_eq!(
+        object,
+        json!({
+            "signatures": {
+                "domain": {
+                    "ed25519:1": "K8280/U9SSy9IVtjBuVeLr+HpOB4BQFWbg+UZaADMtTdGYI7Geitb76LTrr5QV/7Xg4ahLwYGYZzuHGZKM5ZAQ"
+                }
+            }
+        })
+    );
+
+    let mut object = json!({ "two": "Two", "one": 1, "unsigned": { "age_ts": 1000000 } });
+    kp.sign_matrix_json(&mut object, "domain", "1").unwrap();
+    assert_eq!(
+        object["signatures"]["domain"]["ed25519:1"],
+        "KqmLSbO39/Bzb0QIYE82zqLwsA+PDzYIpIRA2sRQ4sL53+sN6/fpNSoqE7BP7vBZhG6kYdD13EIMJpvhJI+6Bw"
+    );
+    assert!(kp.pk.verify_matrix_json(&object, "domain", "1").is_ok());
+    assert!(kp.pk.verify_matrix_json(&object, "domain", "2").is_err());
+    object["unsigned"]["age_ts"] = json!(2000000);
+    assert!(kp.pk.verify_matrix_json(&object, "domain", "1").is_ok());
+    object["one"] = json!(2);
+    assert!(kp.pk.verify_matrix_json(&object, "domain", "1").is_err());
+
+    assert_eq!(
+        matrix_canonical_json(&json!({ "b": [1, "\u{1}\n"], "a": "日本語", "": null })).unwrap(),
+        r#"{"":null,"a":"日本語","b":[1,"\u0001\n"]}"#
+    );
+    assert!(matrix_canonical_json(&json!({ "a": 1.5 })).is_err());
+    assert!(kp.sign_matrix_json(&mut json!([]), "domain", "1").is_err());
+}
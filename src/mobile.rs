@@ -0,0 +1,117 @@
+//! UniFFI bindings, for generating Swift and Kotlin interfaces.
+//!
+//! Bindings can be generated from the compiled library with
+//! `uniffi-bindgen generate --library <path to the library> --language swift`
+//! (or `kotlin`).
+
+use super::error::Error;
+use super::{x25519, KeyPair, PublicKey, SecretKey, Seed, Signature};
+
+/// An Ed25519 key pair.
+#[derive(uniffi::Record)]
+pub struct Ed25519KeyPair {
+    /// The 32-byte public key.
+    pub public_key: Vec<u8>,
+    /// The 64-byte secret key.
+    pub secret_key: Vec<u8>,
+}
+
+impl From<KeyPair> for Ed25519KeyPair {
+    fn from(kp: KeyPair) -> Self {
+        Ed25519KeyPair {
+            public_key: kp.pk.to_vec(),
+            secret_key: kp.sk.to_vec(),
+        }
+    }
+}
+
+/// An X25519 key pair.
+#[derive(uniffi::Record)]
+pub struct X25519KeyPair {
+    /// The 32-byte public key.
+    pub public_key: Vec<u8>,
+    /// The 32-byte secret key.
+    pub secret_key: Vec<u8>,
+}
+
+/// Generates a random Ed25519 key pair.
+#[uniffi::export]
+pub fn ed25519_generate_key_pair() -> Result<Ed25519KeyPair, Error> {
+    Ok(KeyPair::try_generate()?.into())
+}
+
+/// Deterministically derives an Ed25519 key pair from a 32-byte seed.
+#[uniffi::export]
+pub fn ed25519_key_pair_from_seed(seed: Vec<u8>) -> Result<Ed25519KeyPair, Error> {
+    Ok(KeyPair::try_from_seed(Seed::from_slice(&seed)?)?.into())
+}
+
+/// Computes a 64-byte Ed25519 signature for a message.
+#[uniffi::export]
+pub fn ed25519_sign(secret_key: Vec<u8>, message: Vec<u8>) -> Result<Vec<u8>, Error> {
+    let sk = SecretKey::from_slice(&secret_key)?;
+    Ok(sk.try_sign(message, None)?.to_vec())
+}
+
+/// Verifies an Ed25519 signature for a message.
+#[uniffi::export]
+pub fn ed25519_verify(
+    public_key: Vec<u8>,
+    message: Vec<u8>,
+    signature: Vec<u8>,
+) -> Result<(), Error> {
+    let pk = PublicKey::from_slice(&public_key)?;
+    pk.verify(message, &Signature::from_slice(&signature)?)
+}
+
+/// Generates a random X25519 key pair.
+#[uniffi::export]
+pub fn x25519_generate_key_pair() -> Result<X25519KeyPair, Error> {
+    let kp = x25519::KeyPair::try_generate()?;
+    Ok(X25519KeyPair {
+        public_key: kp.pk.to_vec(),
+        secret_key: kp.sk.to_vec(),
+    })
+}
+
+/// Computes the X25519 public key for a secret key.
+#[uniffi::export]
+pub fn x25519_public_key(secret_key: Vec<u8>) -> Result<Vec<u8>, Error> {
+    let sk = x25519::SecretKey::from_slice(&secret_key)?;
+    Ok(sk.recover_public_key()?.to_vec())
+}
+
+/// Computes an X25519 shared secret.
+#[uniffi::export]
+pub fn x25519_dh(public_key: Vec<u8>, secret_key: Vec<u8>) -> Result<Vec<u8>, Error> {
+    let pk = x25519::PublicKey::from_slice(&public_key)?;
+    let sk = x25519::SecretKey::from_slice(&secret_key)?;
+    Ok(pk.dh(&sk)?.to_vec())
+}
+
+#[test]
+fn test_mobile() {
+    let kp = ed25519_key_pair_from_seed(vec![42u8; 32]).unwrap();
+    let signature = ed25519_sign(kp.secret_key, b"message".to_vec()).unwrap();
+    assert!(ed25519_verify(
+        kp.public_key.clone(),
+        b"message".to_vec(),
+        signature.clone()
+    )
+    .is_ok());
+    assert_eq!(
+        ed25519_verify(kp.public_key, b"other".to_vec(), signature),
+        Err(Error::SignatureMismatch)
+    );
+
+    let kp_a = x25519_generate_key_pair().unwrap();
+    let kp_b = x25519_generate_key_pair().unwrap();
+    assert_eq!(
+        x25519_public_key(kp_a.secret_key.clone()).unwrap(),
+        kp_a.public_key
+    );
+    assert_eq!(
+        x25519_dh(kp_b.public_key, kp_a.secret_key).unwrap(),
+        x25519_dh(kp_a.public_key, kp_b.secret_key).unwrap()
+    );
+}
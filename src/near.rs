@@ -0,0 +1,76 @@
+use super::base58;
+use super::common::wipe;
+use super::{Error, PublicKey, SecretKey};
+
+const PREFIX: &str = "ed25519:";
+
+fn decode_near(s: &str, max_len: usize) -> Result<Vec<u8>, Error> {
+    let b58 = s.trim().strip_prefix(PREFIX).ok_or(Error::ParseError)?;
+    base58::decode(b58, max_len)
+}
+
+impl PublicKey {
+    /// Returns the public key in the NEAR format: `ed25519:<base58>`.
+    pub fn to_near_string(&self) -> String {
+        format!("{}{}", PREFIX, base58::encode(&self[..]))
+    }
+
+    /// Imports a public key in the NEAR format: `ed25519:<base58>`.
+    pub fn from_near_str(s: &str) -> Result<Self, Error> {
+        PublicKey::from_slice(&decode_near(s, PublicKey::BYTES)?)
+    }
+}
+
+impl SecretKey {
+    /// Returns the secret key in the NEAR format: `ed25519:<base58>`, where
+    /// the encoded value is the 64-byte secret key.
+    pub fn to_near_string(&self) -> String {
+        format!("{}{}", PREFIX, base58::encode(&self[..]))
+    }
+
+    /// Imports a secret key in the NEAR format: `ed25519:<base58>`.
+    ///
+    /// The public key embedded in the secret key is validated.
+    pub fn from_near_str(s: &str) -> Result<Self, Error> {
+        let mut bin = decode_near(s, SecretKey::BYTES)?;
+        let sk = if bin.len() == SecretKey::BYTES {
+            SecretKey::from_slice(&bin)
+        } else {
+            Err(Error::InvalidSecretKey)
+        };
+        wipe(&mut bin);
+        let sk = sk?;
+        sk.validate()?;
+        Ok(sk)
+    }
+}
+
+#[test]
+fn test_near() {
+    use super::{KeyPair, Seed};
+
+    let kp = KeyPair::from_seed(Seed::new([3u8; Seed::BYTES]));
+    let pk = kp.pk.to_near_string();
+    assert_eq!(pk, "ed25519:GyGKxMyg1p9SsHfm15MkNUu1u9TN2JtTspcdmrtGUdse");
+    assert_eq!(PublicKey::from_near_str(&pk).unwrap(), kp.pk);
+    let sk = kp.sk.to_near_string();
+    assert_eq!(
+        sk,
+        "ed25519:4VZdodJgBy6dxMgm45zusmRzrPvKtiumu5YrK9RLPJAVbW5qTGHqsYeFR8HsFWEh71pjwJffSPkmficrRvk3p3a"
+    );
+    assert_eq!(SecretKey::from_near_str(&sk).unwrap(), kp.sk);
+
+    assert!(
+        PublicKey::from_near_str("secp256k1:GyGKxMyg1p9SsHfm15MkNUu1u9TN2JtTspcdmrtGUdse").is_err()
+    );
+    assert!(SecretKey::from_near_str(&pk).is_err());
+    let other = KeyPair::from_seed(Seed::new([4u8; Seed::BYTES]));
+    let mut mixed = [0u8; SecretKey::BYTES];
+    mixed[..32].copy_from_slice(&kp.sk[..32]);
+    mixed[32..].copy_from_slice(&other.pk[..]);
+    let mixed = format!("ed25519:{}", base58::encode(&mixed));
+    assert_eq!(
+        SecretKey::from_near_str(&mixed),
+        Err(Error::InvalidSecretKey)
+    );
+}
@@ -0,0 +1,127 @@
+use ct_codecs::{Base64UrlSafeNoPadding, Decoder, Encoder};
+
+use super::common::ct_eq;
+use super::{Error, KeyPair, PublicKey, Signature};
+
+const HEADER: &str = "v4.public.";
+
+/// Pre-authentication encoding (PASETO, Section 2.2).
+fn pae(pieces: &[&[u8]]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(8 + pieces.iter().map(|p| 8 + p.len()).sum::<usize>());
+    out.extend_from_slice(&(pieces.len() as u64).to_le_bytes());
+    for piece in pieces {
+        out.extend_from_slice(&(piece.len() as u64 & (u64::MAX >> 1)).to_le_bytes());
+        out.extend_from_slice(piece);
+    }
+    out
+}
+
+/// Splits a token into its decoded body and footer.
+fn split_token(token: &str) -> Result<(Vec<u8>, Vec<u8>), Error> {
+    let rest = token.strip_prefix(HEADER).ok_or(Error::ParseError)?;
+    let mut parts = rest.split('.');
+    let body = parts.next().ok_or(Error::ParseError)?;
+    let footer = parts.next().unwrap_or("");
+    if parts.next().is_some() {
+        return Err(Error::ParseError);
+    }
+    let body =
+        Base64UrlSafeNoPadding::decode_to_vec(body, None).map_err(|_| Error::InvalidBase64)?;
+    let footer =
+        Base64UrlSafeNoPadding::decode_to_vec(footer, None).map_err(|_| Error::InvalidBase64)?;
+    if body.len() < Signature::BYTES {
+        return Err(Error::ParseError);
+    }
+    Ok((body, footer))
+}
+
+/// Returns the footer of a PASETO token, without verifying the token.
+///
+/// This can be used to find the key a token was signed with, before calling
+/// `PublicKey::verify_paseto()`.
+pub fn paseto_footer(token: &str) -> Result<Vec<u8>, Error> {
+    split_token(token).map(|(_, footer)| footer)
+}
+
+impl KeyPair {
+    /// Creates a PASETO `v4.public` token for `message`, with an optional
+    /// `footer` (sent in the clear, but authenticated) and an optional
+    /// `implicit` assertion (authenticated, but not sent).
+    pub fn sign_paseto(&self, message: &[u8], footer: &[u8], implicit: &[u8]) -> String {
+        let m2 = pae(&[HEADER.as_bytes(), message, footer, implicit]);
+        let signature = self.sk.sign(m2, None);
+        let mut body = Vec::with_capacity(message.len() + Signature::BYTES);
+        body.extend_from_slice(message);
+        body.extend_from_slice(&signature[..]);
+        let mut token = String::from(HEADER);
+        token.push_str(&Base64UrlSafeNoPadding::encode_to_string(body).unwrap());
+        if !footer.is_empty() {
+            token.push('.');
+            token.push_str(&Base64UrlSafeNoPadding::encode_to_string(footer).unwrap());
+        }
+        token
+    }
+}
+
+impl PublicKey {
+    /// Verifies a PASETO `v4.public` token, and returns its message.
+    ///
+    /// If `expected_footer` is set, the footer of the token must be equal to
+    /// it. `implicit` must be the implicit assertion the token was created
+    /// with.
+    pub fn verify_paseto(
+        &self,
+        token: &str,
+        expected_footer: Option<&[u8]>,
+        implicit: &[u8],
+    ) -> Result<Vec<u8>, Error> {
+        let (mut body, footer) = split_token(token)?;
+        if let Some(expected_footer) = expected_footer {
+            if !ct_eq(expected_footer, &footer) {
+                return Err(Error::SignatureMismatch);
+            }
+        }
+        let signature = Signature::from_slice(&body[body.len() - Signature::BYTES..])?;
+        body.truncate(body.len() - Signature::BYTES);
+        let m2 = pae(&[HEADER.as_bytes(), &body, &footer, implicit]);
+        self.verify(m2, &signature)?;
+        Ok(body)
+    }
+}
+
+#[test]
+fn test_paseto() {
+    use super::{SecretKey, Seed};
+
+    // PASETO test vectors 4-S-1 and 4-S-3
+    let sk = SecretKey::new([
+        0xb4, 0xcb, 0xfb, 0x43, 0xdf, 0x4c, 0xe2, 0x10, 0x72, 0x7d, 0x95, 0x3e, 0x4a, 0x71, 0x33,
+        0x07, 0xfa, 0x19, 0xbb, 0x7d, 0x9f, 0x85, 0x04, 0x14, 0x38, 0xd9, 0xe1, 0x1b, 0x94, 0x2a,
+        0x37, 0x74, 0x1e, 0xb9, 0xdb, 0xbb, 0xbc, 0x04, 0x7c, 0x03, 0xfd, 0x70, 0x60, 0x4e, 0x00,
+        0x71, 0xf0, 0x98, 0x7e, 0x16, 0xb2, 0x8b, 0x75, 0x72, 0x25, 0xc1, 0x1f, 0x00, 0x41, 0x5d,
+        0x0e, 0x20, 0xb1, 0xa2,
+    ]);
+    let kp = KeyPair::from_seed(Seed::from_slice(&sk.seed()[..]).unwrap());
+    assert_eq!(kp.sk, sk);
+    let message = br#"{"data":"this is a signed message","exp":"2022-01-01T00:00:00+00:00"}"#;
+    let footer = br#"{"kid":"zVhMiPBP9fRf2snEcT7gFTioeA9COcNy9DfgL1W60haN"}"#;
+    let implicit = br#"{"test-vector":"4-S-3"}"#;
+
+    let token = kp.sign_paseto(message, b"", b"");
+    assert_eq!(token, "v4.public.eyJkYXRhIjoidGhpcyBpcyBhIHNpZ25lZCBtZXNzYWdlIiwiZXhwIjoiMjAyMi0wMS0wMVQwMDowMDowMCswMDowMCJ9bg_XBBzds8lTZShVlwwKSgeKpLT3yukTw6JUz3W4h_ExsQV-P0V54zemZDcAxFaSeef1QlXEFtkqxT1ciiQEDA");
+    assert_eq!(kp.pk.verify_paseto(&token, None, b"").unwrap(), message);
+
+    let token = kp.sign_paseto(message, footer, implicit);
+    assert_eq!(token, "v4.public.eyJkYXRhIjoidGhpcyBpcyBhIHNpZ25lZCBtZXNzYWdlIiwiZXhwIjoiMjAyMi0wMS0wMVQwMDowMDowMCswMDowMCJ9NPWciuD3d0o5eXJXG5pJy-DiVEoyPYWs1YSTwWHNJq6DZD3je5gf-0M4JR9ipdUSJbIovzmBECeaWmaqcaP0DQ.eyJraWQiOiJ6VmhNaVBCUDlmUmYyc25FY1Q3Z0ZUaW9lQTlDT2NOeTlEZmdMMVc2MGhhTiJ9");
+    assert_eq!(paseto_footer(&token).unwrap(), footer);
+    assert_eq!(
+        kp.pk.verify_paseto(&token, Some(footer), implicit).unwrap(),
+        message
+    );
+    assert!(kp.pk.verify_paseto(&token, Some(b""), implicit).is_err());
+    assert!(kp.pk.verify_paseto(&token, None, b"").is_err());
+    assert!(kp
+        .pk
+        .verify_paseto(&token.replacen("v4.", "v3.", 1), None, implicit)
+        .is_err());
+}
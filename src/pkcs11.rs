@@ -0,0 +1,113 @@
+//! PKCS#11 signing backend.
+//!
+//! Signatures are computed by a token (HSM, smart card...) using the
+//! `CKM_EDDSA` mechanism, so that the secret key never leaves it.
+
+use std::sync::Mutex;
+
+use cryptoki::error::Error as Pkcs11Error;
+use cryptoki::mechanism::eddsa::{EddsaParams, EddsaSignatureScheme};
+use cryptoki::mechanism::Mechanism;
+use cryptoki::object::{Attribute, AttributeType, KeyType, ObjectClass, ObjectHandle};
+use cryptoki::session::Session;
+
+use super::{Error, PublicKey, Signature, Signer};
+
+/// An Ed25519 key stored in a PKCS#11 token.
+pub struct Pkcs11Signer {
+    session: Mutex<Session>,
+    key: ObjectHandle,
+    pk: PublicKey,
+}
+
+impl Pkcs11Signer {
+    /// Looks up the Ed25519 key pair labeled `label` in an open session.
+    ///
+    /// The session must already be logged in if the token requires it.
+    /// Returns `Ok(None)` if the token doesn't contain both a private and a
+    /// public Ed25519 key with that label.
+    pub fn find(session: Session, label: &str) -> Result<Option<Self>, Pkcs11Error> {
+        let template = |class| {
+            [
+                Attribute::Class(class),
+                Attribute::KeyType(KeyType::EC_EDWARDS),
+                Attribute::Label(label.as_bytes().to_vec()),
+            ]
+        };
+        let key = match session
+            .find_objects(&template(ObjectClass::PRIVATE_KEY))?
+            .first()
+        {
+            Some(&key) => key,
+            None => return Ok(None),
+        };
+        let public_key = match session
+            .find_objects(&template(ObjectClass::PUBLIC_KEY))?
+            .first()
+        {
+            Some(&public_key) => public_key,
+            None => return Ok(None),
+        };
+        let pk = match session
+            .get_attributes(public_key, &[AttributeType::EcPoint])?
+            .first()
+        {
+            Some(Attribute::EcPoint(ec_point)) => {
+                parse_ec_point(ec_point).ok_or(Pkcs11Error::InvalidValue)?
+            }
+            _ => return Err(Pkcs11Error::InvalidValue),
+        };
+        Ok(Some(Pkcs11Signer {
+            session: Mutex::new(session),
+            key,
+            pk,
+        }))
+    }
+
+    /// Returns the session, for operations this module doesn't cover.
+    pub fn into_session(self) -> Session {
+        self.session.into_inner().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Signer for Pkcs11Signer {
+    fn public_key(&self) -> PublicKey {
+        self.pk
+    }
+
+    /// Computes a signature using the token. The signature is verified
+    /// before being returned. Token errors are reported as
+    /// `Error::SignerFailure`.
+    fn sign(&self, message: &[u8]) -> Result<Signature, Error> {
+        let mechanism = Mechanism::Eddsa(EddsaParams::new(EddsaSignatureScheme::Pure));
+        let signature = self
+            .session
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .sign(&mechanism, self.key, message)
+            .map_err(|_| Error::SignerFailure)?;
+        let signature = Signature::from_slice(&signature)?;
+        self.pk.verify(message, &signature)?;
+        Ok(signature)
+    }
+}
+
+/// Decodes a `CKA_EC_POINT` attribute. Tokens store it either as a DER
+/// octet string, as PKCS#11 requires, or as the raw public key.
+fn parse_ec_point(ec_point: &[u8]) -> Option<PublicKey> {
+    let raw = match ec_point {
+        [4, 32, raw @ ..] if raw.len() == PublicKey::BYTES => raw,
+        raw => raw,
+    };
+    PublicKey::from_slice(raw).ok()
+}
+
+#[test]
+fn test_pkcs11() {
+    let pk = [42u8; PublicKey::BYTES];
+    let mut der = vec![4, 32];
+    der.extend_from_slice(&pk);
+    assert_eq!(parse_ec_point(&der), Some(PublicKey::new(pk)));
+    assert_eq!(parse_ec_point(&pk), Some(PublicKey::new(pk)));
+    assert_eq!(parse_ec_point(&der[1..]), None);
+}
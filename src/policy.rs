@@ -0,0 +1,132 @@
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+use super::{Error, PublicKey, Signature, Signer};
+
+/// Restrictions on the use of a signing key.
+///
+/// The default policy doesn't restrict anything.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct SigningPolicy {
+    /// Maximum number of signatures that can be computed.
+    pub max_signatures: Option<u64>,
+    /// If set, only messages starting with one of these domain separation
+    /// prefixes can be signed.
+    pub allowed_domains: Option<Vec<Vec<u8>>>,
+    /// Signatures can't be computed before this time.
+    pub not_before: Option<SystemTime>,
+    /// Signatures can't be computed after this time.
+    pub not_after: Option<SystemTime>,
+}
+
+impl SigningPolicy {
+    fn check(&self, message: &[u8], count: u64, now: SystemTime) -> Result<(), Error> {
+        if matches!(self.max_signatures, Some(max) if count >= max) {
+            return Err(Error::PolicyViolation);
+        }
+        if let Some(domains) = &self.allowed_domains {
+            if !domains.iter().any(|domain| message.starts_with(domain)) {
+                return Err(Error::PolicyViolation);
+            }
+        }
+        if matches!(self.not_before, Some(t) if now < t)
+            || matches!(self.not_after, Some(t) if now > t)
+        {
+            return Err(Error::PolicyViolation);
+        }
+        Ok(())
+    }
+}
+
+/// A `Signer` that only computes signatures allowed by a `SigningPolicy`,
+/// for example for ephemeral session keys.
+///
+/// Signatures that are not allowed are rejected with
+/// `Error::PolicyViolation`.
+pub struct RateLimitedSigner<S: Signer> {
+    signer: S,
+    policy: SigningPolicy,
+    count: Mutex<u64>,
+}
+
+impl<S: Signer> RateLimitedSigner<S> {
+    /// Wraps `signer`, restricting its use according to `policy`.
+    pub fn new(signer: S, policy: SigningPolicy) -> Self {
+        RateLimitedSigner {
+            signer,
+            policy,
+            count: Mutex::new(0),
+        }
+    }
+
+    /// Returns the policy.
+    pub fn policy(&self) -> &SigningPolicy {
+        &self.policy
+    }
+
+    /// Returns the number of signatures computed so far.
+    pub fn signature_count(&self) -> u64 {
+        *self.count.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Computes a signature for the message `message`, if the policy allows
+    /// it at time `now`.
+    pub fn sign_at(&self, message: &[u8], now: SystemTime) -> Result<Signature, Error> {
+        let mut count = self.count.lock().unwrap_or_else(|e| e.into_inner());
+        self.policy.check(message, *count, now)?;
+        let signature = self.signer.sign(message)?;
+        *count += 1;
+        Ok(signature)
+    }
+
+    /// Returns the wrapped signer.
+    pub fn into_inner(self) -> S {
+        self.signer
+    }
+}
+
+impl<S: Signer> Signer for RateLimitedSigner<S> {
+    fn public_key(&self) -> PublicKey {
+        self.signer.public_key()
+    }
+
+    /// Computes a signature if the policy allows it at the current time.
+    fn sign(&self, message: &[u8]) -> Result<Signature, Error> {
+        self.sign_at(message, SystemTime::now())
+    }
+}
+
+#[test]
+fn test_policy() {
+    use std::time::Duration;
+
+    use super::{KeyPair, Seed};
+
+    let kp = KeyPair::from_seed(Seed::new([5u8; Seed::BYTES]));
+    let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+    let policy = SigningPolicy {
+        max_signatures: Some(2),
+        allowed_domains: Some(vec![b"session:".to_vec()]),
+        not_before: Some(now - Duration::from_secs(60)),
+        not_after: Some(now + Duration::from_secs(60)),
+    };
+    let signer = RateLimitedSigner::new(&kp, policy);
+    assert_eq!(signer.public_key(), kp.pk);
+    let signature = signer.sign_at(b"session:hello", now).unwrap();
+    assert!(kp.pk.verify(b"session:hello", &signature).is_ok());
+    assert_eq!(
+        signer.sign_at(b"other:hello", now),
+        Err(Error::PolicyViolation)
+    );
+    assert_eq!(
+        signer.sign_at(b"session:hello", now + Duration::from_secs(61)),
+        Err(Error::PolicyViolation)
+    );
+    assert_eq!(signer.signature_count(), 1);
+    assert!(signer.sign_at(b"session:again", now).is_ok());
+    assert_eq!(
+        signer.sign_at(b"session:hello", now),
+        Err(Error::PolicyViolation)
+    );
+    assert_eq!(signer.signature_count(), 2);
+}
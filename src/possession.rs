@@ -0,0 +1,55 @@
+use super::{Error, PublicKey, SecretKey, Signature};
+
+const DOMAIN: &[u8] = b"ed25519-compact proof of possession v1";
+
+impl SecretKey {
+    /// Proves possession of the secret key, by signing the public key along
+    /// with an application-defined context (for example a registration
+    /// challenge or a user identifier).
+    ///
+    /// The message is domain-separated, so a proof can't be mistaken for a
+    /// signature made by the application for another purpose.
+    ///
+    /// Returns `Error::FaultDetected` if a fault is detected while signing.
+    pub fn prove_possession(&self, context: &[u8]) -> Result<Signature, Error> {
+        let pk = self.public_key();
+        self.try_sign_vectored(
+            &[
+                DOMAIN,
+                &(context.len() as u64).to_le_bytes(),
+                context,
+                &pk[..],
+            ],
+            None,
+        )
+    }
+}
+
+impl PublicKey {
+    /// Verifies a proof of possession created with
+    /// `SecretKey::prove_possession()` for the same context.
+    pub fn verify_possession(&self, proof: &Signature, context: &[u8]) -> Result<(), Error> {
+        self.verify_vectored(
+            &[
+                DOMAIN,
+                &(context.len() as u64).to_le_bytes(),
+                context,
+                &self[..],
+            ],
+            proof,
+        )
+    }
+}
+
+#[test]
+fn test_possession() {
+    use super::{KeyPair, Seed};
+
+    let kp = KeyPair::from_seed(Seed::new([1u8; Seed::BYTES]));
+    let kp2 = KeyPair::from_seed(Seed::new([2u8; Seed::BYTES]));
+    let proof = kp.sk.prove_possession(b"challenge 1234").unwrap();
+    assert!(kp.pk.verify_possession(&proof, b"challenge 1234").is_ok());
+    assert!(kp.pk.verify_possession(&proof, b"challenge 1235").is_err());
+    assert!(kp2.pk.verify_possession(&proof, b"challenge 1234").is_err());
+    assert!(kp.pk.verify(b"challenge 1234", &proof).is_err());
+}
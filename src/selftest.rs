@@ -0,0 +1,145 @@
+//! Known-answer self tests.
+
+use super::error::Error;
+use super::sha512;
+#[cfg(not(feature = "disable-signatures"))]
+use super::{KeyPair, Seed, Signature};
+
+/// Runs known-answer tests over SHA-512, Ed25519 signing and verification
+/// (RFC 8032) and X25519 (RFC 7748).
+///
+/// Returns `Error::SelfTestFailed` if any of them doesn't produce the expected
+/// output. Products that must perform startup self tests can call this
+/// function before using any other part of the crate.
+pub fn self_test() -> Result<(), Error> {
+    self_test_sha512()?;
+    #[cfg(not(feature = "disable-signatures"))]
+    self_test_ed25519()?;
+    #[cfg(feature = "x25519")]
+    self_test_x25519()?;
+    Ok(())
+}
+
+fn self_test_sha512() -> Result<(), Error> {
+    const EXPECTED: [u8; 64] = [
+        0xdd, 0xaf, 0x35, 0xa1, 0x93, 0x61, 0x7a, 0xba, 0xcc, 0x41, 0x73, 0x49, 0xae, 0x20, 0x41,
+        0x31, 0x12, 0xe6, 0xfa, 0x4e, 0x89, 0xa9, 0x7e, 0xa2, 0x0a, 0x9e, 0xee, 0xe6, 0x4b, 0x55,
+        0xd3, 0x9a, 0x21, 0x92, 0x99, 0x2a, 0x27, 0x4f, 0xc1, 0xa8, 0x36, 0xba, 0x3c, 0x23, 0xa3,
+        0xfe, 0xeb, 0xbd, 0x45, 0x4d, 0x44, 0x23, 0x64, 0x3c, 0xe8, 0x0e, 0x2a, 0x9a, 0xc9, 0x4f,
+        0xa5, 0x4c, 0xa4, 0x9f,
+    ];
+    if sha512::Hash::hash(b"abc") != EXPECTED {
+        return Err(Error::SelfTestFailed);
+    }
+    Ok(())
+}
+
+#[cfg(not(feature = "disable-signatures"))]
+fn self_test_ed25519() -> Result<(), Error> {
+    struct Kat {
+        seed: [u8; 32],
+        pk: [u8; 32],
+        message: &'static [u8],
+        signature: [u8; 64],
+    }
+
+    // RFC 8032, section 7.1, tests 1 and 2
+    const VECTORS: [Kat; 2] = [
+        Kat {
+            seed: [
+                0x9d, 0x61, 0xb1, 0x9d, 0xef, 0xfd, 0x5a, 0x60, 0xba, 0x84, 0x4a, 0xf4, 0x92, 0xec,
+                0x2c, 0xc4, 0x44, 0x49, 0xc5, 0x69, 0x7b, 0x32, 0x69, 0x19, 0x70, 0x3b, 0xac, 0x03,
+                0x1c, 0xae, 0x7f, 0x60,
+            ],
+            pk: [
+                0xd7, 0x5a, 0x98, 0x01, 0x82, 0xb1, 0x0a, 0xb7, 0xd5, 0x4b, 0xfe, 0xd3, 0xc9, 0x64,
+                0x07, 0x3a, 0x0e, 0xe1, 0x72, 0xf3, 0xda, 0xa6, 0x23, 0x25, 0xaf, 0x02, 0x1a, 0x68,
+                0xf7, 0x07, 0x51, 0x1a,
+            ],
+            message: b"",
+            signature: [
+                0xe5, 0x56, 0x43, 0x00, 0xc3, 0x60, 0xac, 0x72, 0x90, 0x86, 0xe2, 0xcc, 0x80, 0x6e,
+                0x82, 0x8a, 0x84, 0x87, 0x7f, 0x1e, 0xb8, 0xe5, 0xd9, 0x74, 0xd8, 0x73, 0xe0, 0x65,
+                0x22, 0x49, 0x01, 0x55, 0x5f, 0xb8, 0x82, 0x15, 0x90, 0xa3, 0x3b, 0xac, 0xc6, 0x1e,
+                0x39, 0x70, 0x1c, 0xf9, 0xb4, 0x6b, 0xd2, 0x5b, 0xf5, 0xf0, 0x59, 0x5b, 0xbe, 0x24,
+                0x65, 0x51, 0x41, 0x43, 0x8e, 0x7a, 0x10, 0x0b,
+            ],
+        },
+        Kat {
+            seed: [
+                0x4c, 0xcd, 0x08, 0x9b, 0x28, 0xff, 0x96, 0xda, 0x9d, 0xb6, 0xc3, 0x46, 0xec, 0x11,
+                0x4e, 0x0f, 0x5b, 0x8a, 0x31, 0x9f, 0x35, 0xab, 0xa6, 0x24, 0xda, 0x8c, 0xf6, 0xed,
+                0x4f, 0xb8, 0xa6, 0xfb,
+            ],
+            pk: [
+                0x3d, 0x40, 0x17, 0xc3, 0xe8, 0x43, 0x89, 0x5a, 0x92, 0xb7, 0x0a, 0xa7, 0x4d, 0x1b,
+                0x7e, 0xbc, 0x9c, 0x98, 0x2c, 0xcf, 0x2e, 0xc4, 0x96, 0x8c, 0xc0, 0xcd, 0x55, 0xf1,
+                0x2a, 0xf4, 0x66, 0x0c,
+            ],
+            message: b"\x72",
+            signature: [
+                0x92, 0xa0, 0x09, 0xa9, 0xf0, 0xd4, 0xca, 0xb8, 0x72, 0x0e, 0x82, 0x0b, 0x5f, 0x64,
+                0x25, 0x40, 0xa2, 0xb2, 0x7b, 0x54, 0x16, 0x50, 0x3f, 0x8f, 0xb3, 0x76, 0x22, 0x23,
+                0xeb, 0xdb, 0x69, 0xda, 0x08, 0x5a, 0xc1, 0xe4, 0x3e, 0x15, 0x99, 0x6e, 0x45, 0x8f,
+                0x36, 0x13, 0xd0, 0xf1, 0x1d, 0x8c, 0x38, 0x7b, 0x2e, 0xae, 0xb4, 0x30, 0x2a, 0xee,
+                0xb0, 0x0d, 0x29, 0x16, 0x12, 0xbb, 0x0c, 0x00,
+            ],
+        },
+    ];
+    for kat in VECTORS.iter() {
+        let kp = KeyPair::try_from_seed(Seed::new(kat.seed)).map_err(|_| Error::SelfTestFailed)?;
+        if kp.pk[..] != kat.pk[..] {
+            return Err(Error::SelfTestFailed);
+        }
+        let signature = kp
+            .sk
+            .try_sign(kat.message, None)
+            .map_err(|_| Error::SelfTestFailed)?;
+        if signature != Signature::new(kat.signature) {
+            return Err(Error::SelfTestFailed);
+        }
+        kp.pk
+            .verify(kat.message, &signature)
+            .map_err(|_| Error::SelfTestFailed)?;
+        let mut forged = kat.signature;
+        forged[0] ^= 1;
+        if kp.pk.verify(kat.message, &Signature::new(forged)).is_ok() {
+            return Err(Error::SelfTestFailed);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(feature = "x25519")]
+fn self_test_x25519() -> Result<(), Error> {
+    use super::x25519;
+
+    // RFC 7748, section 5.2, first test vector
+    const SCALAR: [u8; 32] = [
+        0xa5, 0x46, 0xe3, 0x6b, 0xf0, 0x52, 0x7c, 0x9d, 0x3b, 0x16, 0x15, 0x4b, 0x82, 0x46, 0x5e,
+        0xdd, 0x62, 0x14, 0x4c, 0x0a, 0xc1, 0xfc, 0x5a, 0x18, 0x50, 0x6a, 0x22, 0x44, 0xba, 0x44,
+        0x9a, 0xc4,
+    ];
+    const U: [u8; 32] = [
+        0xe6, 0xdb, 0x68, 0x67, 0x58, 0x30, 0x30, 0xdb, 0x35, 0x94, 0xc1, 0xa4, 0x24, 0xb1, 0x5f,
+        0x7c, 0x72, 0x66, 0x24, 0xec, 0x26, 0xb3, 0x35, 0x3b, 0x10, 0xa9, 0x03, 0xa6, 0xd0, 0xab,
+        0x1c, 0x4c,
+    ];
+    const EXPECTED: [u8; 32] = [
+        0xc3, 0xda, 0x55, 0x37, 0x9d, 0xe9, 0xc6, 0x90, 0x8e, 0x94, 0xea, 0x4d, 0xf2, 0x8d, 0x08,
+        0x4f, 0x32, 0xec, 0xcf, 0x03, 0x49, 0x1c, 0x71, 0xf7, 0x54, 0xb4, 0x07, 0x55, 0x77, 0xa2,
+        0x85, 0x52,
+    ];
+    let pk = x25519::PublicKey::from_slice(&U).map_err(|_| Error::SelfTestFailed)?;
+    let sk = x25519::SecretKey::new(SCALAR);
+    let shared = pk.dh(&sk).map_err(|_| Error::SelfTestFailed)?;
+    if shared[..] != EXPECTED[..] {
+        return Err(Error::SelfTestFailed);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_self_test() {
+    assert!(self_test().is_ok());
+}
@@ -0,0 +1,189 @@
+//! A small, self-contained SHA1 implementation, with HMAC-SHA1.
+//!
+//! SHA1 is only used where legacy formats require it.
+
+#![allow(clippy::many_single_char_names, clippy::unreadable_literal)]
+
+const IV: [u32; 5] = [0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476, 0xc3d2e1f0];
+
+#[derive(Copy, Clone)]
+struct State([u32; 5]);
+
+impl State {
+    fn new() -> Self {
+        State(IV)
+    }
+
+    fn blocks(&mut self, mut input: &[u8]) -> usize {
+        while input.len() >= 64 {
+            let mut w = [0u32; 80];
+            for (i, e) in w.iter_mut().take(16).enumerate() {
+                *e = u32::from_be_bytes([
+                    input[i * 4],
+                    input[i * 4 + 1],
+                    input[i * 4 + 2],
+                    input[i * 4 + 3],
+                ]);
+            }
+            for i in 16..80 {
+                w[i] = (w[i - 3] ^ w[i - 8] ^ w[i - 14] ^ w[i - 16]).rotate_left(1);
+            }
+            let [mut a, mut b, mut c, mut d, mut e] = self.0;
+            for (i, wi) in w.iter().enumerate() {
+                let (f, k) = match i {
+                    0..=19 => ((b & c) | (!b & d), 0x5a827999),
+                    20..=39 => (b ^ c ^ d, 0x6ed9eba1),
+                    40..=59 => ((b & c) | (b & d) | (c & d), 0x8f1bbcdc),
+                    _ => (b ^ c ^ d, 0xca62c1d6),
+                };
+                let t = a
+                    .rotate_left(5)
+                    .wrapping_add(f)
+                    .wrapping_add(e)
+                    .wrapping_add(k)
+                    .wrapping_add(*wi);
+                e = d;
+                d = c;
+                c = b.rotate_left(30);
+                b = a;
+                a = t;
+            }
+            for (s, x) in self.0.iter_mut().zip([a, b, c, d, e]) {
+                *s = s.wrapping_add(x);
+            }
+            input = &input[64..];
+        }
+        input.len()
+    }
+}
+
+#[derive(Copy, Clone)]
+pub struct Hash {
+    state: State,
+    w: [u8; 64],
+    r: usize,
+    len: usize,
+}
+
+impl Hash {
+    pub fn new() -> Hash {
+        Hash {
+            state: State::new(),
+            r: 0,
+            w: [0u8; 64],
+            len: 0,
+        }
+    }
+
+    /// Absorb content
+    pub fn update<T: AsRef<[u8]>>(&mut self, input: T) {
+        let input = input.as_ref();
+        let mut n = input.len();
+        self.len += n;
+        let av = 64 - self.r;
+        let tc = ::core::cmp::min(n, av);
+        self.w[self.r..self.r + tc].copy_from_slice(&input[0..tc]);
+        self.r += tc;
+        n -= tc;
+        let pos = tc;
+        if self.r == 64 {
+            self.state.blocks(&self.w);
+            self.r = 0;
+        }
+        if self.r == 0 && n > 0 {
+            let rb = self.state.blocks(&input[pos..]);
+            if rb > 0 {
+                self.w[..rb].copy_from_slice(&input[pos + n - rb..]);
+                self.r = rb;
+            }
+        }
+    }
+
+    /// Compute SHA1(absorbed content)
+    pub fn finalize(mut self) -> [u8; 20] {
+        let mut padded = [0u8; 128];
+        padded[..self.r].copy_from_slice(&self.w[..self.r]);
+        padded[self.r] = 0x80;
+        let r = if self.r < 56 { 64 } else { 128 };
+        let bits = self.len as u64 * 8;
+        padded[r - 8..r].copy_from_slice(&bits.to_be_bytes());
+        self.state.blocks(&padded[..r]);
+        let mut out = [0u8; 20];
+        for (i, e) in self.state.0.iter().enumerate() {
+            out[i * 4..i * 4 + 4].copy_from_slice(&e.to_be_bytes());
+        }
+        out
+    }
+
+    /// Compute SHA1(`input`)
+    pub fn hash<T: AsRef<[u8]>>(input: T) -> [u8; 20] {
+        let mut h = Hash::new();
+        h.update(input);
+        h.finalize()
+    }
+}
+
+impl Default for Hash {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Compute HMAC-SHA1(`key`, `input`)
+pub fn hmac(key: &[u8], input: &[u8]) -> [u8; 20] {
+    let mut k = [0u8; 64];
+    if key.len() > 64 {
+        k[..20].copy_from_slice(&Hash::hash(key));
+    } else {
+        k[..key.len()].copy_from_slice(key);
+    }
+    let mut ipad = [0x36u8; 64];
+    let mut opad = [0x5cu8; 64];
+    for ((i, o), k) in ipad.iter_mut().zip(opad.iter_mut()).zip(k.iter()) {
+        *i ^= k;
+        *o ^= k;
+    }
+    let mut ih = Hash::new();
+    ih.update(ipad);
+    ih.update(input);
+    let mut oh = Hash::new();
+    oh.update(opad);
+    oh.update(ih.finalize());
+    oh.finalize()
+}
+
+#[test]
+fn test_sha1() {
+    assert_eq!(
+        Hash::hash(b"abc"),
+        [
+            0xa9, 0x99, 0x3e, 0x36, 0x47, 0x06, 0x81, 0x6a, 0xba, 0x3e, 0x25, 0x71, 0x78, 0x50,
+            0xc2, 0x6c, 0x9c, 0xd0, 0xd8, 0x9d,
+        ]
+    );
+    let mut h = Hash::new();
+    for _ in 0..1000 {
+        h.update([0x61u8; 1000]);
+    }
+    assert_eq!(
+        h.finalize(),
+        [
+            0x34, 0xaa, 0x97, 0x3c, 0xd4, 0xc4, 0xda, 0xa4, 0xf6, 0x1e, 0xeb, 0x2b, 0xdb, 0xad,
+            0x27, 0x31, 0x65, 0x34, 0x01, 0x6f,
+        ]
+    );
+    assert_eq!(
+        hmac(b"key", b"The quick brown fox jumps over the lazy dog"),
+        [
+            0xde, 0x7c, 0x9b, 0x85, 0xb8, 0xb7, 0x8a, 0xa6, 0xbc, 0x8a, 0x7a, 0x36, 0xf7, 0x0a,
+            0x90, 0x70, 0x1c, 0x9d, 0xb4, 0xd9,
+        ]
+    );
+    assert_eq!(
+        hmac(&[b'k'; 100], b"msg"),
+        [
+            0xa3, 0xf6, 0xe9, 0x0d, 0x79, 0x2b, 0x6c, 0xa5, 0xed, 0x3b, 0xfc, 0x55, 0x6a, 0x4a,
+            0xa7, 0x3d, 0x62, 0xd7, 0xca, 0xe4,
+        ]
+    );
+}
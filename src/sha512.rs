@@ -371,8 +371,103 @@ impl Hash {
     }
 }
 
+#[cfg(not(feature = "disable-signatures"))]
+impl Hash {
+    /// Number of bytes in a serialized hash state.
+    pub const STATE_BYTES: usize = 64 + 8 + 128;
+
+    /// Serializes the state of the hash function, to resume the computation
+    /// later with `from_state()`.
+    pub fn to_state(&self) -> [u8; Hash::STATE_BYTES] {
+        let mut bytes = [0u8; Hash::STATE_BYTES];
+        self.state.store(&mut bytes[0..64]);
+        store_be(&mut bytes, 64, self.len as u64);
+        bytes[72..72 + self.r].copy_from_slice(&self.w[..self.r]);
+        bytes
+    }
+
+    /// Restores a hash function from a state serialized with `to_state()`.
+    pub fn from_state(bytes: &[u8; Hash::STATE_BYTES]) -> Hash {
+        let mut state = State([0u64; 8]);
+        for (i, e) in state.0.iter_mut().enumerate() {
+            *e = load_be(bytes, i * 8);
+        }
+        let len = load_be(bytes, 64) as usize;
+        let r = len % 128;
+        let mut w = [0u8; 128];
+        w[..r].copy_from_slice(&bytes[72..72 + r]);
+        Hash { state, w, r, len }
+    }
+}
+
 impl Default for Hash {
     fn default() -> Self {
         Self::new()
     }
 }
+
+/// HMAC-SHA512
+#[derive(Copy, Clone)]
+pub struct Hmac {
+    ih: Hash,
+    oh: Hash,
+}
+
+impl Hmac {
+    /// Create a new HMAC-SHA512 state keyed with `key`
+    pub fn new(key: &[u8]) -> Hmac {
+        let mut k = [0u8; 128];
+        if key.len() > 128 {
+            k[..64].copy_from_slice(&Hash::hash(key));
+        } else {
+            k[..key.len()].copy_from_slice(key);
+        }
+        let mut ipad = [0x36u8; 128];
+        let mut opad = [0x5cu8; 128];
+        for ((i, o), k) in ipad.iter_mut().zip(opad.iter_mut()).zip(k.iter()) {
+            *i ^= k;
+            *o ^= k;
+        }
+        let mut ih = Hash::new();
+        ih.update(ipad);
+        let mut oh = Hash::new();
+        oh.update(opad);
+        Hmac { ih, oh }
+    }
+
+    /// Absorb content
+    pub fn update<T: AsRef<[u8]>>(&mut self, input: T) {
+        self.ih.update(input)
+    }
+
+    /// Compute HMAC-SHA512(key, absorbed content)
+    pub fn finalize(self) -> [u8; 64] {
+        let mut oh = self.oh;
+        oh.update(self.ih.finalize());
+        oh.finalize()
+    }
+
+    /// Compute HMAC-SHA512(`key`, `input`)
+    pub fn mac<T: AsRef<[u8]>>(key: &[u8], input: T) -> [u8; 64] {
+        let mut h = Hmac::new(key);
+        h.update(input);
+        h.finalize()
+    }
+}
+
+#[test]
+fn test_hmac() {
+    let mut h = Hmac::new(b"key");
+    h.update(b"The quick brown fox ");
+    h.update(b"jumps over the lazy dog");
+    assert_eq!(
+        h.finalize(),
+        [
+            0xb4, 0x2a, 0xf0, 0x90, 0x57, 0xba, 0xc1, 0xe2, 0xd4, 0x17, 0x08, 0xe4, 0x8a, 0x90,
+            0x2e, 0x09, 0xb5, 0xff, 0x7f, 0x12, 0xab, 0x42, 0x8a, 0x4f, 0xe8, 0x66, 0x53, 0xc7,
+            0x3d, 0xd2, 0x48, 0xfb, 0x82, 0xf9, 0x48, 0xa5, 0x49, 0xf7, 0xb7, 0x91, 0xa5, 0xb4,
+            0x19, 0x15, 0xee, 0x4d, 0x1e, 0xc3, 0x93, 0x53, 0x57, 0xe4, 0xe2, 0x31, 0x72, 0x50,
+            0xd0, 0x37, 0x2a, 0xfa, 0x2e, 0xbe, 0xeb, 0x3a,
+        ]
+    );
+}
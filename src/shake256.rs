@@ -0,0 +1,180 @@
+//! A small, self-contained SHAKE256 implementation, as required by Ed448.
+
+#![allow(clippy::unreadable_literal)]
+
+const RATE: usize = 136;
+
+const RC: [u64; 24] = [
+    0x0000000000000001,
+    0x0000000000008082,
+    0x800000000000808a,
+    0x8000000080008000,
+    0x000000000000808b,
+    0x0000000080000001,
+    0x8000000080008081,
+    0x8000000000008009,
+    0x000000000000008a,
+    0x0000000000000088,
+    0x0000000080008009,
+    0x000000008000000a,
+    0x000000008000808b,
+    0x800000000000008b,
+    0x8000000000008089,
+    0x8000000000008003,
+    0x8000000000008002,
+    0x8000000000000080,
+    0x000000000000800a,
+    0x800000008000000a,
+    0x8000000080008081,
+    0x8000000000008080,
+    0x0000000080000001,
+    0x8000000080008008,
+];
+
+const RHO: [u32; 24] = [
+    1, 3, 6, 10, 15, 21, 28, 36, 45, 55, 2, 14, 27, 41, 56, 8, 25, 43, 62, 18, 39, 61, 20, 44,
+];
+
+const PI: [usize; 24] = [
+    10, 7, 11, 17, 18, 3, 5, 16, 8, 21, 24, 4, 15, 23, 19, 13, 12, 2, 20, 14, 22, 9, 6, 1,
+];
+
+fn keccak_f(a: &mut [u64; 25]) {
+    for rc in RC.iter() {
+        let mut c = [0u64; 5];
+        for x in 0..5 {
+            c[x] = a[x] ^ a[x + 5] ^ a[x + 10] ^ a[x + 15] ^ a[x + 20];
+        }
+        for x in 0..5 {
+            let d = c[(x + 4) % 5] ^ c[(x + 1) % 5].rotate_left(1);
+            for y in 0..5 {
+                a[x + 5 * y] ^= d;
+            }
+        }
+        let mut last = a[1];
+        for (rho, pi) in RHO.iter().zip(PI.iter()) {
+            let t = a[*pi];
+            a[*pi] = last.rotate_left(*rho);
+            last = t;
+        }
+        for y in 0..5 {
+            let row = [
+                a[5 * y],
+                a[5 * y + 1],
+                a[5 * y + 2],
+                a[5 * y + 3],
+                a[5 * y + 4],
+            ];
+            for x in 0..5 {
+                a[5 * y + x] = row[x] ^ (!row[(x + 1) % 5] & row[(x + 2) % 5]);
+            }
+        }
+        a[0] ^= rc;
+    }
+}
+
+#[derive(Copy, Clone)]
+pub struct Hash {
+    a: [u64; 25],
+    r: usize,
+}
+
+impl Hash {
+    pub fn new() -> Hash {
+        Hash {
+            a: [0u64; 25],
+            r: 0,
+        }
+    }
+
+    fn xor_byte(&mut self, pos: usize, b: u8) {
+        self.a[pos / 8] ^= (b as u64) << (8 * (pos % 8));
+    }
+
+    /// Absorb content
+    pub fn update<T: AsRef<[u8]>>(&mut self, input: T) {
+        for &b in input.as_ref() {
+            self.xor_byte(self.r, b);
+            self.r += 1;
+            if self.r == RATE {
+                keccak_f(&mut self.a);
+                self.r = 0;
+            }
+        }
+    }
+
+    /// Fill `out` with the output of SHAKE256(absorbed content)
+    pub fn finalize_into(mut self, out: &mut [u8]) {
+        self.xor_byte(self.r, 0x1f);
+        self.xor_byte(RATE - 1, 0x80);
+        keccak_f(&mut self.a);
+        let mut pos = 0;
+        for b in out.iter_mut() {
+            if pos == RATE {
+                keccak_f(&mut self.a);
+                pos = 0;
+            }
+            *b = (self.a[pos / 8] >> (8 * (pos % 8))) as u8;
+            pos += 1;
+        }
+    }
+
+    /// Compute the 114-byte output of SHAKE256(absorbed content) used by
+    /// Ed448
+    pub fn finalize(self) -> [u8; 114] {
+        let mut out = [0u8; 114];
+        self.finalize_into(&mut out);
+        out
+    }
+}
+
+impl Default for Hash {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[test]
+fn test_shake256() {
+    let mut out = [0u8; 32];
+    Hash::new().finalize_into(&mut out);
+    assert_eq!(
+        out,
+        [
+            0x46, 0xb9, 0xdd, 0x2b, 0x0b, 0xa8, 0x8d, 0x13, 0x23, 0x3b, 0x3f, 0xeb, 0x74, 0x3e,
+            0xeb, 0x24, 0x3f, 0xcd, 0x52, 0xea, 0x62, 0xb8, 0x1b, 0x82, 0xb5, 0x0c, 0x27, 0x64,
+            0x6e, 0xd5, 0x76, 0x2f,
+        ]
+    );
+    let mut h = Hash::new();
+    h.update(b"abc");
+    let out = h.finalize();
+    assert_eq!(
+        out[..16],
+        [
+            0x48, 0x33, 0x66, 0x60, 0x13, 0x60, 0xa8, 0x77, 0x1c, 0x68, 0x63, 0x08, 0x0c, 0xc4,
+            0x11, 0x4d
+        ]
+    );
+    assert_eq!(
+        out[98..],
+        [
+            0xdb, 0xd9, 0x12, 0x99, 0x3f, 0x09, 0x13, 0xf1, 0x64, 0xfb, 0x2c, 0xe9, 0x51, 0x31,
+            0xa2, 0xd0
+        ]
+    );
+    let mut h = Hash::new();
+    h.update([0xa3u8; 100]);
+    h.update([0xa3u8; 100]);
+    let mut out = [0u8; 300];
+    h.finalize_into(&mut out);
+    let mut h2 = Hash::new();
+    h2.update([0xa3u8; 200]);
+    let mut out2 = [0u8; 300];
+    h2.finalize_into(&mut out2);
+    assert_eq!(out[..], out2[..]);
+    assert_eq!(
+        out[290..],
+        [0x7a, 0xd0, 0xe1, 0x41, 0xc2, 0x4c, 0x78, 0x39, 0x06, 0x4c]
+    );
+}
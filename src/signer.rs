@@ -0,0 +1,156 @@
+#[cfg(feature = "async")]
+use core::future::{self, Future};
+
+use super::{CompactSecretKey, Error, KeyPair, PublicKey, SecretKey, Signature};
+
+/// A key that can compute Ed25519 signatures.
+///
+/// This trait is implemented by the secret key types of this crate, and can
+/// be implemented for keys that never enter process memory, such as keys
+/// held by a cloud KMS, an HSM or a secure element. Helpers that only need
+/// to sign can then accept any `Signer`.
+pub trait Signer {
+    /// Returns the public key corresponding to the signing key.
+    fn public_key(&self) -> PublicKey;
+
+    /// Computes a signature for the message `message`.
+    fn sign(&self, message: &[u8]) -> Result<Signature, Error>;
+}
+
+/// A key that can verify Ed25519 signatures.
+pub trait Verifier {
+    /// Verifies that the signature `signature` is valid for the message
+    /// `message`.
+    fn verify(&self, message: &[u8], signature: &Signature) -> Result<(), Error>;
+}
+
+impl Signer for SecretKey {
+    fn public_key(&self) -> PublicKey {
+        SecretKey::public_key(self)
+    }
+
+    fn sign(&self, message: &[u8]) -> Result<Signature, Error> {
+        self.try_sign(message, None)
+    }
+}
+
+impl Signer for KeyPair {
+    fn public_key(&self) -> PublicKey {
+        self.pk
+    }
+
+    fn sign(&self, message: &[u8]) -> Result<Signature, Error> {
+        self.sk.try_sign(message, None)
+    }
+}
+
+impl Signer for CompactSecretKey {
+    fn public_key(&self) -> PublicKey {
+        CompactSecretKey::public_key(self)
+    }
+
+    fn sign(&self, message: &[u8]) -> Result<Signature, Error> {
+        self.try_sign(message, None)
+    }
+}
+
+impl<T: Signer + ?Sized> Signer for &T {
+    fn public_key(&self) -> PublicKey {
+        (**self).public_key()
+    }
+
+    fn sign(&self, message: &[u8]) -> Result<Signature, Error> {
+        (**self).sign(message)
+    }
+}
+
+impl Verifier for PublicKey {
+    fn verify(&self, message: &[u8], signature: &Signature) -> Result<(), Error> {
+        PublicKey::verify(self, message, signature)
+    }
+}
+
+/// Asynchronous version of `Signer`, for remote signing backends.
+///
+/// It is implemented by every `Signer` that is `Sync`.
+#[cfg(feature = "async")]
+pub trait AsyncSigner {
+    /// Returns the public key corresponding to the signing key.
+    fn public_key(&self) -> PublicKey;
+
+    /// Computes a signature for the message `message`.
+    fn sign(&self, message: &[u8]) -> impl Future<Output = Result<Signature, Error>> + Send;
+}
+
+/// Asynchronous version of `Verifier`.
+///
+/// It is implemented by every `Verifier` that is `Sync`.
+#[cfg(feature = "async")]
+pub trait AsyncVerifier {
+    /// Verifies that the signature `signature` is valid for the message
+    /// `message`.
+    fn verify(
+        &self,
+        message: &[u8],
+        signature: &Signature,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+}
+
+#[cfg(feature = "async")]
+impl<T: Signer + Sync> AsyncSigner for T {
+    fn public_key(&self) -> PublicKey {
+        Signer::public_key(self)
+    }
+
+    fn sign(&self, message: &[u8]) -> impl Future<Output = Result<Signature, Error>> + Send {
+        future::ready(Signer::sign(self, message))
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T: Verifier + Sync> AsyncVerifier for T {
+    fn verify(
+        &self,
+        message: &[u8],
+        signature: &Signature,
+    ) -> impl Future<Output = Result<(), Error>> + Send {
+        future::ready(Verifier::verify(self, message, signature))
+    }
+}
+
+#[test]
+fn test_signer() {
+    fn sign_and_verify(signer: &dyn Signer, message: &[u8]) -> Result<(), Error> {
+        let signature = signer.sign(message)?;
+        Verifier::verify(&signer.public_key(), message, &signature)
+    }
+
+    let kp = KeyPair::from_seed([42u8; 32].into());
+    assert!(sign_and_verify(&kp, b"message").is_ok());
+    assert!(sign_and_verify(&kp.sk, b"message").is_ok());
+    assert!(sign_and_verify(&CompactSecretKey::from(kp.sk), b"message").is_ok());
+    assert_eq!(
+        Signer::sign(&kp, b"message"),
+        Ok(kp.sk.sign(b"message", None))
+    );
+
+    #[cfg(feature = "async")]
+    {
+        use core::pin::pin;
+        use core::task::{Context, Poll, Waker};
+
+        fn block_on<F: Future>(fut: F) -> F::Output {
+            let mut fut = pin!(fut);
+            let mut cx = Context::from_waker(Waker::noop());
+            loop {
+                if let Poll::Ready(output) = fut.as_mut().poll(&mut cx) {
+                    return output;
+                }
+            }
+        }
+
+        let signature = block_on(AsyncSigner::sign(&kp, b"message")).unwrap();
+        let pk = AsyncSigner::public_key(&kp);
+        assert!(block_on(AsyncVerifier::verify(&pk, b"message", &signature)).is_ok());
+    }
+}
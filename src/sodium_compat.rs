@@ -0,0 +1,305 @@
+//! Functions mirroring libsodium's `crypto_sign_*()` and `crypto_kdf_*()`
+//! APIs.
+//!
+//! Names, argument orders and key formats are the same as in libsodium, so
+//! that code and test suites written for it can be ported with minimal
+//! changes. Secret keys are 64 bytes (seed followed by the public key), and
+//! signatures are deterministic, exactly like libsodium's.
+//!
+//! Instead of returning `-1`, functions return an `Error`.
+
+#![allow(non_upper_case_globals)]
+
+use super::blake2b;
+use super::common::wipe;
+use super::error::Error;
+#[cfg(feature = "x25519")]
+use super::x25519;
+use super::{PublicKey, SecretKey, Seed, Signature};
+
+/// Size of a signature, in bytes.
+pub const crypto_sign_BYTES: usize = Signature::BYTES;
+
+/// Size of a seed, in bytes.
+pub const crypto_sign_SEEDBYTES: usize = Seed::BYTES;
+
+/// Size of a public key, in bytes.
+pub const crypto_sign_PUBLICKEYBYTES: usize = PublicKey::BYTES;
+
+/// Size of a secret key, in bytes.
+pub const crypto_sign_SECRETKEYBYTES: usize = SecretKey::BYTES;
+
+/// Minimum size of a derived subkey, in bytes.
+pub const crypto_kdf_BYTES_MIN: usize = 16;
+
+/// Maximum size of a derived subkey, in bytes.
+pub const crypto_kdf_BYTES_MAX: usize = 64;
+
+/// Size of a KDF context, in bytes.
+pub const crypto_kdf_CONTEXTBYTES: usize = 8;
+
+/// Size of a KDF master key, in bytes.
+pub const crypto_kdf_KEYBYTES: usize = 32;
+
+/// Deterministically derives a key pair from a seed.
+///
+/// Unlike libsodium, an all-zero seed is rejected.
+pub fn crypto_sign_seed_keypair(
+    pk: &mut [u8; crypto_sign_PUBLICKEYBYTES],
+    sk: &mut [u8; crypto_sign_SECRETKEYBYTES],
+    seed: &[u8; crypto_sign_SEEDBYTES],
+) -> Result<(), Error> {
+    let sk_ = SecretKey::from_seed(&Seed::new(*seed))?;
+    sk.copy_from_slice(&sk_[..]);
+    pk.copy_from_slice(&sk_[32..]);
+    Ok(())
+}
+
+/// Generates a random key pair.
+#[cfg(any(feature = "random", feature = "entropy-source"))]
+pub fn crypto_sign_keypair(
+    pk: &mut [u8; crypto_sign_PUBLICKEYBYTES],
+    sk: &mut [u8; crypto_sign_SECRETKEYBYTES],
+) -> Result<(), Error> {
+    crypto_sign_seed_keypair(pk, sk, &*Seed::try_generate()?)
+}
+
+/// Signs `m` and stores the signature followed by the message into `sm`.
+///
+/// `sm` must be at least `crypto_sign_BYTES` bytes larger than `m`. Returns
+/// the length of the signed message.
+pub fn crypto_sign(
+    sm: &mut [u8],
+    m: &[u8],
+    sk: &[u8; crypto_sign_SECRETKEYBYTES],
+) -> Result<usize, Error> {
+    let smlen = m.len() + crypto_sign_BYTES;
+    if sm.len() < smlen {
+        return Err(Error::InvalidSignature);
+    }
+    let signature = SecretKey::new(*sk).try_sign(m, None)?;
+    sm[..crypto_sign_BYTES].copy_from_slice(&signature[..]);
+    sm[crypto_sign_BYTES..smlen].copy_from_slice(m);
+    Ok(smlen)
+}
+
+/// Verifies a signed message produced by `crypto_sign()` and copies the
+/// message into `m`.
+///
+/// `m` must be at least as large as the message. Returns the length of the
+/// message.
+pub fn crypto_sign_open(
+    m: &mut [u8],
+    sm: &[u8],
+    pk: &[u8; crypto_sign_PUBLICKEYBYTES],
+) -> Result<usize, Error> {
+    let message = PublicKey::new(*pk).open(sm)?;
+    if m.len() < message.len() {
+        return Err(Error::InvalidSignature);
+    }
+    m[..message.len()].copy_from_slice(message);
+    Ok(message.len())
+}
+
+/// Computes a detached signature for `m`.
+pub fn crypto_sign_detached(
+    sig: &mut [u8; crypto_sign_BYTES],
+    m: &[u8],
+    sk: &[u8; crypto_sign_SECRETKEYBYTES],
+) -> Result<(), Error> {
+    let signature = SecretKey::new(*sk).try_sign(m, None)?;
+    sig.copy_from_slice(&signature[..]);
+    Ok(())
+}
+
+/// Verifies a detached signature for `m`.
+pub fn crypto_sign_verify_detached(
+    sig: &[u8; crypto_sign_BYTES],
+    m: &[u8],
+    pk: &[u8; crypto_sign_PUBLICKEYBYTES],
+) -> Result<(), Error> {
+    PublicKey::new(*pk).verify(m, &Signature::new(*sig))
+}
+
+/// Extracts the seed from a secret key.
+pub fn crypto_sign_ed25519_sk_to_seed(
+    seed: &mut [u8; crypto_sign_SEEDBYTES],
+    sk: &[u8; crypto_sign_SECRETKEYBYTES],
+) {
+    seed.copy_from_slice(&sk[..crypto_sign_SEEDBYTES]);
+}
+
+/// Extracts the public key from a secret key.
+pub fn crypto_sign_ed25519_sk_to_pk(
+    pk: &mut [u8; crypto_sign_PUBLICKEYBYTES],
+    sk: &[u8; crypto_sign_SECRETKEYBYTES],
+) {
+    pk.copy_from_slice(&sk[crypto_sign_SEEDBYTES..]);
+}
+
+/// Generates a random KDF master key.
+#[cfg(any(feature = "random", feature = "entropy-source"))]
+pub fn crypto_kdf_keygen(key: &mut [u8; crypto_kdf_KEYBYTES]) -> Result<(), Error> {
+    crate::entropy::fill_random(key)
+}
+
+/// Derives the subkey number `subkey_id` from a master key and a context.
+///
+/// The subkey fills `subkey`, whose length must be between
+/// `crypto_kdf_BYTES_MIN` and `crypto_kdf_BYTES_MAX` bytes. Subkeys are
+/// identical to the ones computed by libsodium.
+pub fn crypto_kdf_derive_from_key(
+    subkey: &mut [u8],
+    subkey_id: u64,
+    ctx: &[u8; crypto_kdf_CONTEXTBYTES],
+    key: &[u8; crypto_kdf_KEYBYTES],
+) -> Result<(), Error> {
+    if subkey.len() < crypto_kdf_BYTES_MIN || subkey.len() > crypto_kdf_BYTES_MAX {
+        return Err(Error::InvalidSecretKey);
+    }
+    let mut salt = [0u8; 16];
+    salt[..8].copy_from_slice(&subkey_id.to_le_bytes());
+    let mut personal = [0u8; 16];
+    personal[..crypto_kdf_CONTEXTBYTES].copy_from_slice(ctx);
+    let h = blake2b::Hash::new_with_params(subkey.len(), key, &salt, &personal);
+    let mut out = h.finalize();
+    subkey.copy_from_slice(&out[..subkey.len()]);
+    wipe(&mut out);
+    Ok(())
+}
+
+/// Derives the key pair number `subkey_id` from a master key and a context.
+///
+/// The key pair is the one `crypto_sign_seed_keypair()` computes from the
+/// 32-byte subkey returned by `crypto_kdf_derive_from_key()`.
+pub fn crypto_kdf_derive_seed_keypair(
+    pk: &mut [u8; crypto_sign_PUBLICKEYBYTES],
+    sk: &mut [u8; crypto_sign_SECRETKEYBYTES],
+    subkey_id: u64,
+    ctx: &[u8; crypto_kdf_CONTEXTBYTES],
+    key: &[u8; crypto_kdf_KEYBYTES],
+) -> Result<(), Error> {
+    let mut seed = [0u8; crypto_sign_SEEDBYTES];
+    crypto_kdf_derive_from_key(&mut seed, subkey_id, ctx, key)?;
+    let res = crypto_sign_seed_keypair(pk, sk, &seed);
+    wipe(&mut seed);
+    res
+}
+
+/// Converts an Ed25519 public key to an X25519 public key.
+#[cfg(feature = "x25519")]
+pub fn crypto_sign_ed25519_pk_to_curve25519(
+    curve25519_pk: &mut [u8; 32],
+    ed25519_pk: &[u8; crypto_sign_PUBLICKEYBYTES],
+) -> Result<(), Error> {
+    let pk = x25519::PublicKey::from_ed25519(&PublicKey::new(*ed25519_pk))?;
+    curve25519_pk.copy_from_slice(&pk[..]);
+    Ok(())
+}
+
+/// Converts an Ed25519 secret key to an X25519 secret key.
+#[cfg(feature = "x25519")]
+pub fn crypto_sign_ed25519_sk_to_curve25519(
+    curve25519_sk: &mut [u8; 32],
+    ed25519_sk: &[u8; crypto_sign_SECRETKEYBYTES],
+) {
+    let mut sk = x25519::SecretKey::from_ed25519(&SecretKey::new(*ed25519_sk));
+    curve25519_sk.copy_from_slice(&sk[..]);
+    wipe(&mut sk[..]);
+}
+
+#[test]
+fn test_sodium_compat() {
+    // RFC 8032, section 7.1, test 2
+    let seed = [
+        0x4c, 0xcd, 0x08, 0x9b, 0x28, 0xff, 0x96, 0xda, 0x9d, 0xb6, 0xc3, 0x46, 0xec, 0x11, 0x4e,
+        0x0f, 0x5b, 0x8a, 0x31, 0x9f, 0x35, 0xab, 0xa6, 0x24, 0xda, 0x8c, 0xf6, 0xed, 0x4f, 0xb8,
+        0xa6, 0xfb,
+    ];
+    let expected_sig = [
+        0x92, 0xa0, 0x09, 0xa9, 0xf0, 0xd4, 0xca, 0xb8, 0x72, 0x0e, 0x82, 0x0b, 0x5f, 0x64, 0x25,
+        0x40, 0xa2, 0xb2, 0x7b, 0x54, 0x16, 0x50, 0x3f, 0x8f, 0xb3, 0x76, 0x22, 0x23, 0xeb, 0xdb,
+        0x69, 0xda, 0x08, 0x5a, 0xc1, 0xe4, 0x3e, 0x15, 0x99, 0x6e, 0x45, 0x8f, 0x36, 0x13, 0xd0,
+        0xf1, 0x1d, 0x8c, 0x38, 0x7b, 0x2e, 0xae, 0xb4, 0x30, 0x2a, 0xee, 0xb0, 0x0d, 0x29, 0x16,
+        0x12, 0xbb, 0x0c, 0x00,
+    ];
+    let m = [0x72u8];
+
+    let mut pk = [0u8; crypto_sign_PUBLICKEYBYTES];
+    let mut sk = [0u8; crypto_sign_SECRETKEYBYTES];
+    crypto_sign_seed_keypair(&mut pk, &mut sk, &seed).unwrap();
+
+    let mut pk2 = [0u8; crypto_sign_PUBLICKEYBYTES];
+    let mut seed2 = [0u8; crypto_sign_SEEDBYTES];
+    crypto_sign_ed25519_sk_to_pk(&mut pk2, &sk);
+    crypto_sign_ed25519_sk_to_seed(&mut seed2, &sk);
+    assert_eq!(pk2, pk);
+    assert_eq!(seed2, seed);
+
+    let mut sig = [0u8; crypto_sign_BYTES];
+    crypto_sign_detached(&mut sig, &m, &sk).unwrap();
+    assert_eq!(sig, expected_sig);
+    assert!(crypto_sign_verify_detached(&sig, &m, &pk).is_ok());
+
+    let mut sm = [0u8; crypto_sign_BYTES + 1];
+    assert_eq!(crypto_sign(&mut sm, &m, &sk), Ok(sm.len()));
+    assert_eq!(sm[..crypto_sign_BYTES], expected_sig[..]);
+    let mut m2 = [0u8; 1];
+    assert_eq!(crypto_sign_open(&mut m2, &sm, &pk), Ok(1));
+    assert_eq!(m2, m);
+    sm[crypto_sign_BYTES] ^= 1;
+    assert_eq!(
+        crypto_sign_open(&mut m2, &sm, &pk),
+        Err(Error::SignatureMismatch)
+    );
+
+    let mut key = [0u8; crypto_kdf_KEYBYTES];
+    for (i, x) in key.iter_mut().enumerate() {
+        *x = i as u8;
+    }
+    let mut subkey = [0u8; 16];
+    crypto_kdf_derive_from_key(&mut subkey, 1, b"Examples", &key).unwrap();
+    assert_eq!(
+        subkey,
+        [
+            0x16, 0x16, 0x9d, 0xc6, 0xca, 0x3f, 0x6b, 0xbf, 0xe8, 0xbe, 0xba, 0x85, 0xa9, 0x78,
+            0x46, 0xf7,
+        ]
+    );
+    let mut subkey = [0u8; 32];
+    crypto_kdf_derive_from_key(&mut subkey, 0, b"Examples", &key).unwrap();
+    assert_eq!(
+        subkey,
+        [
+            0xd6, 0x76, 0xd6, 0xd5, 0x44, 0x80, 0xf1, 0x3e, 0xd7, 0x5c, 0x93, 0x06, 0x29, 0xf2,
+            0x19, 0x19, 0xbf, 0x71, 0x26, 0x65, 0x6e, 0x4b, 0x7f, 0x9e, 0xf0, 0x45, 0xee, 0x34,
+            0xac, 0x28, 0x81, 0x61,
+        ]
+    );
+    assert_eq!(
+        crypto_kdf_derive_from_key(&mut [0u8; 8], 0, b"Examples", &key),
+        Err(Error::InvalidSecretKey)
+    );
+    crypto_kdf_derive_seed_keypair(&mut pk, &mut sk, 7, b"signing\0", &key).unwrap();
+    assert_eq!(
+        pk,
+        [
+            0x24, 0xb1, 0x66, 0xf6, 0x78, 0xd0, 0x59, 0x36, 0x48, 0x1e, 0xb5, 0x40, 0x2d, 0x9d,
+            0x50, 0xa9, 0x69, 0xbf, 0x38, 0x72, 0x87, 0xd8, 0xc3, 0x37, 0x2e, 0xad, 0x40, 0x18,
+            0xac, 0x44, 0x73, 0xae,
+        ]
+    );
+
+    #[cfg(feature = "x25519")]
+    {
+        crypto_sign_seed_keypair(&mut pk, &mut sk, &seed).unwrap();
+        let mut curve25519_pk = [0u8; 32];
+        let mut curve25519_sk = [0u8; 32];
+        crypto_sign_ed25519_pk_to_curve25519(&mut curve25519_pk, &pk).unwrap();
+        crypto_sign_ed25519_sk_to_curve25519(&mut curve25519_sk, &sk);
+        assert_eq!(
+            x25519::SecretKey::new(curve25519_sk).recover_public_key(),
+            Ok(x25519::PublicKey::new(curve25519_pk))
+        );
+    }
+}
@@ -0,0 +1,90 @@
+use super::base58;
+use super::common::wipe;
+use super::{Error, KeyPair, PublicKey, SecretKey};
+
+impl KeyPair {
+    /// Imports a key pair from the JSON format used by `solana-keygen`: an
+    /// array of the 64 bytes of the secret key.
+    ///
+    /// The public key embedded in the secret key is validated.
+    pub fn from_solana_json(json: &str) -> Result<Self, Error> {
+        let inner = json
+            .trim()
+            .strip_prefix('[')
+            .and_then(|s| s.strip_suffix(']'))
+            .ok_or(Error::ParseError)?;
+        let mut sk = [0u8; SecretKey::BYTES];
+        let mut len = 0;
+        for item in inner.split(',') {
+            if len == sk.len() {
+                wipe(&mut sk);
+                return Err(Error::InvalidSecretKey);
+            }
+            match item.trim().parse::<u8>() {
+                Ok(x) => sk[len] = x,
+                Err(_) => {
+                    wipe(&mut sk);
+                    return Err(Error::ParseError);
+                }
+            }
+            len += 1;
+        }
+        let kp = if len == sk.len() {
+            KeyPair::from_slice(&sk)
+        } else {
+            Err(Error::InvalidSecretKey)
+        };
+        wipe(&mut sk);
+        kp
+    }
+
+    /// Exports the key pair in the JSON format used by `solana-keygen`.
+    pub fn to_solana_json(&self) -> String {
+        let mut json = String::with_capacity(2 + SecretKey::BYTES * 4);
+        json.push('[');
+        for (i, x) in self.sk.iter().enumerate() {
+            if i > 0 {
+                json.push(',');
+            }
+            json.push_str(&x.to_string());
+        }
+        json.push(']');
+        json
+    }
+}
+
+impl PublicKey {
+    /// Returns the Base58 encoding of the public key, as used for Solana
+    /// addresses.
+    pub fn to_base58(&self) -> String {
+        base58::encode(&self[..])
+    }
+
+    /// Imports a public key from its Base58 encoding.
+    pub fn from_base58(s: &str) -> Result<Self, Error> {
+        PublicKey::from_slice(&base58::decode(s.trim(), PublicKey::BYTES)?)
+    }
+}
+
+#[test]
+fn test_solana() {
+    use super::Seed;
+
+    let kp = KeyPair::from_seed(Seed::new([1u8; Seed::BYTES]));
+    let json = kp.to_solana_json();
+    assert!(json.starts_with("[1,1,1,"));
+    assert!(json.ends_with(",180,15,111,92]"));
+    let kp2 = KeyPair::from_solana_json(&format!(" {}\n", json.replace(',', ", "))).unwrap();
+    assert_eq!(kp2.sk, kp.sk);
+    assert_eq!(kp2.pk, kp.pk);
+
+    let address = kp.pk.to_base58();
+    assert_eq!(address, "AKnL4NNf3DGWZJS6cPknBuEGnVsV4A4m5tgebLHaRSZ9");
+    assert_eq!(PublicKey::from_base58(&address).unwrap(), kp.pk);
+
+    let corrupted = json.replace(",180,15,111,92]", ",180,15,111,93]");
+    assert!(KeyPair::from_solana_json(&corrupted).is_err());
+    assert!(KeyPair::from_solana_json("[1,2,3]").is_err());
+    assert!(KeyPair::from_solana_json(&json.replace("[1,", "[256,")).is_err());
+    assert!(PublicKey::from_base58("11111").is_err());
+}
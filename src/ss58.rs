@@ -0,0 +1,95 @@
+use super::{base58, blake2b, Error, PublicKey};
+
+/// SS58 network prefix for generic Substrate addresses.
+pub const SS58_GENERIC_PREFIX: u16 = 42;
+
+/// SS58 network prefix for Polkadot addresses.
+pub const SS58_POLKADOT_PREFIX: u16 = 0;
+
+/// SS58 network prefix for Kusama addresses.
+pub const SS58_KUSAMA_PREFIX: u16 = 2;
+
+const CHECKSUM_BYTES: usize = 2;
+
+fn checksum(data: &[u8]) -> [u8; CHECKSUM_BYTES] {
+    let mut h = blake2b::Hash::new(64);
+    h.update(b"SS58PRE");
+    h.update(data);
+    let hash = h.finalize();
+    [hash[0], hash[1]]
+}
+
+impl PublicKey {
+    /// Returns the SS58 address of the public key, with network prefix
+    /// `prefix`.
+    ///
+    /// Prefixes must be lower than 16384.
+    pub fn to_ss58(&self, prefix: u16) -> Result<String, Error> {
+        let mut data = Vec::with_capacity(2 + PublicKey::BYTES + CHECKSUM_BYTES);
+        match prefix {
+            0..=63 => data.push(prefix as u8),
+            64..=16383 => {
+                data.push(((prefix & 0xfc) >> 2) as u8 | 0x40);
+                data.push((prefix >> 8) as u8 | ((prefix & 0x03) << 6) as u8);
+            }
+            _ => return Err(Error::ParseError),
+        }
+        data.extend_from_slice(&self[..]);
+        let checksum = checksum(&data);
+        data.extend_from_slice(&checksum);
+        Ok(base58::encode(&data))
+    }
+
+    /// Decodes an SS58 address, and returns the public key along with its
+    /// network prefix.
+    pub fn from_ss58(address: &str) -> Result<(Self, u16), Error> {
+        let data = base58::decode(address.trim(), 2 + PublicKey::BYTES + CHECKSUM_BYTES)?;
+        let (prefix, prefix_len) = match data.first() {
+            Some(&b) if b < 64 => (b as u16, 1),
+            Some(&b) if b < 128 && data.len() > 1 => {
+                let lower = ((b & 0x3f) << 2) | (data[1] >> 6);
+                let upper = data[1] & 0x3f;
+                (lower as u16 | (upper as u16) << 8, 2)
+            }
+            _ => return Err(Error::ParseError),
+        };
+        if data.len() != prefix_len + PublicKey::BYTES + CHECKSUM_BYTES {
+            return Err(Error::ParseError);
+        }
+        let (payload, expected) = data.split_at(prefix_len + PublicKey::BYTES);
+        if checksum(payload) != expected {
+            return Err(Error::ParseError);
+        }
+        Ok((PublicKey::from_slice(&payload[prefix_len..])?, prefix))
+    }
+}
+
+#[test]
+fn test_ss58() {
+    // Ed25519 key of `//Alice`
+    let pk = PublicKey::new([
+        0x88, 0xdc, 0x34, 0x17, 0xd5, 0x05, 0x8e, 0xc4, 0xb4, 0x50, 0x3e, 0x0c, 0x12, 0xea, 0x1a,
+        0x0a, 0x89, 0xbe, 0x20, 0x0f, 0xe9, 0x89, 0x22, 0x42, 0x3d, 0x43, 0x34, 0x01, 0x4f, 0xa6,
+        0xb0, 0xee,
+    ]);
+    for (prefix, address) in [
+        (
+            SS58_GENERIC_PREFIX,
+            "5FA9nQDVg267DEd8m1ZypXLBnvN7SFxYwV7ndqSYGiN9TTpu",
+        ),
+        (
+            SS58_POLKADOT_PREFIX,
+            "146SvjUZXoMaemdeiecyxgALeYMm8ZWh1yrGo8RtpoPfe7WL",
+        ),
+        (
+            SS58_KUSAMA_PREFIX,
+            "FfmSiZNJP72xtSaXiP2iUhBwWeMEvmjPrxY2ViVkWaeChDC",
+        ),
+        (1284, "VdtcyQ8eTeX5gLpN8cbZLD6rQG6cDg6GJn2yiM2CUmbjx68B7"),
+    ] {
+        assert_eq!(pk.to_ss58(prefix).unwrap(), address);
+        assert_eq!(PublicKey::from_ss58(address).unwrap(), (pk, prefix));
+    }
+    assert!(pk.to_ss58(16384).is_err());
+    assert!(PublicKey::from_ss58("5FA9nQDVg267DEd8m1ZypXLBnvN7SFxYwV7ndqSYGiN9TTpv").is_err());
+}
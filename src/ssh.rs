@@ -0,0 +1,119 @@
+//! SSH wire encoding (RFC 4251), for Ed25519 keys and signatures.
+//!
+//! Shared by several features, which don't all use every function.
+
+use super::error::Error;
+use super::PublicKey;
+#[cfg(any(all(feature = "agent", unix), feature = "ssh"))]
+use super::Signature;
+
+/// Key and signature type name for Ed25519.
+pub(crate) const ED25519_TYPE: &[u8] = b"ssh-ed25519";
+
+pub(crate) fn put_u32(out: &mut Vec<u8>, x: u32) {
+    out.extend_from_slice(&x.to_be_bytes());
+}
+
+pub(crate) fn put_string(out: &mut Vec<u8>, s: &[u8]) {
+    put_u32(out, s.len() as u32);
+    out.extend_from_slice(s);
+}
+
+/// Encodes a public key as an SSH public key blob.
+pub(crate) fn public_key_blob(pk: &PublicKey) -> Vec<u8> {
+    let mut blob = Vec::with_capacity(4 + ED25519_TYPE.len() + 4 + PublicKey::BYTES);
+    put_string(&mut blob, ED25519_TYPE);
+    put_string(&mut blob, &pk[..]);
+    blob
+}
+
+/// Encodes a signature as an SSH signature blob.
+#[cfg(all(test, feature = "agent", unix))]
+pub(crate) fn signature_blob(signature: &Signature) -> Vec<u8> {
+    let mut blob = Vec::with_capacity(4 + ED25519_TYPE.len() + 4 + Signature::BYTES);
+    put_string(&mut blob, ED25519_TYPE);
+    put_string(&mut blob, &signature[..]);
+    blob
+}
+
+/// Decodes an SSH public key blob.
+///
+/// Returns `Ok(None)` if the blob is well-formed, but not an Ed25519 key.
+pub(crate) fn parse_public_key_blob(blob: &[u8]) -> Result<Option<PublicKey>, Error> {
+    let mut reader = Reader::new(blob);
+    if reader.string()? != ED25519_TYPE {
+        return Ok(None);
+    }
+    let pk = PublicKey::from_slice(reader.string()?)?;
+    reader.finish()?;
+    Ok(Some(pk))
+}
+
+/// Decodes an SSH signature blob.
+#[cfg(any(all(feature = "agent", unix), feature = "ssh"))]
+pub(crate) fn parse_signature_blob(blob: &[u8]) -> Result<Signature, Error> {
+    let mut reader = Reader::new(blob);
+    if reader.string()? != ED25519_TYPE {
+        return Err(Error::InvalidSignature);
+    }
+    let signature = Signature::from_slice(reader.string()?)?;
+    reader.finish()?;
+    Ok(signature)
+}
+
+/// A reader for SSH-encoded data.
+pub(crate) struct Reader<'t> {
+    buf: &'t [u8],
+}
+
+impl<'t> Reader<'t> {
+    pub fn new(buf: &'t [u8]) -> Self {
+        Reader { buf }
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'t [u8], Error> {
+        if self.buf.len() < len {
+            return Err(Error::ParseError);
+        }
+        let (x, rest) = self.buf.split_at(len);
+        self.buf = rest;
+        Ok(x)
+    }
+
+    #[cfg(all(feature = "agent", unix))]
+    pub fn u8(&mut self) -> Result<u8, Error> {
+        Ok(self.take(1)?[0])
+    }
+
+    pub fn u32(&mut self) -> Result<u32, Error> {
+        let x = self.take(4)?;
+        Ok(u32::from_be_bytes([x[0], x[1], x[2], x[3]]))
+    }
+
+    #[cfg(feature = "ssh")]
+    pub fn u64(&mut self) -> Result<u64, Error> {
+        let x = self.take(8)?;
+        let mut be = [0u8; 8];
+        be.copy_from_slice(x);
+        Ok(u64::from_be_bytes(be))
+    }
+
+    pub fn string(&mut self) -> Result<&'t [u8], Error> {
+        let len = self.u32()? as usize;
+        self.take(len)
+    }
+
+    /// Returns the bytes that haven't been read yet.
+    #[cfg(feature = "ssh")]
+    pub fn remaining(&self) -> &'t [u8] {
+        self.buf
+    }
+
+    /// Checks that everything has been read.
+    pub fn finish(&self) -> Result<(), Error> {
+        if !self.buf.is_empty() {
+            return Err(Error::ParseError);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,322 @@
+use std::str;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use ct_codecs::{Base64, Decoder};
+
+use super::ssh::{self, Reader};
+use super::{Error, PublicKey, Signature};
+
+const CERT_TYPE: &[u8] = b"ssh-ed25519-cert-v01@openssh.com";
+
+/// Critical options that OpenSSH knows about. Certificates with other
+/// critical options are rejected by `SshCertificate::verify()`.
+const KNOWN_CRITICAL_OPTIONS: [&str; 3] = ["force-command", "source-address", "verify-required"];
+
+/// Type of an OpenSSH certificate.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum SshCertType {
+    /// A user certificate.
+    User,
+    /// A host certificate.
+    Host,
+}
+
+/// An OpenSSH certificate for an Ed25519 key, signed by an Ed25519 CA.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SshCertificate {
+    public_key: PublicKey,
+    serial: u64,
+    cert_type: SshCertType,
+    key_id: String,
+    principals: Vec<String>,
+    valid_after: u64,
+    valid_before: u64,
+    critical_options: Vec<(String, String)>,
+    extensions: Vec<(String, String)>,
+    ca_key: PublicKey,
+    signed: Vec<u8>,
+    signature: Signature,
+}
+
+impl SshCertificate {
+    /// Parses a certificate from its SSH wire encoding.
+    ///
+    /// The signature is not verified: use `verify()` for that.
+    pub fn from_blob(blob: &[u8]) -> Result<Self, Error> {
+        Self::parse(blob).map_err(|e| match e {
+            Error::ParseError => Error::InvalidCertificate,
+            e => e,
+        })
+    }
+
+    /// Parses a certificate from a line in the OpenSSH public key format
+    /// (`ssh-ed25519-cert-v01@openssh.com AAAA... comment`).
+    pub fn from_openssh(line: &str) -> Result<Self, Error> {
+        let mut tokens = line.split_whitespace();
+        if tokens.next().map(str::as_bytes) != Some(CERT_TYPE) {
+            return Err(Error::InvalidCertificate);
+        }
+        let b64 = tokens.next().ok_or(Error::InvalidCertificate)?;
+        let blob = Base64::decode_to_vec(b64, None).map_err(|_| Error::InvalidBase64)?;
+        Self::from_blob(&blob)
+    }
+
+    fn parse(blob: &[u8]) -> Result<Self, Error> {
+        let mut reader = Reader::new(blob);
+        if reader.string()? != CERT_TYPE {
+            return Err(Error::InvalidCertificate);
+        }
+        let _nonce = reader.string()?;
+        let public_key = PublicKey::from_slice(reader.string()?)?;
+        let serial = reader.u64()?;
+        let cert_type = match reader.u32()? {
+            1 => SshCertType::User,
+            2 => SshCertType::Host,
+            _ => return Err(Error::InvalidCertificate),
+        };
+        let key_id = utf8(reader.string()?)?;
+        let mut principals = Vec::new();
+        let mut principals_reader = Reader::new(reader.string()?);
+        while !principals_reader.remaining().is_empty() {
+            principals.push(utf8(principals_reader.string()?)?);
+        }
+        let valid_after = reader.u64()?;
+        let valid_before = reader.u64()?;
+        let critical_options = options(reader.string()?)?;
+        let extensions = options(reader.string()?)?;
+        let _reserved = reader.string()?;
+        let ca_key =
+            ssh::parse_public_key_blob(reader.string()?)?.ok_or(Error::InvalidCertificate)?;
+        let signed = blob[..blob.len() - reader.remaining().len()].to_vec();
+        let signature = ssh::parse_signature_blob(reader.string()?)?;
+        reader.finish()?;
+        Ok(SshCertificate {
+            public_key,
+            serial,
+            cert_type,
+            key_id,
+            principals,
+            valid_after,
+            valid_before,
+            critical_options,
+            extensions,
+            ca_key,
+            signed,
+            signature,
+        })
+    }
+
+    /// Returns the certified public key.
+    pub fn public_key(&self) -> &PublicKey {
+        &self.public_key
+    }
+
+    /// Returns the serial number.
+    pub fn serial(&self) -> u64 {
+        self.serial
+    }
+
+    /// Returns the certificate type.
+    pub fn cert_type(&self) -> SshCertType {
+        self.cert_type
+    }
+
+    /// Returns the key identifier, set by the CA.
+    pub fn key_id(&self) -> &str {
+        &self.key_id
+    }
+
+    /// Returns the user names or host names the certificate is valid for.
+    ///
+    /// An empty list means any host for host certificates; user
+    /// certificates without principals are rejected by `verify()`.
+    pub fn principals(&self) -> &[String] {
+        &self.principals
+    }
+
+    /// Returns the start of the validity period, in seconds since the Unix
+    /// epoch.
+    pub fn valid_after(&self) -> u64 {
+        self.valid_after
+    }
+
+    /// Returns the end of the validity period, in seconds since the Unix
+    /// epoch.
+    pub fn valid_before(&self) -> u64 {
+        self.valid_before
+    }
+
+    /// Returns the critical options (name and value), which the application
+    /// must enforce.
+    pub fn critical_options(&self) -> &[(String, String)] {
+        &self.critical_options
+    }
+
+    /// Returns the extensions (name and value).
+    pub fn extensions(&self) -> &[(String, String)] {
+        &self.extensions
+    }
+
+    /// Returns the public key of the CA that signed the certificate.
+    pub fn ca_key(&self) -> &PublicKey {
+        &self.ca_key
+    }
+
+    /// Verifies that the certificate was signed by `ca_key`, that it is a
+    /// certificate of type `expected`, that it is valid at time `now`, and
+    /// that it is valid for `principal`.
+    ///
+    /// Like OpenSSH, user certificates must list at least one principal,
+    /// while host certificates without principals are valid for any host.
+    ///
+    /// Returns `Error::SignatureMismatch` if the signature is not valid for
+    /// `ca_key`, and `Error::InvalidCertificate` for any other failure,
+    /// including unknown critical options.
+    pub fn verify(
+        &self,
+        ca_key: &PublicKey,
+        expected: SshCertType,
+        principal: &str,
+        now: SystemTime,
+    ) -> Result<(), Error> {
+        if self.ca_key != *ca_key {
+            return Err(Error::SignatureMismatch);
+        }
+        ca_key.verify(&self.signed, &self.signature)?;
+        if self.cert_type != expected {
+            return Err(Error::InvalidCertificate);
+        }
+        let now = now
+            .duration_since(UNIX_EPOCH)
+            .map_err(|_| Error::InvalidCertificate)?
+            .as_secs();
+        if now < self.valid_after || now >= self.valid_before {
+            return Err(Error::InvalidCertificate);
+        }
+        if self.principals.is_empty() {
+            if self.cert_type == SshCertType::User {
+                return Err(Error::InvalidCertificate);
+            }
+        } else if !self.principals.iter().any(|p| p == principal) {
+            return Err(Error::InvalidCertificate);
+        }
+        if self
+            .critical_options
+            .iter()
+            .any(|(name, _)| !KNOWN_CRITICAL_OPTIONS.contains(&name.as_str()))
+        {
+            return Err(Error::InvalidCertificate);
+        }
+        Ok(())
+    }
+}
+
+fn utf8(s: &[u8]) -> Result<String, Error> {
+    str::from_utf8(s)
+        .map(str::to_string)
+        .map_err(|_| Error::InvalidCertificate)
+}
+
+/// Decodes a list of critical options or extensions.
+fn options(encoded: &[u8]) -> Result<Vec<(String, String)>, Error> {
+    let mut options = Vec::new();
+    let mut reader = Reader::new(encoded);
+    while !reader.remaining().is_empty() {
+        let name = utf8(reader.string()?)?;
+        let data = reader.string()?;
+        let value = if data.is_empty() {
+            String::new()
+        } else {
+            let mut data = Reader::new(data);
+            let value = utf8(data.string()?)?;
+            data.finish()?;
+            value
+        };
+        options.push((name, value));
+    }
+    Ok(options)
+}
+
+#[test]
+fn test_ssh_cert() {
+    use std::time::Duration;
+
+    // `ssh-keygen -s ca -I alice@example -n alice,root \
+    //    -V 20250101000000Z:20350101000000Z -O force-command=/bin/true -z 42 user.pub`
+    let line = "ssh-ed25519-cert-v01@openssh.com AAAAIHNzaC1lZDI1NTE5LWNlcnQtdjAxQG9wZW5zc2guY29tAAAAIMwHwlRdEgZfsnjv1cdoDgnAmLqJZD1IezDL0mMtWhLXAAAAIL7b/acZitc9GH8PMugP92R0O4eUH5CDh8hQ+CR2BMu7AAAAAAAAACoAAAABAAAADWFsaWNlQGV4YW1wbGUAAAARAAAABWFsaWNlAAAABHJvb3QAAAAAZ3SFgAAAAAB6QyuAAAAAIgAAAA1mb3JjZS1jb21tYW5kAAAADQAAAAkvYmluL3RydWUAAACCAAAAFXBlcm1pdC1YMTEtZm9yd2FyZGluZwAAAAAAAAAXcGVybWl0LWFnZW50LWZvcndhcmRpbmcAAAAAAAAAFnBlcm1pdC1wb3J0LWZvcndhcmRpbmcAAAAAAAAACnBlcm1pdC1wdHkAAAAAAAAADnBlcm1pdC11c2VyLXJjAAAAAAAAAAAAAAAzAAAAC3NzaC1lZDI1NTE5AAAAIB3bin3lAsOqntz9OzKpQKzEn1XiGpso4xnQ5S/0mZbeAAAAUwAAAAtzc2gtZWQyNTUxOQAAAEC7uMHxKel/adIhl3bAQEYICJ7C+Pg4BRa1c361xja5JoPdEfaVDQ1Z94iyi5uFlzNSkakroWgOuV6F5yrGmCsI user.pub";
+    let ca_key = PublicKey::from_any_str(
+        "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIB3bin3lAsOqntz9OzKpQKzEn1XiGpso4xnQ5S/0mZbe",
+    )
+    .unwrap();
+    let user_key = PublicKey::from_any_str(
+        "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIL7b/acZitc9GH8PMugP92R0O4eUH5CDh8hQ+CR2BMu7",
+    )
+    .unwrap();
+
+    let cert = SshCertificate::from_openssh(line).unwrap();
+    assert_eq!(cert.public_key(), &user_key);
+    assert_eq!(cert.ca_key(), &ca_key);
+    assert_eq!(cert.serial(), 42);
+    assert_eq!(cert.cert_type(), SshCertType::User);
+    assert_eq!(cert.key_id(), "alice@example");
+    assert_eq!(cert.principals(), ["alice", "root"]);
+    assert_eq!(
+        cert.critical_options(),
+        [("force-command".to_string(), "/bin/true".to_string())]
+    );
+    assert_eq!(cert.extensions().len(), 5);
+
+    let now = UNIX_EPOCH + Duration::from_secs(1800000000);
+    assert!(cert
+        .verify(&ca_key, SshCertType::User, "alice", now)
+        .is_ok());
+    assert_eq!(
+        cert.verify(&ca_key, SshCertType::User, "bob", now),
+        Err(Error::InvalidCertificate)
+    );
+    assert_eq!(
+        cert.verify(
+            &ca_key,
+            SshCertType::User,
+            "alice",
+            UNIX_EPOCH + Duration::from_secs(2100000000)
+        ),
+        Err(Error::InvalidCertificate)
+    );
+    assert_eq!(
+        cert.verify(&user_key, SshCertType::User, "alice", now),
+        Err(Error::SignatureMismatch)
+    );
+
+    let mut unrestricted = cert.clone();
+    unrestricted.principals.clear();
+    assert_eq!(
+        unrestricted.verify(&ca_key, SshCertType::User, "alice", now),
+        Err(Error::InvalidCertificate)
+    );
+    unrestricted.cert_type = SshCertType::Host;
+    assert!(unrestricted
+        .verify(&ca_key, SshCertType::Host, "host.example", now)
+        .is_ok());
+
+    assert_eq!(
+        cert.verify(&ca_key, SshCertType::Host, "alice", now),
+        Err(Error::InvalidCertificate)
+    );
+    let mut host = cert.clone();
+    host.cert_type = SshCertType::Host;
+    assert!(host
+        .verify(&ca_key, SshCertType::Host, "alice", now)
+        .is_ok());
+    assert_eq!(
+        host.verify(&ca_key, SshCertType::User, "alice", now),
+        Err(Error::InvalidCertificate)
+    );
+
+    let mut tampered = cert.clone();
+    tampered.signed[100] ^= 1;
+    assert_eq!(
+        tampered.verify(&ca_key, SshCertType::User, "alice", now),
+        Err(Error::SignatureMismatch)
+    );
+}
@@ -0,0 +1,76 @@
+use std::fmt;
+
+use super::ssh;
+use super::{sha1, sha256, PublicKey};
+
+/// SSHFP algorithm number for Ed25519 (RFC 7479).
+pub const SSHFP_ALGORITHM_ED25519: u8 = 4;
+
+/// SSHFP fingerprint type for SHA-1.
+pub const SSHFP_FINGERPRINT_SHA1: u8 = 1;
+
+/// SSHFP fingerprint type for SHA-256.
+pub const SSHFP_FINGERPRINT_SHA256: u8 = 2;
+
+/// The data of an SSHFP DNS record (RFC 4255).
+///
+/// `Display` produces the presentation format of the record data
+/// (`4 2 <hex digest>`), to be prefixed with `<host> IN SSHFP` in a zone
+/// file.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct SshfpRecord {
+    /// Public key algorithm.
+    pub algorithm: u8,
+    /// Fingerprint type.
+    pub fingerprint_type: u8,
+    /// Digest of the SSH wire encoding of the public key.
+    pub fingerprint: Vec<u8>,
+}
+
+impl fmt::Display for SshfpRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} ", self.algorithm, self.fingerprint_type)?;
+        for x in &self.fingerprint {
+            write!(f, "{:02x}", x)?;
+        }
+        Ok(())
+    }
+}
+
+impl PublicKey {
+    /// Returns the SSHFP records for the public key, with SHA-1 and SHA-256
+    /// fingerprints, in that order.
+    pub fn to_sshfp(&self) -> [SshfpRecord; 2] {
+        let blob = ssh::public_key_blob(self);
+        [
+            SshfpRecord {
+                algorithm: SSHFP_ALGORITHM_ED25519,
+                fingerprint_type: SSHFP_FINGERPRINT_SHA1,
+                fingerprint: sha1::Hash::hash(&blob).to_vec(),
+            },
+            SshfpRecord {
+                algorithm: SSHFP_ALGORITHM_ED25519,
+                fingerprint_type: SSHFP_FINGERPRINT_SHA256,
+                fingerprint: sha256::Hash::hash(&blob).to_vec(),
+            },
+        ]
+    }
+}
+
+#[test]
+fn test_sshfp() {
+    let pk = PublicKey::from_any_str(
+        "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIB3bin3lAsOqntz9OzKpQKzEn1XiGpso4xnQ5S/0mZbe",
+    )
+    .unwrap();
+    let records = pk.to_sshfp();
+    // `ssh-keygen -r`
+    assert_eq!(
+        records[0].to_string(),
+        "4 1 459bea02069c373e4678cdccfc9a929b5376d8b9"
+    );
+    assert_eq!(
+        records[1].to_string(),
+        "4 2 cf7b7f12aeb5b2627ab4e7d2d08c36a4aed7975c14bc0d024ff705fbc64210af"
+    );
+}
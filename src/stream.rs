@@ -0,0 +1,161 @@
+use std::io::{self, Read, Write};
+
+use super::sha512;
+use super::{Error, Noise, PublicKey, SecretKey, Signature, SigningState};
+
+/// A writer that forwards everything written through it to an inner writer,
+/// and signs it.
+///
+/// Signatures are Ed25519ph (RFC 8032) signatures, computed incrementally
+/// like `SecretKey::sign_incremental()`.
+pub struct SigningWriter<W: Write> {
+    inner: W,
+    state: SigningState,
+}
+
+impl<W: Write> SigningWriter<W> {
+    /// Creates a new signing writer.
+    pub fn new(inner: W, sk: &SecretKey, noise: Option<Noise>) -> Self {
+        SigningWriter {
+            inner,
+            state: sk.sign_incremental(noise),
+        }
+    }
+
+    /// Returns the inner writer, and the signature of everything that has
+    /// been written.
+    pub fn finish(self) -> Result<(W, Signature), Error> {
+        let signature = self.state.sign()?;
+        Ok((self.inner, signature))
+    }
+}
+
+impl<W: Write> Write for SigningWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let len = self.inner.write(buf)?;
+        self.state.absorb(&buf[..len]);
+        Ok(len)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// A writer that forwards everything written through it to an inner writer,
+/// and verifies an Ed25519ph signature for it, such as one computed by a
+/// `SigningWriter`.
+pub struct VerifyingWriter<W: Write> {
+    inner: W,
+    hasher: sha512::Hash,
+    pk: PublicKey,
+    signature: Signature,
+}
+
+impl<W: Write> VerifyingWriter<W> {
+    /// Creates a new verifying writer for the signature `signature`.
+    pub fn new(inner: W, pk: &PublicKey, signature: &Signature) -> Self {
+        VerifyingWriter {
+            inner,
+            hasher: sha512::Hash::new(),
+            pk: *pk,
+            signature: *signature,
+        }
+    }
+
+    /// Verifies the signature for everything that has been written, and
+    /// returns the inner writer if it is valid.
+    pub fn finish(self) -> Result<W, Error> {
+        self.pk
+            .verify_prehashed(&self.hasher.finalize(), &[], &self.signature)?;
+        Ok(self.inner)
+    }
+}
+
+impl<W: Write> Write for VerifyingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let len = self.inner.write(buf)?;
+        self.hasher.update(&buf[..len]);
+        Ok(len)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+impl SecretKey {
+    /// Computes an Ed25519ph (RFC 8032) signature for everything that can be
+    /// read from `reader`, using a fixed amount of memory.
+    pub fn sign_reader(
+        &self,
+        reader: &mut impl Read,
+        noise: Option<Noise>,
+    ) -> io::Result<Signature> {
+        let prehashed = prehash_reader(reader)?;
+        self.sign_prehashed(&prehashed, &[], noise)
+            .map_err(io::Error::other)
+    }
+}
+
+impl PublicKey {
+    /// Verifies an Ed25519ph (RFC 8032) signature for everything that can be
+    /// read from `reader`, using a fixed amount of memory.
+    ///
+    /// An invalid signature is reported as an `io::ErrorKind::InvalidData`
+    /// error wrapping the underlying `Error`.
+    pub fn verify_reader(&self, reader: &mut impl Read, signature: &Signature) -> io::Result<()> {
+        let prehashed = prehash_reader(reader)?;
+        self.verify_prehashed(&prehashed, &[], signature)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+}
+
+fn prehash_reader(reader: &mut impl Read) -> io::Result<[u8; 64]> {
+    let mut hasher = sha512::Hash::new();
+    let mut buf = [0u8; 4096];
+    loop {
+        match reader.read(&mut buf) {
+            Ok(0) => break,
+            Ok(len) => hasher.update(&buf[..len]),
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(hasher.finalize())
+}
+
+#[test]
+fn test_stream() {
+    use super::KeyPair;
+
+    let kp = KeyPair::from_seed([42u8; 32].into());
+    let message = [0x5au8; 10000];
+
+    let mut writer = SigningWriter::new(Vec::new(), &kp.sk, None);
+    io::copy(&mut &message[..], &mut writer).unwrap();
+    let (copy, signature) = writer.finish().unwrap();
+    assert_eq!(copy, &message[..]);
+    assert_eq!(
+        kp.sk.sign_reader(&mut &message[..], None).unwrap(),
+        signature
+    );
+
+    let mut writer = VerifyingWriter::new(io::sink(), &kp.pk, &signature);
+    io::copy(&mut &message[..], &mut writer).unwrap();
+    assert!(writer.finish().is_ok());
+
+    let mut writer = VerifyingWriter::new(io::sink(), &kp.pk, &signature);
+    io::copy(&mut &message[1..], &mut writer).unwrap();
+    assert_eq!(writer.finish().err(), Some(Error::SignatureMismatch));
+
+    let signature = kp.sk.sign_reader(&mut &message[..], None).unwrap();
+    assert!(kp.pk.verify_reader(&mut &message[..], &signature).is_ok());
+    assert_eq!(
+        kp.pk
+            .verify_reader(&mut &message[1..], &signature)
+            .unwrap_err()
+            .kind(),
+        io::ErrorKind::InvalidData
+    );
+}
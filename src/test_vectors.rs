@@ -0,0 +1,259 @@
+//! Standard test vectors from RFC 8032 (Ed25519) and RFC 7748 (X25519), as
+//! typed constants, for use in integration tests.
+
+#[cfg(feature = "x25519")]
+use super::x25519;
+#[cfg(not(feature = "disable-signatures"))]
+use super::{PublicKey, Seed, Signature};
+
+/// An Ed25519 test vector.
+#[cfg(not(feature = "disable-signatures"))]
+#[derive(Copy, Clone, Debug)]
+pub struct Ed25519Vector {
+    /// The seed of the key pair.
+    pub seed: Seed,
+    /// The public key derived from the seed.
+    pub public_key: PublicKey,
+    /// The message.
+    pub message: &'static [u8],
+    /// The signature of the message.
+    pub signature: Signature,
+}
+
+/// An Ed25519ph test vector.
+#[cfg(not(feature = "disable-signatures"))]
+#[derive(Copy, Clone, Debug)]
+pub struct Ed25519phVector {
+    /// The seed of the key pair.
+    pub seed: Seed,
+    /// The public key derived from the seed.
+    pub public_key: PublicKey,
+    /// The message, whose SHA-512 hash is signed.
+    pub message: &'static [u8],
+    /// The context.
+    pub context: &'static [u8],
+    /// The signature of the message.
+    pub signature: Signature,
+}
+
+/// An X25519 scalar multiplication test vector.
+#[cfg(feature = "x25519")]
+#[derive(Copy, Clone, Debug)]
+pub struct X25519Vector {
+    /// The scalar, clamped before use.
+    pub scalar: x25519::SecretKey,
+    /// The input u-coordinate.
+    pub u: x25519::PublicKey,
+    /// The output u-coordinate.
+    pub output: x25519::PublicKey,
+}
+
+/// An X25519 key exchange test vector.
+#[cfg(feature = "x25519")]
+#[derive(Copy, Clone, Debug)]
+pub struct X25519DhVector {
+    /// Alice's secret key.
+    pub alice_sk: x25519::SecretKey,
+    /// Alice's public key.
+    pub alice_pk: x25519::PublicKey,
+    /// Bob's secret key.
+    pub bob_sk: x25519::SecretKey,
+    /// Bob's public key.
+    pub bob_pk: x25519::PublicKey,
+    /// The shared secret.
+    pub shared: x25519::PublicKey,
+}
+
+/// RFC 8032, section 7.1, tests 1 to 3.
+#[cfg(not(feature = "disable-signatures"))]
+pub const RFC8032_ED25519: [Ed25519Vector; 3] = [
+    // Test 1
+    Ed25519Vector {
+        seed: Seed::new([
+            0x9d, 0x61, 0xb1, 0x9d, 0xef, 0xfd, 0x5a, 0x60, 0xba, 0x84, 0x4a, 0xf4, 0x92, 0xec,
+            0x2c, 0xc4, 0x44, 0x49, 0xc5, 0x69, 0x7b, 0x32, 0x69, 0x19, 0x70, 0x3b, 0xac, 0x03,
+            0x1c, 0xae, 0x7f, 0x60,
+        ]),
+        public_key: PublicKey::new([
+            0xd7, 0x5a, 0x98, 0x01, 0x82, 0xb1, 0x0a, 0xb7, 0xd5, 0x4b, 0xfe, 0xd3, 0xc9, 0x64,
+            0x07, 0x3a, 0x0e, 0xe1, 0x72, 0xf3, 0xda, 0xa6, 0x23, 0x25, 0xaf, 0x02, 0x1a, 0x68,
+            0xf7, 0x07, 0x51, 0x1a,
+        ]),
+        message: b"",
+        signature: Signature::new([
+            0xe5, 0x56, 0x43, 0x00, 0xc3, 0x60, 0xac, 0x72, 0x90, 0x86, 0xe2, 0xcc, 0x80, 0x6e,
+            0x82, 0x8a, 0x84, 0x87, 0x7f, 0x1e, 0xb8, 0xe5, 0xd9, 0x74, 0xd8, 0x73, 0xe0, 0x65,
+            0x22, 0x49, 0x01, 0x55, 0x5f, 0xb8, 0x82, 0x15, 0x90, 0xa3, 0x3b, 0xac, 0xc6, 0x1e,
+            0x39, 0x70, 0x1c, 0xf9, 0xb4, 0x6b, 0xd2, 0x5b, 0xf5, 0xf0, 0x59, 0x5b, 0xbe, 0x24,
+            0x65, 0x51, 0x41, 0x43, 0x8e, 0x7a, 0x10, 0x0b,
+        ]),
+    },
+    // Test 2
+    Ed25519Vector {
+        seed: Seed::new([
+            0x4c, 0xcd, 0x08, 0x9b, 0x28, 0xff, 0x96, 0xda, 0x9d, 0xb6, 0xc3, 0x46, 0xec, 0x11,
+            0x4e, 0x0f, 0x5b, 0x8a, 0x31, 0x9f, 0x35, 0xab, 0xa6, 0x24, 0xda, 0x8c, 0xf6, 0xed,
+            0x4f, 0xb8, 0xa6, 0xfb,
+        ]),
+        public_key: PublicKey::new([
+            0x3d, 0x40, 0x17, 0xc3, 0xe8, 0x43, 0x89, 0x5a, 0x92, 0xb7, 0x0a, 0xa7, 0x4d, 0x1b,
+            0x7e, 0xbc, 0x9c, 0x98, 0x2c, 0xcf, 0x2e, 0xc4, 0x96, 0x8c, 0xc0, 0xcd, 0x55, 0xf1,
+            0x2a, 0xf4, 0x66, 0x0c,
+        ]),
+        message: b"\x72",
+        signature: Signature::new([
+            0x92, 0xa0, 0x09, 0xa9, 0xf0, 0xd4, 0xca, 0xb8, 0x72, 0x0e, 0x82, 0x0b, 0x5f, 0x64,
+            0x25, 0x40, 0xa2, 0xb2, 0x7b, 0x54, 0x16, 0x50, 0x3f, 0x8f, 0xb3, 0x76, 0x22, 0x23,
+            0xeb, 0xdb, 0x69, 0xda, 0x08, 0x5a, 0xc1, 0xe4, 0x3e, 0x15, 0x99, 0x6e, 0x45, 0x8f,
+            0x36, 0x13, 0xd0, 0xf1, 0x1d, 0x8c, 0x38, 0x7b, 0x2e, 0xae, 0xb4, 0x30, 0x2a, 0xee,
+            0xb0, 0x0d, 0x29, 0x16, 0x12, 0xbb, 0x0c, 0x00,
+        ]),
+    },
+    // Test 3
+    Ed25519Vector {
+        seed: Seed::new([
+            0xc5, 0xaa, 0x8d, 0xf4, 0x3f, 0x9f, 0x83, 0x7b, 0xed, 0xb7, 0x44, 0x2f, 0x31, 0xdc,
+            0xb7, 0xb1, 0x66, 0xd3, 0x85, 0x35, 0x07, 0x6f, 0x09, 0x4b, 0x85, 0xce, 0x3a, 0x2e,
+            0x0b, 0x44, 0x58, 0xf7,
+        ]),
+        public_key: PublicKey::new([
+            0xfc, 0x51, 0xcd, 0x8e, 0x62, 0x18, 0xa1, 0xa3, 0x8d, 0xa4, 0x7e, 0xd0, 0x02, 0x30,
+            0xf0, 0x58, 0x08, 0x16, 0xed, 0x13, 0xba, 0x33, 0x03, 0xac, 0x5d, 0xeb, 0x91, 0x15,
+            0x48, 0x90, 0x80, 0x25,
+        ]),
+        message: b"\xaf\x82",
+        signature: Signature::new([
+            0x62, 0x91, 0xd6, 0x57, 0xde, 0xec, 0x24, 0x02, 0x48, 0x27, 0xe6, 0x9c, 0x3a, 0xbe,
+            0x01, 0xa3, 0x0c, 0xe5, 0x48, 0xa2, 0x84, 0x74, 0x3a, 0x44, 0x5e, 0x36, 0x80, 0xd7,
+            0xdb, 0x5a, 0xc3, 0xac, 0x18, 0xff, 0x9b, 0x53, 0x8d, 0x16, 0xf2, 0x90, 0xae, 0x67,
+            0xf7, 0x60, 0x98, 0x4d, 0xc6, 0x59, 0x4a, 0x7c, 0x15, 0xe9, 0x71, 0x6e, 0xd2, 0x8d,
+            0xc0, 0x27, 0xbe, 0xce, 0xea, 0x1e, 0xc4, 0x0a,
+        ]),
+    },
+];
+
+/// RFC 8032, section 7.3.
+#[cfg(not(feature = "disable-signatures"))]
+pub const RFC8032_ED25519PH: Ed25519phVector = Ed25519phVector {
+    seed: Seed::new([
+        0x83, 0x3f, 0xe6, 0x24, 0x09, 0x23, 0x7b, 0x9d, 0x62, 0xec, 0x77, 0x58, 0x75, 0x20, 0x91,
+        0x1e, 0x9a, 0x75, 0x9c, 0xec, 0x1d, 0x19, 0x75, 0x5b, 0x7d, 0xa9, 0x01, 0xb9, 0x6d, 0xca,
+        0x3d, 0x42,
+    ]),
+    public_key: PublicKey::new([
+        0xec, 0x17, 0x2b, 0x93, 0xad, 0x5e, 0x56, 0x3b, 0xf4, 0x93, 0x2c, 0x70, 0xe1, 0x24, 0x50,
+        0x34, 0xc3, 0x54, 0x67, 0xef, 0x2e, 0xfd, 0x4d, 0x64, 0xeb, 0xf8, 0x19, 0x68, 0x34, 0x67,
+        0xe2, 0xbf,
+    ]),
+    message: b"abc",
+    context: b"",
+    signature: Signature::new([
+        0x98, 0xa7, 0x02, 0x22, 0xf0, 0xb8, 0x12, 0x1a, 0xa9, 0xd3, 0x0f, 0x81, 0x3d, 0x68, 0x3f,
+        0x80, 0x9e, 0x46, 0x2b, 0x46, 0x9c, 0x7f, 0xf8, 0x76, 0x39, 0x49, 0x9b, 0xb9, 0x4e, 0x6d,
+        0xae, 0x41, 0x31, 0xf8, 0x50, 0x42, 0x46, 0x3c, 0x2a, 0x35, 0x5a, 0x20, 0x03, 0xd0, 0x62,
+        0xad, 0xf5, 0xaa, 0xa1, 0x0b, 0x8c, 0x61, 0xe6, 0x36, 0x06, 0x2a, 0xaa, 0xd1, 0x1c, 0x2a,
+        0x26, 0x08, 0x34, 0x06,
+    ]),
+};
+
+/// RFC 7748, section 5.2.
+#[cfg(feature = "x25519")]
+pub const RFC7748_X25519: [X25519Vector; 2] = [
+    X25519Vector {
+        scalar: x25519::SecretKey::new([
+            0xa5, 0x46, 0xe3, 0x6b, 0xf0, 0x52, 0x7c, 0x9d, 0x3b, 0x16, 0x15, 0x4b, 0x82, 0x46,
+            0x5e, 0xdd, 0x62, 0x14, 0x4c, 0x0a, 0xc1, 0xfc, 0x5a, 0x18, 0x50, 0x6a, 0x22, 0x44,
+            0xba, 0x44, 0x9a, 0xc4,
+        ]),
+        u: x25519::PublicKey::new([
+            0xe6, 0xdb, 0x68, 0x67, 0x58, 0x30, 0x30, 0xdb, 0x35, 0x94, 0xc1, 0xa4, 0x24, 0xb1,
+            0x5f, 0x7c, 0x72, 0x66, 0x24, 0xec, 0x26, 0xb3, 0x35, 0x3b, 0x10, 0xa9, 0x03, 0xa6,
+            0xd0, 0xab, 0x1c, 0x4c,
+        ]),
+        output: x25519::PublicKey::new([
+            0xc3, 0xda, 0x55, 0x37, 0x9d, 0xe9, 0xc6, 0x90, 0x8e, 0x94, 0xea, 0x4d, 0xf2, 0x8d,
+            0x08, 0x4f, 0x32, 0xec, 0xcf, 0x03, 0x49, 0x1c, 0x71, 0xf7, 0x54, 0xb4, 0x07, 0x55,
+            0x77, 0xa2, 0x85, 0x52,
+        ]),
+    },
+    X25519Vector {
+        scalar: x25519::SecretKey::new([
+            0x4b, 0x66, 0xe9, 0xd4, 0xd1, 0xb4, 0x67, 0x3c, 0x5a, 0xd2, 0x26, 0x91, 0x95, 0x7d,
+            0x6a, 0xf5, 0xc1, 0x1b, 0x64, 0x21, 0xe0, 0xea, 0x01, 0xd4, 0x2c, 0xa4, 0x16, 0x9e,
+            0x79, 0x18, 0xba, 0x0d,
+        ]),
+        u: x25519::PublicKey::new([
+            0xe5, 0x21, 0x0f, 0x12, 0x78, 0x68, 0x11, 0xd3, 0xf4, 0xb7, 0x95, 0x9d, 0x05, 0x38,
+            0xae, 0x2c, 0x31, 0xdb, 0xe7, 0x10, 0x6f, 0xc0, 0x3c, 0x3e, 0xfc, 0x4c, 0xd5, 0x49,
+            0xc7, 0x15, 0xa4, 0x93,
+        ]),
+        output: x25519::PublicKey::new([
+            0x95, 0xcb, 0xde, 0x94, 0x76, 0xe8, 0x90, 0x7d, 0x7a, 0xad, 0xe4, 0x5c, 0xb4, 0xb8,
+            0x73, 0xf8, 0x8b, 0x59, 0x5a, 0x68, 0x79, 0x9f, 0xa1, 0x52, 0xe6, 0xf8, 0xf7, 0x64,
+            0x7a, 0xac, 0x79, 0x57,
+        ]),
+    },
+];
+
+/// RFC 7748, section 6.1.
+#[cfg(feature = "x25519")]
+pub const RFC7748_DH: X25519DhVector = X25519DhVector {
+    alice_sk: x25519::SecretKey::new([
+        0x77, 0x07, 0x6d, 0x0a, 0x73, 0x18, 0xa5, 0x7d, 0x3c, 0x16, 0xc1, 0x72, 0x51, 0xb2, 0x66,
+        0x45, 0xdf, 0x4c, 0x2f, 0x87, 0xeb, 0xc0, 0x99, 0x2a, 0xb1, 0x77, 0xfb, 0xa5, 0x1d, 0xb9,
+        0x2c, 0x2a,
+    ]),
+    alice_pk: x25519::PublicKey::new([
+        0x85, 0x20, 0xf0, 0x09, 0x89, 0x30, 0xa7, 0x54, 0x74, 0x8b, 0x7d, 0xdc, 0xb4, 0x3e, 0xf7,
+        0x5a, 0x0d, 0xbf, 0x3a, 0x0d, 0x26, 0x38, 0x1a, 0xf4, 0xeb, 0xa4, 0xa9, 0x8e, 0xaa, 0x9b,
+        0x4e, 0x6a,
+    ]),
+    bob_sk: x25519::SecretKey::new([
+        0x5d, 0xab, 0x08, 0x7e, 0x62, 0x4a, 0x8a, 0x4b, 0x79, 0xe1, 0x7f, 0x8b, 0x83, 0x80, 0x0e,
+        0xe6, 0x6f, 0x3b, 0xb1, 0x29, 0x26, 0x18, 0xb6, 0xfd, 0x1c, 0x2f, 0x8b, 0x27, 0xff, 0x88,
+        0xe0, 0xeb,
+    ]),
+    bob_pk: x25519::PublicKey::new([
+        0xde, 0x9e, 0xdb, 0x7d, 0x7b, 0x7d, 0xc1, 0xb4, 0xd3, 0x5b, 0x61, 0xc2, 0xec, 0xe4, 0x35,
+        0x37, 0x3f, 0x83, 0x43, 0xc8, 0x5b, 0x78, 0x67, 0x4d, 0xad, 0xfc, 0x7e, 0x14, 0x6f, 0x88,
+        0x2b, 0x4f,
+    ]),
+    shared: x25519::PublicKey::new([
+        0x4a, 0x5d, 0x9d, 0x5b, 0xa4, 0xce, 0x2d, 0xe1, 0x72, 0x8e, 0x3b, 0xf4, 0x80, 0x35, 0x0f,
+        0x25, 0xe0, 0x7e, 0x21, 0xc9, 0x47, 0xd1, 0x9e, 0x33, 0x76, 0xf0, 0x9b, 0x3c, 0x1e, 0x16,
+        0x17, 0x42,
+    ]),
+};
+
+#[test]
+fn test_test_vectors() {
+    #[cfg(not(feature = "disable-signatures"))]
+    {
+        use super::{sha512, KeyPair};
+
+        for v in RFC8032_ED25519.iter() {
+            let kp = KeyPair::from_seed(v.seed);
+            assert_eq!(kp.pk, v.public_key);
+            assert_eq!(kp.sk.sign(v.message, None), v.signature);
+        }
+        let v = RFC8032_ED25519PH;
+        let kp = KeyPair::from_seed(v.seed);
+        assert_eq!(kp.pk, v.public_key);
+        let prehashed = sha512::Hash::hash(v.message);
+        assert!(v
+            .public_key
+            .verify_prehashed(&prehashed, v.context, &v.signature)
+            .is_ok());
+    }
+    #[cfg(feature = "x25519")]
+    {
+        for v in RFC7748_X25519.iter() {
+            assert_eq!(v.u.dh(&v.scalar), Ok(v.output));
+        }
+        let v = RFC7748_DH;
+        assert_eq!(v.alice_sk.recover_public_key(), Ok(v.alice_pk));
+        assert_eq!(v.bob_sk.recover_public_key(), Ok(v.bob_pk));
+        assert_eq!(v.bob_pk.dh(&v.alice_sk), Ok(v.shared));
+        assert_eq!(v.alice_pk.dh(&v.bob_sk), Ok(v.shared));
+    }
+}
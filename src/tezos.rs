@@ -0,0 +1,164 @@
+use super::common::wipe;
+use super::{base58, blake2b, sha256};
+use super::{Error, PublicKey, SecretKey, Seed, Signature};
+
+const PREFIX_EDPK: &[u8] = &[13, 15, 37, 217];
+const PREFIX_EDSK_SEED: &[u8] = &[13, 15, 58, 7];
+const PREFIX_EDSK: &[u8] = &[43, 246, 78, 7];
+const PREFIX_EDSIG: &[u8] = &[9, 245, 205, 134, 18];
+const PREFIX_TZ1: &[u8] = &[6, 161, 159];
+
+/// Length of a Tezos public key hash.
+pub const TEZOS_ADDRESS_HASH_BYTES: usize = 20;
+
+/// Encodes `prefix || data` with a double SHA-256 checksum, in Base58.
+fn encode_check(prefix: &[u8], data: &[u8]) -> String {
+    let mut bin = Vec::with_capacity(prefix.len() + data.len() + 4);
+    bin.extend_from_slice(prefix);
+    bin.extend_from_slice(data);
+    let checksum = sha256::Hash::hash(sha256::Hash::hash(&bin));
+    bin.extend_from_slice(&checksum[..4]);
+    let s = base58::encode(&bin);
+    wipe(&mut bin);
+    s
+}
+
+/// Decodes a Base58Check string, and returns its payload if it starts with
+/// `prefix` and is `len` bytes long.
+fn decode_check(prefix: &[u8], len: usize, s: &str) -> Result<Vec<u8>, Error> {
+    let mut bin = base58::decode(s.trim(), prefix.len() + len + 4)?;
+    if bin.len() != prefix.len() + len + 4 || !bin.starts_with(prefix) {
+        wipe(&mut bin);
+        return Err(Error::ParseError);
+    }
+    let (data, checksum) = bin.split_at(prefix.len() + len);
+    if sha256::Hash::hash(sha256::Hash::hash(data))[..4] != *checksum {
+        wipe(&mut bin);
+        return Err(Error::ParseError);
+    }
+    let payload = data[prefix.len()..].to_vec();
+    wipe(&mut bin);
+    Ok(payload)
+}
+
+impl PublicKey {
+    /// Returns the Tezos encoding of the public key (`edpk...`).
+    pub fn to_tezos(&self) -> String {
+        encode_check(PREFIX_EDPK, &self[..])
+    }
+
+    /// Imports a public key from its Tezos encoding (`edpk...`).
+    pub fn from_tezos(s: &str) -> Result<Self, Error> {
+        PublicKey::from_slice(&decode_check(PREFIX_EDPK, PublicKey::BYTES, s)?)
+    }
+
+    /// Returns the Tezos public key hash: BLAKE2b-160 of the public key.
+    pub fn tezos_address_hash(&self) -> [u8; TEZOS_ADDRESS_HASH_BYTES] {
+        let mut h = blake2b::Hash::new(TEZOS_ADDRESS_HASH_BYTES);
+        h.update(&self[..]);
+        let mut hash = [0u8; TEZOS_ADDRESS_HASH_BYTES];
+        hash.copy_from_slice(&h.finalize()[..TEZOS_ADDRESS_HASH_BYTES]);
+        hash
+    }
+
+    /// Returns the Tezos address of the public key (`tz1...`).
+    pub fn to_tezos_address(&self) -> String {
+        encode_check(PREFIX_TZ1, &self.tezos_address_hash())
+    }
+}
+
+/// Decodes a Tezos `tz1` address, and returns the public key hash.
+pub fn tezos_address_hash(address: &str) -> Result<[u8; TEZOS_ADDRESS_HASH_BYTES], Error> {
+    let payload = decode_check(PREFIX_TZ1, TEZOS_ADDRESS_HASH_BYTES, address)?;
+    let mut hash = [0u8; TEZOS_ADDRESS_HASH_BYTES];
+    hash.copy_from_slice(&payload);
+    Ok(hash)
+}
+
+impl SecretKey {
+    /// Returns the Tezos encoding of the full secret key (`edsk...`, 98
+    /// characters).
+    pub fn to_tezos(&self) -> String {
+        encode_check(PREFIX_EDSK, &self[..])
+    }
+
+    /// Imports a secret key from its Tezos encoding: either a full secret
+    /// key (98 characters), or a seed (54 characters).
+    ///
+    /// Full secret keys are validated.
+    pub fn from_tezos(s: &str) -> Result<Self, Error> {
+        if let Ok(mut seed) = decode_check(PREFIX_EDSK_SEED, Seed::BYTES, s) {
+            let sk = Seed::from_slice(&seed).and_then(|seed| SecretKey::from_seed(&seed));
+            wipe(&mut seed);
+            return sk;
+        }
+        let mut bin = decode_check(PREFIX_EDSK, SecretKey::BYTES, s)?;
+        let sk = SecretKey::from_slice(&bin);
+        wipe(&mut bin);
+        let sk = sk?;
+        sk.validate()?;
+        Ok(sk)
+    }
+}
+
+impl Seed {
+    /// Returns the Tezos encoding of the seed (`edsk...`, 54 characters).
+    pub fn to_tezos(&self) -> String {
+        encode_check(PREFIX_EDSK_SEED, &self[..])
+    }
+}
+
+impl Signature {
+    /// Returns the Tezos encoding of the signature (`edsig...`).
+    pub fn to_tezos(&self) -> String {
+        encode_check(PREFIX_EDSIG, &self[..])
+    }
+
+    /// Imports a signature from its Tezos encoding (`edsig...`).
+    pub fn from_tezos(s: &str) -> Result<Self, Error> {
+        Signature::from_slice(&decode_check(PREFIX_EDSIG, Signature::BYTES, s)?)
+    }
+}
+
+#[test]
+fn test_tezos() {
+    use super::KeyPair;
+
+    assert_eq!(
+        encode_check(PREFIX_TZ1, &[0u8; TEZOS_ADDRESS_HASH_BYTES]),
+        "tz1Ke2h7sDdakHJQh8WX4Z372du1KChsksyU"
+    );
+
+    let seed = Seed::new([5u8; Seed::BYTES]);
+    let kp = KeyPair::from_seed(seed);
+    let pk = kp.pk.to_tezos();
+    assert_eq!(pk, "edpkuUsvXRJ5KRR9eeHyhfa3ThHXYZhbXmzEAuRfkaUtkeiy3DQGQE");
+    assert_eq!(PublicKey::from_tezos(&pk).unwrap(), kp.pk);
+
+    let address = kp.pk.to_tezos_address();
+    assert_eq!(address, "tz1Yi6aFVS85UZAUFqKn696Uv8tEP1AAQnJB");
+    assert_eq!(
+        tezos_address_hash(&address).unwrap(),
+        kp.pk.tezos_address_hash()
+    );
+
+    let edsk_seed = seed.to_tezos();
+    assert_eq!(
+        edsk_seed,
+        "edsk2i7VsnercrJh3JoagS3Mn7fgbwWkuxkR1nmeJ9sEEjY243Fvps"
+    );
+    let edsk = kp.sk.to_tezos();
+    assert_eq!(edsk, "edskRcfsBEiV4RxXbh2Fmn7ZXf6EecEsgPCHu4u1CmRS4xQgZP7twRfUfcDeHBLRdZ97LmZtDVmHn3Vvn5aN7pVtfL8WhRdS8W");
+    assert_eq!(SecretKey::from_tezos(&edsk_seed).unwrap(), kp.sk);
+    assert_eq!(SecretKey::from_tezos(&edsk).unwrap(), kp.sk);
+
+    let signature = kp.sk.sign(b"hello", None);
+    let edsig = signature.to_tezos();
+    assert_eq!(edsig, "edsigtYSGXZhUWNPHK4HTo96hTePdZX9jSMEQswJ1cV1kqMPnS3FdkPSahDNjxLYQ26oUeiKMzKgn12RBxQd6naWnRQRqFtaJJE");
+    assert_eq!(Signature::from_tezos(&edsig).unwrap(), signature);
+
+    assert!(PublicKey::from_tezos(&address).is_err());
+    assert!(
+        PublicKey::from_tezos("edpkuUsvXRJ5KRR9eeHyhfa3ThHXYZhbXmzEAuRfkaUtkeiy3DQGQF").is_err()
+    );
+}
@@ -0,0 +1,68 @@
+//! rustls integration.
+//!
+//! `KeyPair` implements `rustls::sign::SigningKey`, so that Ed25519
+//! certificates can be served without depending on another crypto library
+//! for signatures:
+//!
+//! ```rust,ignore
+//! let certified_key = CertifiedKey::new(cert_chain, Arc::new(key_pair));
+//! ```
+
+use ::rustls::pki_types::SubjectPublicKeyInfoDer;
+use ::rustls::sign::{Signer, SigningKey};
+use ::rustls::{Error as TlsError, SignatureAlgorithm, SignatureScheme};
+
+use super::KeyPair;
+
+impl SigningKey for KeyPair {
+    fn choose_scheme(&self, offered: &[SignatureScheme]) -> Option<Box<dyn Signer>> {
+        if offered.contains(&SignatureScheme::ED25519) {
+            Some(Box::new(*self))
+        } else {
+            None
+        }
+    }
+
+    fn public_key(&self) -> Option<SubjectPublicKeyInfoDer<'_>> {
+        Some(SubjectPublicKeyInfoDer::from(self.pk.to_der()))
+    }
+
+    fn algorithm(&self) -> SignatureAlgorithm {
+        SignatureAlgorithm::ED25519
+    }
+}
+
+impl Signer for KeyPair {
+    fn sign(&self, message: &[u8]) -> Result<Vec<u8>, TlsError> {
+        self.sk
+            .try_sign(message, None)
+            .map(|signature| signature.to_vec())
+            .map_err(|e| TlsError::General(e.to_string()))
+    }
+
+    fn scheme(&self) -> SignatureScheme {
+        SignatureScheme::ED25519
+    }
+}
+
+#[test]
+fn test_tls() {
+    let kp = KeyPair::from_seed([42u8; 32].into());
+    assert!(kp
+        .choose_scheme(&[SignatureScheme::ECDSA_NISTP256_SHA256])
+        .is_none());
+    let signer = kp
+        .choose_scheme(&[
+            SignatureScheme::ECDSA_NISTP256_SHA256,
+            SignatureScheme::ED25519,
+        ])
+        .unwrap();
+    assert_eq!(signer.scheme(), SignatureScheme::ED25519);
+    let signature = signer.sign(b"message").unwrap();
+    let signature = super::Signature::from_slice(&signature).unwrap();
+    assert!(kp.pk.verify(b"message", &signature).is_ok());
+    assert_eq!(
+        SigningKey::public_key(&kp).unwrap().as_ref(),
+        &kp.pk.to_der()[..]
+    );
+}
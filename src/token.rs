@@ -0,0 +1,145 @@
+use std::time::SystemTime;
+
+use ct_codecs::{Base64UrlSafeNoPadding, Decoder, Encoder};
+
+use super::common::unix_time;
+use super::{Error, KeyPair, PublicKey, Signature, KEY_ID_BYTES};
+
+const VERSION: u8 = 1;
+const CONTEXT: &[u8] = b"ed25519-compact token v1";
+const HEADER_BYTES: usize = 1 + KEY_ID_BYTES + 8;
+
+/// The content of a signed token.
+///
+/// A token is a single URL-safe Base64 string encoding a version byte, the
+/// key ID of the signer, an optional expiration time, the payload and a
+/// signature. It is short enough to be used as a license key or as an
+/// activation code.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct SignedToken {
+    /// Key ID of the public key the token was signed with.
+    pub key_id: [u8; KEY_ID_BYTES],
+    /// Expiration time, in seconds since the Unix epoch, if any.
+    pub expires_at: Option<u64>,
+    /// The signed payload.
+    pub payload: Vec<u8>,
+}
+
+impl SignedToken {
+    /// Decodes a token without verifying it.
+    ///
+    /// This can be used to find the key a token was signed with, using its
+    /// key ID. The content must not be trusted before the token has been
+    /// verified with `PublicKey::verify_token()`.
+    pub fn decode_unverified(token: &str) -> Result<Self, Error> {
+        decode(token).map(|(token, _, _)| token)
+    }
+}
+
+fn decode(token: &str) -> Result<(SignedToken, Vec<u8>, Signature), Error> {
+    let mut bin = Base64UrlSafeNoPadding::decode_to_vec(token.trim(), None)
+        .map_err(|_| Error::InvalidBase64)?;
+    if bin.len() < HEADER_BYTES + Signature::BYTES || bin[0] != VERSION {
+        return Err(Error::ParseError);
+    }
+    let signature = Signature::from_slice(&bin[bin.len() - Signature::BYTES..])?;
+    bin.truncate(bin.len() - Signature::BYTES);
+    let mut key_id = [0u8; KEY_ID_BYTES];
+    key_id.copy_from_slice(&bin[1..1 + KEY_ID_BYTES]);
+    let mut expires_at = [0u8; 8];
+    expires_at.copy_from_slice(&bin[1 + KEY_ID_BYTES..HEADER_BYTES]);
+    let expires_at = match u64::from_be_bytes(expires_at) {
+        0 => None,
+        t => Some(t),
+    };
+    let token = SignedToken {
+        key_id,
+        expires_at,
+        payload: bin[HEADER_BYTES..].to_vec(),
+    };
+    Ok((token, bin, signature))
+}
+
+impl KeyPair {
+    /// Issues a signed token for `payload`, optionally expiring at
+    /// `expires_at`.
+    ///
+    /// Returns `Error::FaultDetected` if a fault is detected while signing.
+    pub fn issue_token(
+        &self,
+        payload: &[u8],
+        expires_at: Option<SystemTime>,
+    ) -> Result<String, Error> {
+        let mut bin = Vec::with_capacity(HEADER_BYTES + payload.len() + Signature::BYTES);
+        bin.push(VERSION);
+        bin.extend_from_slice(&self.pk.key_id());
+        let expires_at = expires_at.map(|t| unix_time(t).max(1)).unwrap_or(0);
+        bin.extend_from_slice(&expires_at.to_be_bytes());
+        bin.extend_from_slice(payload);
+        let signature = self.sk.try_sign_vectored(&[CONTEXT, &bin], None)?;
+        bin.extend_from_slice(&signature[..]);
+        Ok(Base64UrlSafeNoPadding::encode_to_string(bin).unwrap())
+    }
+}
+
+impl PublicKey {
+    /// Verifies a token issued with `KeyPair::issue_token()`, and returns its
+    /// content.
+    ///
+    /// Returns `Error::Expired` if the token has expired at time `now`.
+    pub fn verify_token(&self, token: &str, now: SystemTime) -> Result<SignedToken, Error> {
+        let (token, signed, signature) = decode(token)?;
+        if token.key_id != self.key_id() {
+            return Err(Error::SignatureMismatch);
+        }
+        let mut message = Vec::with_capacity(CONTEXT.len() + signed.len());
+        message.extend_from_slice(CONTEXT);
+        message.extend_from_slice(&signed);
+        self.verify(message, &signature)?;
+        if let Some(expires_at) = token.expires_at {
+            if unix_time(now) >= expires_at {
+                return Err(Error::Expired);
+            }
+        }
+        Ok(token)
+    }
+}
+
+#[test]
+fn test_token() {
+    use super::Seed;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    let kp = KeyPair::from_seed(Seed::new([42u8; Seed::BYTES]));
+    let kp2 = KeyPair::from_seed(Seed::new([43u8; Seed::BYTES]));
+    let now = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+    let expires_at = now + Duration::from_secs(86400);
+
+    let token = kp.issue_token(b"pro;seats=5", Some(expires_at)).unwrap();
+    let content = SignedToken::decode_unverified(&token).unwrap();
+    assert_eq!(content.key_id, kp.pk.key_id());
+    assert_eq!(content.expires_at, Some(1_700_086_400));
+    let verified = kp.pk.verify_token(&token, now).unwrap();
+    assert_eq!(verified, content);
+    assert_eq!(verified.payload, b"pro;seats=5");
+    assert_eq!(kp.pk.verify_token(&token, expires_at), Err(Error::Expired));
+    assert_eq!(
+        kp2.pk.verify_token(&token, now),
+        Err(Error::SignatureMismatch)
+    );
+
+    let token = kp.issue_token(b"", None).unwrap();
+    assert_eq!(token.len(), 108);
+    assert_eq!(
+        kp.pk
+            .verify_token(&token, UNIX_EPOCH + Duration::from_secs(u32::MAX as u64))
+            .unwrap()
+            .expires_at,
+        None
+    );
+
+    let mut tampered = Base64UrlSafeNoPadding::decode_to_vec(&token, None).unwrap();
+    tampered[12] ^= 1;
+    let tampered = Base64UrlSafeNoPadding::encode_to_string(tampered).unwrap();
+    assert!(kp.pk.verify_token(&tampered, now).is_err());
+}
@@ -0,0 +1,123 @@
+use ct_codecs::{Decoder, Encoder, Hex};
+use serde_json::{json, Value};
+
+use super::json::{canonical_json, Flavor};
+use super::{sha256, Error, KeyPair, PublicKey, Signature};
+
+/// Encodes a JSON value in the OLPC canonical form used by TUF and in-toto.
+pub fn tuf_canonical_json(value: &Value) -> Result<String, Error> {
+    canonical_json(value, Flavor::Olpc)
+}
+
+impl PublicKey {
+    /// Returns the TUF/in-toto representation of the public key:
+    /// `{"keytype": "ed25519", "scheme": "ed25519", "keyval": {"public": <hex>}}`.
+    pub fn to_tuf_key(&self) -> Value {
+        json!({
+            "keytype": "ed25519",
+            "scheme": "ed25519",
+            "keyval": { "public": Hex::encode_to_string(&self[..]).unwrap() },
+        })
+    }
+
+    /// Returns the TUF key ID of the public key: the hex-encoded SHA-256 hash
+    /// of the canonical form of its TUF representation.
+    pub fn tuf_key_id(&self) -> String {
+        let key = tuf_canonical_json(&self.to_tuf_key()).unwrap();
+        Hex::encode_to_string(sha256::Hash::hash(key)).unwrap()
+    }
+
+    /// Verifies the signature made with that key on TUF/in-toto metadata
+    /// (`{"signed": {...}, "signatures": [{"keyid": ..., "sig": ...}]}`).
+    pub fn verify_tuf(&self, metadata: &Value) -> Result<(), Error> {
+        let key_id = self.tuf_key_id();
+        let signed = metadata.get("signed").ok_or(Error::ParseError)?;
+        let sig = metadata
+            .get("signatures")
+            .and_then(Value::as_array)
+            .ok_or(Error::ParseError)?
+            .iter()
+            .find(|s| s.get("keyid").and_then(Value::as_str) == Some(&key_id))
+            .and_then(|s| s.get("sig"))
+            .and_then(Value::as_str)
+            .ok_or(Error::SignatureMismatch)?;
+        let mut bin = [0u8; Signature::BYTES];
+        let bin = Hex::decode(&mut bin, sig, None).map_err(|_| Error::InvalidSignature)?;
+        let signature = Signature::from_slice(bin)?;
+        self.verify(tuf_canonical_json(signed)?, &signature)
+    }
+}
+
+impl KeyPair {
+    /// Signs the `signed` property of TUF/in-toto metadata, and adds the
+    /// signature to its `signatures` array, replacing a previous signature
+    /// made with the same key.
+    pub fn sign_tuf(&self, metadata: &mut Value) -> Result<(), Error> {
+        let signed = metadata.get("signed").ok_or(Error::ParseError)?;
+        let signature = self.sk.sign(tuf_canonical_json(signed)?, None);
+        let key_id = self.pk.tuf_key_id();
+        let object = metadata.as_object_mut().ok_or(Error::ParseError)?;
+        let signatures = object
+            .entry("signatures")
+            .or_insert_with(|| Value::Array(Vec::new()))
+            .as_array_mut()
+            .ok_or(Error::ParseError)?;
+        signatures.retain(|s| s.get("keyid").and_then(Value::as_str) != Some(&key_id));
+        signatures.push(json!({
+            "keyid": key_id,
+            "sig": Hex::encode_to_string(&signature[..]).unwrap(),
+        }));
+        Ok(())
+    }
+}
+
+#[test]
+fn test_tuf() {
+    use super::Seed;
+
+    let kp = KeyPair::from_seed(Seed::new([7u8; Seed::BYTES]));
+    assert_eq!(
+        kp.pk.tuf_key_id(),
+        "bfe1a83869e5bf574e3ff3acbb83f840de84a990d10059f3096f0bf4f2b90c10"
+    );
+    let mut metadata = json!({
+        "signed": {
+            "_type": "targets",
+            "spec_version": "1.0.31",
+            "version": 1,
+            "expires": "2030-01-01T00:00:00Z",
+            "targets": {
+                "file.txt": {
+                    "length": 5,
+                    "hashes": {
+                        "sha256": "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824"
+                    }
+                }
+            }
+        },
+        "signatures": []
+    });
+    assert_eq!(
+        tuf_canonical_json(&metadata["signed"]).unwrap(),
+        r#"{"_type":"targets","expires":"2030-01-01T00:00:00Z","spec_version":"1.0.31","targets":{"file.txt":{"hashes":{"sha256":"2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824"},"length":5}},"version":1}"#
+    );
+    kp.sign_tuf(&mut metadata).unwrap();
+    kp.sign_tuf(&mut metadata).unwrap();
+    assert_eq!(
+        metadata["signatures"],
+        json!([{
+            "keyid": "bfe1a83869e5bf574e3ff3acbb83f840de84a990d10059f3096f0bf4f2b90c10",
+            "sig": "9d00b9d250dc84f46fe672e028e7d3ee4503bc0e43700974fc44499895909294ccf5544d0f871423c1d826be9732e5b2a5a388698c06b7141c52890c23a27503"
+        }])
+    );
+    assert!(kp.pk.verify_tuf(&metadata).is_ok());
+    let kp2 = KeyPair::from_seed(Seed::new([8u8; Seed::BYTES]));
+    assert!(kp2.pk.verify_tuf(&metadata).is_err());
+    metadata["signed"]["version"] = json!(2);
+    assert!(kp.pk.verify_tuf(&metadata).is_err());
+
+    assert_eq!(
+        tuf_canonical_json(&json!({ "a": "\n\"\\", "b": u64::MAX })).unwrap(),
+        "{\"a\":\"\n\\\"\\\\\",\"b\":18446744073709551615}"
+    );
+}
@@ -1,5 +1,9 @@
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::fmt;
 use core::ops::{Deref, DerefMut};
 
+use super::common::{clamp, fmt_redacted, wipe};
 use super::error::Error;
 use super::field25519::*;
 
@@ -11,7 +15,7 @@ impl PublicKey {
     pub const BYTES: usize = 32;
 
     /// Creates a public key from raw bytes.
-    pub fn new(pk: [u8; PublicKey::BYTES]) -> Self {
+    pub const fn new(pk: [u8; PublicKey::BYTES]) -> Self {
         PublicKey(pk)
     }
 
@@ -39,8 +43,10 @@ impl PublicKey {
     /// Multiply the point represented by the public key by the scalar after
     /// clamping it
     pub fn dh(&self, sk: &SecretKey) -> Result<Self, Error> {
-        let sk = sk.clamped();
-        self.ladder(&sk.0, 255)
+        let mut sk = sk.clamped();
+        let res = self.ladder(&sk.0, 255);
+        wipe(&mut sk.0);
+        res
     }
 
     /// Multiply the point represented by the public key by the scalar WITHOUT
@@ -51,6 +57,11 @@ impl PublicKey {
     }
 
     pub(crate) fn ladder(&self, s: &[u8], bits: usize) -> Result<Self, Error> {
+        let (x2, z2) = self.ladder_projective(s, bits);
+        PublicKey::from_projective(x2, z2.invert())
+    }
+
+    fn ladder_projective(&self, s: &[u8], bits: usize) -> (Fe, Fe) {
         let x1 = Fe::from_bytes(&self.0);
         let mut x2 = FE_ONE;
         let mut z2 = FE_ZERO;
@@ -80,12 +91,15 @@ impl PublicKey {
             pos -= 1;
         }
         Fe::cswap2(&mut x2, &mut x3, &mut z2, &mut z3, swap);
-        z2 = z2.invert();
-        x2 = x2 * z2;
-        if !x2.is_nonzero() {
+        (x2, z2)
+    }
+
+    fn from_projective(x: Fe, z_inv: Fe) -> Result<Self, Error> {
+        let x = x * z_inv;
+        if !x.is_nonzero() {
             return Err(Error::WeakPublicKey);
         }
-        Ok(PublicKey(x2.to_bytes()))
+        Ok(PublicKey(x.to_bytes()))
     }
 
     /// The Curve25519 base point
@@ -105,15 +119,21 @@ impl Deref for PublicKey {
 }
 
 /// A secret key.
-#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[derive(Copy, Clone, Eq, PartialEq, Hash)]
 pub struct SecretKey([u8; SecretKey::BYTES]);
 
+impl fmt::Debug for SecretKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_redacted(f, "SecretKey", &self.0)
+    }
+}
+
 impl SecretKey {
     /// Number of bytes in a secret key.
     pub const BYTES: usize = 32;
 
     /// Creates a secret key from raw bytes.
-    pub fn new(sk: [u8; SecretKey::BYTES]) -> Self {
+    pub const fn new(sk: [u8; SecretKey::BYTES]) -> Self {
         SecretKey(sk)
     }
 
@@ -130,19 +150,76 @@ impl SecretKey {
     /// Perform the X25519 clamping magic
     pub fn clamped(&self) -> SecretKey {
         let mut clamped = *self;
-        clamped[0] &= 248;
-        clamped[31] &= 63;
-        clamped[31] |= 64;
+        clamp(&mut clamped.0);
         clamped
     }
 
     /// Recover the public key
     pub fn recover_public_key(&self) -> Result<PublicKey, Error> {
-        let sk = self.clamped();
-        PublicKey::base_point().ladder(&sk.0, 255)
+        let mut sk = self.clamped();
+        let res = PublicKey::base_point().ladder(&sk.0, 255);
+        wipe(&mut sk.0);
+        res
     }
 }
 
+/// Computes the shared secrets between the secret key `sk` and each of the
+/// public keys `pks`.
+///
+/// This is equivalent to calling `PublicKey::dh()` for every public key,
+/// but the secret key is only clamped once, and a single field inversion is
+/// shared by all the computations.
+#[cfg(feature = "alloc")]
+pub fn dh_batch(sk: &SecretKey, pks: &[PublicKey]) -> Vec<Result<PublicKey, Error>> {
+    let mut sk = sk.clamped();
+    let (xs, mut zs): (Vec<Fe>, Vec<Fe>) = pks
+        .iter()
+        .map(|pk| pk.ladder_projective(&sk.0, 255))
+        .unzip();
+    wipe(&mut sk.0);
+    batch_invert(&mut zs);
+    xs.into_iter()
+        .zip(zs)
+        .map(|(x, z_inv)| PublicKey::from_projective(x, z_inv))
+        .collect()
+}
+
+/// Replaces every element of `zs` with its inverse, using a single field
+/// inversion (Montgomery's trick). Zero elements are left unchanged.
+#[cfg(feature = "alloc")]
+fn batch_invert(zs: &mut [Fe]) {
+    let mut products = Vec::with_capacity(zs.len());
+    let mut acc = FE_ONE;
+    for z in zs.iter() {
+        products.push(acc);
+        if z.is_nonzero() {
+            acc = acc * *z;
+        }
+    }
+    let mut acc_inv = acc.invert();
+    for (z, product) in zs.iter_mut().zip(products).rev() {
+        if z.is_nonzero() {
+            let z_inv = acc_inv * product;
+            acc_inv = acc_inv * *z;
+            *z = z_inv;
+        }
+    }
+}
+
+/// Multiplies the point with u-coordinate `point` by `scalar`, and returns
+/// the u-coordinate of the result, as required by PAKE and OPRF
+/// constructions.
+///
+/// Unlike `PublicKey::dh()`, the scalar is not clamped, all its 256 bits
+/// are used, and points of small order are not rejected: the result is zero
+/// if it is the point at infinity. The most significant bit of `point` is
+/// ignored, as specified in RFC 7748.
+#[cfg(feature = "hazmat")]
+pub fn scalarmult(scalar: &[u8; 32], point: &[u8; 32]) -> [u8; 32] {
+    let (x, z) = PublicKey(*point).ladder_projective(scalar, 256);
+    (x * z.invert()).to_bytes()
+}
+
 impl Deref for SecretKey {
     type Target = [u8; SecretKey::BYTES];
 
@@ -173,18 +250,33 @@ impl KeyPair {
     pub const BYTES: usize = SecretKey::BYTES;
 
     /// Generates a new key pair.
-    #[cfg(feature = "random")]
+    #[cfg(any(feature = "random", feature = "entropy-source"))]
     pub fn generate() -> KeyPair {
+        KeyPair::try_generate().expect("Unable to generate a key pair")
+    }
+
+    /// Generates a new key pair, returning an error if the system RNG is not
+    /// available or returned a weak key.
+    #[cfg(any(feature = "random", feature = "entropy-source"))]
+    pub fn try_generate() -> Result<KeyPair, Error> {
+        KeyPair::generate_with_rng(crate::entropy::fill_random)
+    }
+
+    /// Generates a new key pair using a custom random number generator.
+    ///
+    /// `rng` is called to fill a buffer with random bytes, and can be used on
+    /// platforms where `getrandom` isn't available.
+    pub fn generate_with_rng(
+        mut rng: impl FnMut(&mut [u8]) -> Result<(), Error>,
+    ) -> Result<KeyPair, Error> {
         let mut sk = [0u8; SecretKey::BYTES];
-        getrandom::getrandom(&mut sk).expect("getrandom");
+        rng(&mut sk)?;
         if !Fe::from_bytes(&sk).is_nonzero() {
-            panic!("All-zero secret key");
+            return Err(Error::InvalidSecretKey);
         }
         let sk = SecretKey(sk);
-        let pk = sk
-            .recover_public_key()
-            .expect("generated public key is weak");
-        KeyPair { pk, sk }
+        let pk = sk.recover_public_key()?;
+        Ok(KeyPair { pk, sk })
     }
 }
 
@@ -197,6 +289,213 @@ impl Deref for KeyPair {
     }
 }
 
+#[cfg(not(feature = "disable-signatures"))]
+mod from_ed25519 {
+    use super::super::edwards25519::GeP3;
+    use super::super::sha512;
+    use super::super::{KeyPair as EdKeyPair, PublicKey as EdPublicKey, SecretKey as EdSecretKey};
+    use super::*;
+
+    impl PublicKey {
+        /// Converts an Ed25519 public key to an X25519 public key
+        /// (`u = (1 + y) / (1 - y)`).
+        ///
+        /// Public keys that are not on the curve, or that are in a
+        /// small-order group, are rejected.
+        pub fn from_ed25519(edwards25519_pk: &EdPublicKey) -> Result<PublicKey, Error> {
+            if GeP3::from_bytes_negate_vartime(edwards25519_pk).is_none() {
+                return Err(Error::PublicKeyNotOnCurve);
+            }
+            let y = Fe::from_bytes(edwards25519_pk);
+            let one_minus_y = FE_ONE - y;
+            if !one_minus_y.is_nonzero() {
+                return Err(Error::WeakPublicKey);
+            }
+            let u = (FE_ONE + y) * one_minus_y.invert();
+            let pk = PublicKey(u.to_bytes());
+            pk.clear_cofactor()?;
+            Ok(pk)
+        }
+
+        /// Converts the X25519 public key to an Ed25519 public key
+        /// (`y = (u - 1) / (u + 1)`).
+        ///
+        /// A Montgomery u-coordinate maps to two Edwards points; `sign` is
+        /// the sign bit of the x-coordinate of the point to return.
+        pub fn to_ed25519(&self, sign: bool) -> Result<EdPublicKey, Error> {
+            let u = Fe::from_bytes(&self.0);
+            let u_plus_one = u + FE_ONE;
+            if !u_plus_one.is_nonzero() {
+                return Err(Error::InvalidPublicKey);
+            }
+            let y = (u - FE_ONE) * u_plus_one.invert();
+            let mut pk = y.to_bytes();
+            pk[31] |= (sign as u8) << 7;
+            if GeP3::from_bytes_negate_vartime(&pk).is_none() {
+                return Err(Error::PublicKeyNotOnCurve);
+            }
+            Ok(EdPublicKey::new(pk))
+        }
+    }
+
+    impl PublicKey {
+        /// Converts Ed25519 public keys to X25519 public keys, and returns the
+        /// result of every conversion.
+        ///
+        /// This is equivalent to calling `PublicKey::from_ed25519()` for every
+        /// key, but a single field inversion is shared by all the conversions.
+        #[cfg(feature = "alloc")]
+        pub fn from_ed25519_batch(
+            edwards25519_pks: &[EdPublicKey],
+        ) -> Vec<Result<PublicKey, Error>> {
+            let mut out = Vec::with_capacity(edwards25519_pks.len());
+            let mut nums = Vec::with_capacity(edwards25519_pks.len());
+            let mut dens = Vec::with_capacity(edwards25519_pks.len());
+            for edwards25519_pk in edwards25519_pks {
+                let y = Fe::from_bytes(edwards25519_pk);
+                let res = match GeP3::from_bytes_negate_vartime(edwards25519_pk) {
+                    None => Err(Error::PublicKeyNotOnCurve),
+                    Some(p) if p.has_small_order() => Err(Error::WeakPublicKey),
+                    Some(_) => Ok(PublicKey([0u8; PublicKey::BYTES])),
+                };
+                nums.push(FE_ONE + y);
+                dens.push(if res.is_ok() { FE_ONE - y } else { FE_ZERO });
+                out.push(res);
+            }
+            batch_invert(&mut dens);
+            for ((res, num), den_inv) in out.iter_mut().zip(nums).zip(dens) {
+                if let Ok(pk) = res {
+                    pk.0 = (num * den_inv).to_bytes();
+                }
+            }
+            out
+        }
+    }
+
+    impl SecretKey {
+        /// Converts an Ed25519 secret key to an X25519 secret key.
+        pub fn from_ed25519(edwards25519_sk: &EdSecretKey) -> SecretKey {
+            let mut az = sha512::Hash::hash(&edwards25519_sk[0..32]);
+            let mut sk = SecretKey([0u8; SecretKey::BYTES]);
+            sk.0.copy_from_slice(&az[0..32]);
+            wipe(&mut az);
+            sk.clamped()
+        }
+    }
+
+    impl KeyPair {
+        /// Converts an Ed25519 key pair to an X25519 key pair.
+        pub fn from_ed25519(edwards25519_kp: &EdKeyPair) -> Result<KeyPair, Error> {
+            Ok(KeyPair {
+                pk: PublicKey::from_ed25519(&edwards25519_kp.pk)?,
+                sk: SecretKey::from_ed25519(&edwards25519_kp.sk),
+            })
+        }
+    }
+}
+
+#[cfg(all(feature = "blind-keys", not(feature = "disable-signatures")))]
+mod blind_keys {
+    use super::super::edwards25519::sc_mul;
+    use super::super::sha512;
+    use super::super::{Blind, KeyPair as EdKeyPair};
+    use super::*;
+
+    fn blind_factor(blind: &Blind, ctx: &[u8]) -> [u8; 32] {
+        let mut hx = sha512::Hash::new();
+        hx.update(&blind[..]);
+        hx.update([0u8]);
+        hx.update(ctx);
+        let hash_output = hx.finalize();
+        EdKeyPair::split(&hash_output, true, false).0
+    }
+
+    impl PublicKey {
+        /// Returns a blind version of the public key.
+        ///
+        /// The same blind and context applied to an Ed25519 public key
+        /// produce the Ed25519 counterpart of this key.
+        pub fn blind(&self, blind: &Blind, ctx: impl AsRef<[u8]>) -> Result<PublicKey, Error> {
+            self.unclamped_mul(&SecretKey(blind_factor(blind, ctx.as_ref())))
+        }
+    }
+
+    impl SecretKey {
+        /// Returns a blind version of the secret key.
+        ///
+        /// The blind secret key is not clamped: shared secrets must be
+        /// computed with `PublicKey::unclamped_mul()` instead of
+        /// `PublicKey::dh()`.
+        pub fn blind(&self, blind: &Blind, ctx: impl AsRef<[u8]>) -> SecretKey {
+            let mut sk = self.clamped();
+            let blind_sk = SecretKey(sc_mul(&sk.0, &blind_factor(blind, ctx.as_ref())));
+            wipe(&mut sk.0);
+            blind_sk
+        }
+    }
+
+    impl KeyPair {
+        /// Returns a blind version of the key pair.
+        pub fn blind(&self, blind: &Blind, ctx: impl AsRef<[u8]>) -> Result<KeyPair, Error> {
+            let sk = self.sk.blind(blind, ctx);
+            let pk = PublicKey::base_point().unclamped_mul(&sk)?;
+            Ok(KeyPair { pk, sk })
+        }
+    }
+}
+
+#[test]
+#[cfg(all(feature = "blind-keys", not(feature = "disable-signatures")))]
+fn test_x25519_blind() {
+    use super::Blind;
+
+    let kp = KeyPair::generate_with_rng(|buf| {
+        buf.fill(1);
+        Ok(())
+    })
+    .unwrap();
+    let peer = KeyPair::generate_with_rng(|buf| {
+        buf.fill(2);
+        Ok(())
+    })
+    .unwrap();
+    let blind = Blind::new([3u8; Blind::BYTES]);
+    let blind_kp = kp.blind(&blind, "ctx").unwrap();
+    assert_eq!(kp.pk.blind(&blind, "ctx"), Ok(blind_kp.pk));
+    assert_ne!(blind_kp.pk, kp.pk);
+    assert_ne!(kp.pk.blind(&blind, "other ctx"), Ok(blind_kp.pk));
+    assert_eq!(
+        peer.pk.unclamped_mul(&blind_kp.sk),
+        blind_kp.pk.dh(&peer.sk)
+    );
+
+    let edwards25519_kp = super::KeyPair::from_seed([42u8; 32].into());
+    let blind_edwards25519_pk = edwards25519_kp.pk.blind(&blind, "ctx").unwrap();
+    assert_eq!(
+        PublicKey::from_ed25519(&blind_edwards25519_pk.into()),
+        PublicKey::from_ed25519(&edwards25519_kp.pk).and_then(|pk| pk.blind(&blind, "ctx"))
+    );
+}
+
+#[test]
+#[cfg(not(feature = "disable-signatures"))]
+fn test_x25519_from_ed25519() {
+    let edwards25519_kp = super::KeyPair::from_seed([42u8; 32].into());
+    let kp = KeyPair::from_ed25519(&edwards25519_kp).unwrap();
+    assert_eq!(kp.sk.recover_public_key(), Ok(kp.pk));
+    let sign = edwards25519_kp.pk[31] >> 7 != 0;
+    assert_eq!(kp.pk.to_ed25519(sign), Ok(edwards25519_kp.pk));
+    assert_ne!(kp.pk.to_ed25519(!sign), Ok(edwards25519_kp.pk));
+    let identity = super::PublicKey::new([
+        1u8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0,
+    ]);
+    assert_eq!(
+        PublicKey::from_ed25519(&identity),
+        Err(Error::WeakPublicKey)
+    );
+}
+
 #[test]
 fn test_x25519() {
     let kp_1 = SecretKey::from_slice(&[
@@ -212,3 +511,94 @@ fn test_x25519() {
     let secret_b = kp_a.pk.dh(&kp_b.sk).unwrap();
     assert_eq!(secret_a, secret_b);
 }
+
+#[test]
+#[cfg(feature = "alloc")]
+fn test_x25519_dh_batch() {
+    let kp = KeyPair::generate_with_rng(|buf| {
+        buf.fill(7);
+        Ok(())
+    })
+    .unwrap();
+    let mut pks: Vec<PublicKey> = (1..=5u8)
+        .map(|i| {
+            KeyPair::generate_with_rng(|buf| {
+                buf.fill(i);
+                Ok(())
+            })
+            .unwrap()
+            .pk
+        })
+        .collect();
+    pks.insert(2, PublicKey::new([0u8; PublicKey::BYTES]));
+    let secrets = dh_batch(&kp.sk, &pks);
+    assert_eq!(secrets.len(), pks.len());
+    for (pk, secret) in pks.iter().zip(secrets) {
+        assert_eq!(secret, pk.dh(&kp.sk));
+    }
+    assert_eq!(dh_batch(&kp.sk, &pks)[2], Err(Error::WeakPublicKey));
+    assert!(dh_batch(&kp.sk, &[]).is_empty());
+}
+
+#[test]
+#[cfg(feature = "hazmat")]
+fn test_x25519_scalarmult() {
+    // RFC 7748, section 5.2
+    let mut scalar = [
+        0xa5, 0x46, 0xe3, 0x6b, 0xf0, 0x52, 0x7c, 0x9d, 0x3b, 0x16, 0x15, 0x4b, 0x82, 0x46, 0x5e,
+        0xdd, 0x62, 0x14, 0x4c, 0x0a, 0xc1, 0xfc, 0x5a, 0x18, 0x50, 0x6a, 0x22, 0x44, 0xba, 0x44,
+        0x9a, 0xc4,
+    ];
+    let point = [
+        0xe6, 0xdb, 0x68, 0x67, 0x58, 0x30, 0x30, 0xdb, 0x35, 0x94, 0xc1, 0xa4, 0x24, 0xb1, 0x5f,
+        0x7c, 0x72, 0x66, 0x24, 0xec, 0x26, 0xb3, 0x35, 0x3b, 0x10, 0xa9, 0x03, 0xa6, 0xd0, 0xab,
+        0x1c, 0x4c,
+    ];
+    let expected = [
+        0xc3, 0xda, 0x55, 0x37, 0x9d, 0xe9, 0xc6, 0x90, 0x8e, 0x94, 0xea, 0x4d, 0xf2, 0x8d, 0x08,
+        0x4f, 0x32, 0xec, 0xcf, 0x03, 0x49, 0x1c, 0x71, 0xf7, 0x54, 0xb4, 0x07, 0x55, 0x77, 0xa2,
+        0x85, 0x52,
+    ];
+    assert_eq!(
+        PublicKey::new(point).dh(&SecretKey::new(scalar)),
+        Ok(PublicKey::new(expected))
+    );
+    assert_ne!(scalarmult(&scalar, &point), expected);
+    clamp(&mut scalar);
+    assert_eq!(scalarmult(&scalar, &point), expected);
+
+    let mut one = [0u8; 32];
+    one[0] = 1;
+    assert_eq!(scalarmult(&one, &point), point);
+    assert_eq!(scalarmult(&scalar, &[0u8; 32]), [0u8; 32]);
+}
+
+#[test]
+#[cfg(all(feature = "alloc", not(feature = "disable-signatures")))]
+fn test_x25519_from_ed25519_batch() {
+    let mut edwards25519_pks: Vec<_> = (1..=4u8)
+        .map(|i| super::KeyPair::from_seed([i; 32].into()).pk)
+        .collect();
+    let mut identity = [0u8; 32];
+    identity[0] = 1;
+    edwards25519_pks.insert(1, super::PublicKey::new(identity));
+    let mut not_on_curve = [0u8; 32];
+    not_on_curve[0] = 2;
+    edwards25519_pks.insert(3, super::PublicKey::new(not_on_curve));
+    // A point of order 8
+    edwards25519_pks.push(super::PublicKey::new([
+        0xc7, 0x17, 0x6a, 0x70, 0x3d, 0x4d, 0xd8, 0x4f, 0xba, 0x3c, 0x0b, 0x76, 0x0d, 0x10, 0x67,
+        0x0f, 0x2a, 0x20, 0x53, 0xfa, 0x2c, 0x39, 0xcc, 0xc6, 0x4e, 0xc7, 0xfd, 0x77, 0x92, 0xac,
+        0x03, 0x7a,
+    ]));
+    let out = PublicKey::from_ed25519_batch(&edwards25519_pks);
+    assert_eq!(out.len(), edwards25519_pks.len());
+    for (edwards25519_pk, res) in edwards25519_pks.iter().zip(&out) {
+        assert_eq!(*res, PublicKey::from_ed25519(edwards25519_pk));
+    }
+    assert_eq!(out[1], Err(Error::WeakPublicKey));
+    assert_eq!(out[3], Err(Error::PublicKeyNotOnCurve));
+    assert_eq!(out[6], Err(Error::WeakPublicKey));
+    assert!(out[0].is_ok());
+    assert!(PublicKey::from_ed25519_batch(&[]).is_empty());
+}
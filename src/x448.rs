@@ -0,0 +1,242 @@
+//! X448 key exchange (RFC 7748).
+//!
+//! The API mirrors the `x25519` module.
+
+use core::convert::TryInto;
+use core::fmt;
+use core::ops::Deref;
+
+use super::common::{fmt_redacted, wipe};
+use super::error::Error;
+use super::field448::*;
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct PublicKey([u8; PublicKey::BYTES]);
+
+impl PublicKey {
+    /// Number of raw bytes in a public key.
+    pub const BYTES: usize = 56;
+
+    /// Creates a public key from raw bytes.
+    pub fn new(pk: [u8; PublicKey::BYTES]) -> Self {
+        PublicKey(pk)
+    }
+
+    /// Creates a public key from a slice.
+    pub fn from_slice(pk: &[u8]) -> Result<Self, Error> {
+        let mut pk_ = [0u8; PublicKey::BYTES];
+        if pk.len() != pk_.len() {
+            return Err(Error::InvalidPublicKey);
+        }
+        Fe::reject_noncanonical(pk.try_into().unwrap())?;
+        pk_.copy_from_slice(pk);
+        Ok(PublicKey::new(pk_))
+    }
+
+    /// Multiply the point represented by the public key by the scalar after
+    /// clamping it
+    pub fn dh(&self, sk: &SecretKey) -> Result<Self, Error> {
+        let mut sk = sk.clamped();
+        let res = self.ladder(&sk.0);
+        wipe(&mut sk.0);
+        res
+    }
+
+    fn ladder(&self, s: &[u8; 56]) -> Result<Self, Error> {
+        let x1 = Fe::from_bytes(&self.0);
+        let mut x2 = FE_ONE;
+        let mut z2 = FE_ZERO;
+        let mut x3 = x1;
+        let mut z3 = FE_ONE;
+        let mut swap: u8 = 0;
+        for pos in (0..448).rev() {
+            let bit = (s[pos >> 3] >> (pos & 7)) & 1;
+            swap ^= bit;
+            Fe::cswap(&mut x2, &mut x3, swap);
+            Fe::cswap(&mut z2, &mut z3, swap);
+            swap = bit;
+            let a = x2 + z2;
+            let b = x2 - z2;
+            let aa = a.square();
+            let bb = b.square();
+            x2 = aa * bb;
+            let e = aa - bb;
+            let da = (x3 - z3) * a;
+            let cb = (x3 + z3) * b;
+            x3 = (da + cb).square();
+            z3 = x1 * ((da - cb).square());
+            z2 = e * (aa + (e.mul32(39081)));
+        }
+        Fe::cswap(&mut x2, &mut x3, swap);
+        Fe::cswap(&mut z2, &mut z3, swap);
+        let x2 = x2 * z2.invert();
+        if !x2.is_nonzero() {
+            return Err(Error::WeakPublicKey);
+        }
+        Ok(PublicKey(x2.to_bytes()))
+    }
+
+    /// The Curve448 base point
+    #[inline]
+    pub fn base_point() -> PublicKey {
+        let mut pk = [0u8; PublicKey::BYTES];
+        pk[0] = 5;
+        PublicKey(pk)
+    }
+}
+
+impl Deref for PublicKey {
+    type Target = [u8; PublicKey::BYTES];
+
+    /// Returns a public key as bytes.
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+/// A secret key.
+#[derive(Copy, Clone, Eq, PartialEq, Hash)]
+pub struct SecretKey([u8; SecretKey::BYTES]);
+
+impl fmt::Debug for SecretKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_redacted(f, "SecretKey", &self.0)
+    }
+}
+
+impl SecretKey {
+    /// Number of bytes in a secret key.
+    pub const BYTES: usize = 56;
+
+    /// Creates a secret key from raw bytes.
+    pub fn new(sk: [u8; SecretKey::BYTES]) -> Self {
+        SecretKey(sk)
+    }
+
+    /// Creates a secret key from a slice.
+    pub fn from_slice(sk: &[u8]) -> Result<Self, Error> {
+        let mut sk_ = [0u8; SecretKey::BYTES];
+        if sk.len() != sk_.len() {
+            return Err(Error::InvalidSecretKey);
+        }
+        sk_.copy_from_slice(sk);
+        Ok(SecretKey::new(sk_))
+    }
+
+    /// Perform the X448 clamping magic
+    pub fn clamped(&self) -> SecretKey {
+        let mut clamped = *self;
+        clamped.0[0] &= 252;
+        clamped.0[55] |= 128;
+        clamped
+    }
+
+    /// Recover the public key
+    pub fn recover_public_key(&self) -> Result<PublicKey, Error> {
+        PublicKey::base_point().dh(self)
+    }
+}
+
+impl Deref for SecretKey {
+    type Target = [u8; SecretKey::BYTES];
+
+    /// Returns a secret key as bytes.
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+/// A key pair.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct KeyPair {
+    /// Public key part of the key pair.
+    pub pk: PublicKey,
+    /// Secret key part of the key pair.
+    pub sk: SecretKey,
+}
+
+impl KeyPair {
+    /// Number of bytes in a key pair.
+    pub const BYTES: usize = SecretKey::BYTES;
+
+    /// Generates a new key pair.
+    #[cfg(any(feature = "random", feature = "entropy-source"))]
+    pub fn generate() -> KeyPair {
+        KeyPair::try_generate().expect("Unable to generate a key pair")
+    }
+
+    /// Generates a new key pair, returning an error if the system RNG is not
+    /// available.
+    #[cfg(any(feature = "random", feature = "entropy-source"))]
+    pub fn try_generate() -> Result<KeyPair, Error> {
+        KeyPair::generate_with_rng(crate::entropy::fill_random)
+    }
+
+    /// Generates a new key pair using a custom random number generator.
+    pub fn generate_with_rng(
+        mut rng: impl FnMut(&mut [u8]) -> Result<(), Error>,
+    ) -> Result<KeyPair, Error> {
+        let mut sk = [0u8; SecretKey::BYTES];
+        rng(&mut sk)?;
+        let sk = SecretKey(sk);
+        let pk = sk.recover_public_key()?;
+        Ok(KeyPair { pk, sk })
+    }
+}
+
+impl Deref for KeyPair {
+    type Target = [u8; KeyPair::BYTES];
+
+    /// Returns a key pair as bytes.
+    fn deref(&self) -> &Self::Target {
+        &self.sk
+    }
+}
+
+#[test]
+fn test_x448() {
+    use ct_codecs::{Decoder, Hex};
+
+    // RFC 7748, section 5.2
+    let sk = SecretKey::from_slice(
+        &Hex::decode_to_vec(
+            "3d262fddf9ec8e88495266fea19a34d28882acef045104d0d1aae121700a779c\
+             984c24f8cdd78fbff44943eba368f54b29259a4f1c600ad3",
+            None,
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    let pk = PublicKey::from_slice(
+        &Hex::decode_to_vec(
+            "06fce640fa3487bfda5f6cf2d5263f8aad88334cbd07437f020f08f9814dc031\
+             ddbdc38c19c6da2583fa5429db94ada18aa7a7fb4ef8a086",
+            None,
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    let expected = Hex::decode_to_vec(
+        "ce3e4ff95a60dc6697da1db1d85e6afbdf79b50a2412d7546d5f239fe14fbaad\
+         eb445fc66a01b0779d98223961111e21766282f73dd96b6f",
+        None,
+    )
+    .unwrap();
+    assert_eq!(&pk.dh(&sk).unwrap()[..], &expected[..]);
+
+    let kp_a = KeyPair::generate_with_rng(|buf| {
+        buf.fill(1);
+        Ok(())
+    })
+    .unwrap();
+    let kp_b = KeyPair::generate_with_rng(|buf| {
+        buf.fill(2);
+        Ok(())
+    })
+    .unwrap();
+    assert_eq!(kp_a.pk.dh(&kp_b.sk), kp_b.pk.dh(&kp_a.sk));
+    assert_eq!(
+        PublicKey::new([0u8; PublicKey::BYTES]).dh(&kp_a.sk),
+        Err(Error::WeakPublicKey)
+    );
+}
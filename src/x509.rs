@@ -0,0 +1,380 @@
+use std::net::IpAddr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use super::der::{self, Reader, TAG_INTEGER, TAG_OCTET_STRING, TAG_OID, TAG_SEQUENCE};
+use super::sha256;
+use super::{Error, PublicKey, SecretKey};
+
+const OID_COMMON_NAME: &[u8] = &[85, 4, 3];
+const OID_ORGANIZATION: &[u8] = &[85, 4, 10];
+const OID_SUBJECT_KEY_IDENTIFIER: &[u8] = &[85, 29, 14];
+const OID_KEY_USAGE: &[u8] = &[85, 29, 15];
+const OID_SUBJECT_ALT_NAME: &[u8] = &[85, 29, 17];
+const OID_BASIC_CONSTRAINTS: &[u8] = &[85, 29, 19];
+const OID_EXTENSION_REQUEST: &[u8] = &[42, 134, 72, 134, 247, 13, 1, 9, 14];
+
+/// Parameters of an X.509 certificate.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CertificateParams<'t> {
+    /// Common name of the subject, or an empty string to omit it.
+    pub common_name: &'t str,
+    /// Organization of the subject, or an empty string to omit it.
+    pub organization: &'t str,
+    /// DNS names to include in the subject alternative name extension.
+    pub dns_names: &'t [&'t str],
+    /// IP addresses to include in the subject alternative name extension.
+    pub ip_addresses: &'t [IpAddr],
+    /// Start of the validity period.
+    pub not_before: SystemTime,
+    /// End of the validity period.
+    pub not_after: SystemTime,
+    /// Serial number. If `None`, it is derived from the other parameters and
+    /// from the public key.
+    pub serial_number: Option<[u8; 16]>,
+    /// Whether the certificate can be used to sign other certificates.
+    pub is_ca: bool,
+}
+
+impl Default for CertificateParams<'static> {
+    /// Returns parameters for a certificate valid from now on, with no
+    /// well-defined expiration date (RFC 5280, section 4.1.2.5).
+    fn default() -> Self {
+        CertificateParams {
+            common_name: "",
+            organization: "",
+            dns_names: &[],
+            ip_addresses: &[],
+            not_before: SystemTime::now(),
+            not_after: UNIX_EPOCH + Duration::from_secs(253402300799),
+            serial_number: None,
+            is_ca: false,
+        }
+    }
+}
+
+impl CertificateParams<'_> {
+    pub(crate) fn subject(&self) -> Vec<u8> {
+        der::name(&[
+            (OID_ORGANIZATION, self.organization),
+            (OID_COMMON_NAME, self.common_name),
+        ])
+    }
+
+    /// Returns the subject alternative name extension, if there are any
+    /// alternative names.
+    pub(crate) fn subject_alt_name(&self) -> Result<Option<Vec<u8>>, Error> {
+        if self.dns_names.is_empty() && self.ip_addresses.is_empty() {
+            return Ok(None);
+        }
+        let mut names = Vec::new();
+        for dns_name in self.dns_names {
+            if dns_name.is_empty() || !dns_name.is_ascii() {
+                return Err(Error::InvalidCertificate);
+            }
+            names.extend_from_slice(&der::tlv(0x82, dns_name.as_bytes()));
+        }
+        for ip_address in self.ip_addresses {
+            match ip_address {
+                IpAddr::V4(ip) => names.extend_from_slice(&der::tlv(0x87, &ip.octets())),
+                IpAddr::V6(ip) => names.extend_from_slice(&der::tlv(0x87, &ip.octets())),
+            }
+        }
+        let critical = self.common_name.is_empty() && self.organization.is_empty();
+        Ok(Some(extension(
+            OID_SUBJECT_ALT_NAME,
+            critical,
+            &der::sequence(&[&names]),
+        )))
+    }
+}
+
+/// Encodes an `Extension`.
+pub(crate) fn extension(oid: &[u8], critical: bool, value: &[u8]) -> Vec<u8> {
+    let oid = der::tlv(TAG_OID, oid);
+    let value = der::tlv(TAG_OCTET_STRING, value);
+    if critical {
+        der::sequence(&[&oid, &der::boolean(true), &value])
+    } else {
+        der::sequence(&[&oid, &value])
+    }
+}
+
+fn unix_secs(time: SystemTime) -> Result<u64, Error> {
+    time.duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .map_err(|_| Error::InvalidCertificate)
+}
+
+impl SecretKey {
+    /// Creates a self-signed X.509 v3 certificate for the public key, and
+    /// returns it in DER format.
+    ///
+    /// The certificate includes the key usage, basic constraints and subject
+    /// key identifier extensions, as well as the subject alternative name
+    /// extension if DNS names or IP addresses are given.
+    pub fn self_signed_certificate(
+        &self,
+        params: &CertificateParams<'_>,
+    ) -> Result<Vec<u8>, Error> {
+        let pk = self.public_key();
+        let not_before = unix_secs(params.not_before)?;
+        let not_after = unix_secs(params.not_after)?;
+        if not_after < not_before {
+            return Err(Error::InvalidCertificate);
+        }
+        let subject = params.subject();
+        let serial_number = match params.serial_number {
+            Some(serial_number) => serial_number,
+            None => {
+                let mut h = sha256::Hash::new();
+                h.update(&pk[..]);
+                h.update(not_before.to_be_bytes());
+                h.update(not_after.to_be_bytes());
+                h.update(&subject);
+                let mut serial_number = [0u8; 16];
+                serial_number.copy_from_slice(&h.finalize()[..16]);
+                serial_number[0] &= 0x7f;
+                serial_number
+            }
+        };
+
+        let mut extensions = Vec::new();
+        let key_usage = if params.is_ca {
+            // digitalSignature, keyCertSign, cRLSign
+            der::tlv(der::TAG_BIT_STRING, &[1, 0x86])
+        } else {
+            // digitalSignature
+            der::tlv(der::TAG_BIT_STRING, &[7, 0x80])
+        };
+        extensions.extend_from_slice(&extension(OID_KEY_USAGE, true, &key_usage));
+        let basic_constraints = if params.is_ca {
+            der::sequence(&[&der::boolean(true)])
+        } else {
+            der::sequence(&[])
+        };
+        extensions.extend_from_slice(&extension(OID_BASIC_CONSTRAINTS, true, &basic_constraints));
+        extensions.extend_from_slice(&extension(
+            OID_SUBJECT_KEY_IDENTIFIER,
+            false,
+            &der::tlv(TAG_OCTET_STRING, &key_identifier(&pk)),
+        ));
+        if let Some(subject_alt_name) = params.subject_alt_name()? {
+            extensions.extend_from_slice(&subject_alt_name);
+        }
+
+        let algorithm = der::ed25519_algorithm();
+        let tbs = der::sequence(&[
+            &der::explicit(0, &der::unsigned_integer(&[2])),
+            &der::unsigned_integer(&serial_number),
+            &algorithm,
+            &subject,
+            &der::sequence(&[&der::time(not_before), &der::time(not_after)]),
+            &subject,
+            &pk.to_der(),
+            &der::explicit(3, &der::sequence(&[&extensions])),
+        ]);
+        let signature = self.try_sign(&tbs, None)?;
+        Ok(der::sequence(&[
+            &tbs,
+            &algorithm,
+            &der::bit_string(&signature[..]),
+        ]))
+    }
+}
+
+impl SecretKey {
+    /// Creates a PKCS#10 certificate signing request for the public key, and
+    /// returns it in DER format.
+    ///
+    /// Only the subject and the subject alternative names of `params` are
+    /// used. Alternative names are requested through the extension request
+    /// attribute.
+    pub fn certificate_signing_request(
+        &self,
+        params: &CertificateParams<'_>,
+    ) -> Result<Vec<u8>, Error> {
+        let attributes = match params.subject_alt_name()? {
+            Some(subject_alt_name) => der::sequence(&[
+                &der::tlv(TAG_OID, OID_EXTENSION_REQUEST),
+                &der::constructed(der::TAG_SET, &[&der::sequence(&[&subject_alt_name])]),
+            ]),
+            None => Vec::new(),
+        };
+        let info = der::sequence(&[
+            &der::unsigned_integer(&[0]),
+            &params.subject(),
+            &self.public_key().to_der(),
+            &der::tlv(0xa0, &attributes),
+        ]);
+        let signature = self.try_sign(&info, None)?;
+        Ok(der::sequence(&[
+            &info,
+            &der::ed25519_algorithm(),
+            &der::bit_string(&signature[..]),
+        ]))
+    }
+}
+
+impl PublicKey {
+    /// Extracts an Ed25519 public key from a DER-encoded X.509 certificate.
+    ///
+    /// The certificate signature is not verified. Returns
+    /// `Error::InvalidCertificate` if the certificate is malformed, and the
+    /// same errors as `PublicKey::from_der()` if it doesn't contain an
+    /// Ed25519 key.
+    pub fn from_x509_der(der: &[u8]) -> Result<Self, Error> {
+        let spki = certificate_spki(der).ok_or(Error::InvalidCertificate)?;
+        PublicKey::from_der(spki)
+    }
+}
+
+/// Returns the encoded `SubjectPublicKeyInfo` of a certificate.
+fn certificate_spki(der: &[u8]) -> Option<&[u8]> {
+    let mut reader = Reader::new(der);
+    let certificate = reader.read(TAG_SEQUENCE)?;
+    if !reader.is_empty() {
+        return None;
+    }
+    let mut reader = Reader::new(certificate);
+    let mut tbs = Reader::new(reader.read(TAG_SEQUENCE)?);
+    reader.read(TAG_SEQUENCE)?;
+    reader.read(der::TAG_BIT_STRING)?;
+    if !reader.is_empty() {
+        return None;
+    }
+    if tbs.peek_tag() == Some(0xa0) {
+        tbs.read(0xa0)?;
+    }
+    tbs.read(TAG_INTEGER)?;
+    tbs.read(TAG_SEQUENCE)?;
+    tbs.read(TAG_SEQUENCE)?;
+    tbs.read(TAG_SEQUENCE)?;
+    tbs.read(TAG_SEQUENCE)?;
+    match tbs.read_tlv()? {
+        (TAG_SEQUENCE, _, spki) => Some(spki),
+        _ => None,
+    }
+}
+
+/// Computes a key identifier, using the first 160 bits of the SHA-256 hash
+/// of the public key (RFC 7093, section 2).
+pub(crate) fn key_identifier(pk: &PublicKey) -> [u8; 20] {
+    let mut key_identifier = [0u8; 20];
+    key_identifier.copy_from_slice(&sha256::Hash::hash(&pk[..])[..20]);
+    key_identifier
+}
+
+#[test]
+fn test_x509() {
+    use super::KeyPair;
+    use std::net::Ipv4Addr;
+
+    let kp = KeyPair::from_seed([42u8; 32].into());
+    let params = CertificateParams {
+        common_name: "device",
+        dns_names: &["example.com", "*.example.com"],
+        ip_addresses: &[IpAddr::V4(Ipv4Addr::LOCALHOST)],
+        not_before: UNIX_EPOCH + Duration::from_secs(1700000000),
+        ..CertificateParams::default()
+    };
+    let cert = kp.sk.self_signed_certificate(&params).unwrap();
+    assert_eq!(cert, kp.sk.self_signed_certificate(&params).unwrap());
+    assert_eq!(cert[0], der::TAG_SEQUENCE);
+
+    let tbs_len = (cert[6] as usize) << 8 | cert[7] as usize;
+    let tbs = &cert[4..8 + tbs_len];
+    let mut signature = [0u8; 64];
+    signature.copy_from_slice(&cert[cert.len() - 64..]);
+    assert!(kp.pk.verify(tbs, &super::Signature::new(signature)).is_ok());
+
+    let params = CertificateParams {
+        not_after: UNIX_EPOCH,
+        ..params
+    };
+    assert_eq!(
+        kp.sk.self_signed_certificate(&params),
+        Err(Error::InvalidCertificate)
+    );
+
+    let cert = kp
+        .sk
+        .self_signed_certificate(&CertificateParams::default())
+        .unwrap();
+    assert_eq!(PublicKey::from_x509_der(&cert), Ok(kp.pk));
+    assert_eq!(
+        PublicKey::from_x509_der(&cert[..cert.len() - 1]),
+        Err(Error::InvalidCertificate)
+    );
+}
+
+#[test]
+fn test_x509_csr() {
+    use super::KeyPair;
+
+    let kp = KeyPair::from_seed([42u8; 32].into());
+    let params = CertificateParams {
+        common_name: "device",
+        dns_names: &["device.example.com"],
+        ..CertificateParams::default()
+    };
+    let csr = kp.sk.certificate_signing_request(&params).unwrap();
+
+    let mut reader = Reader::new(&csr);
+    let mut reader = Reader::new(reader.read(TAG_SEQUENCE).unwrap());
+    let (_, _, info) = reader.read_tlv().unwrap();
+    reader.read(TAG_SEQUENCE).unwrap();
+    let signature = reader.read(der::TAG_BIT_STRING).unwrap();
+    let signature = super::Signature::from_slice(&signature[1..]).unwrap();
+    assert!(kp.pk.verify(info, &signature).is_ok());
+
+    assert_eq!(
+        kp.sk.certificate_signing_request(&CertificateParams {
+            dns_names: &["\u{e9}.example.com"],
+            ..params
+        }),
+        Err(Error::InvalidCertificate)
+    );
+}
+
+#[test]
+fn test_x509_parse() {
+    use ct_codecs::{Base64, Decoder};
+
+    // `openssl req -x509 -key ed25519.key -subj /CN=test`
+    let ed25519_cert = Base64::decode_to_vec(
+        "MIIBMjCB5aADAgECAhQfWlbmJHqQshB8awey9E5gBFrWAzAFBgMrZXAwDzENMAsGA1UEAwwEdGVzdDAeFw0yNjEw\
+         MTUwODAwMDVaFw0zNjEwMTIwODAwMDVaMA8xDTALBgNVBAMMBHRlc3QwKjAFBgMrZXADIQD5updInGpzcpXAwvcK\
+         5sUwGr/DMjvVQn70Td/874UdqqNTMFEwHQYDVR0OBBYEFMtXkt2jp8IcAP8Iz36zjj6dXefJMB8GA1UdIwQYMBaA\
+         FMtXkt2jp8IcAP8Iz36zjj6dXefJMA8GA1UdEwEB/wQFMAMBAf8wBQYDK2VwA0EA2xElMfUXw5vftAy52Nzx3Apv\
+         aFFl/lPhdS+E51O5dwEtfv1FdoBnneTnbTbb/vxf9z/ZPNmnPmRCTMQb93iOBw==",
+        None,
+    )
+    .unwrap();
+    assert_eq!(
+        PublicKey::from_x509_der(&ed25519_cert),
+        Ok(PublicKey::new([
+            0xf9, 0xba, 0x97, 0x48, 0x9c, 0x6a, 0x73, 0x72, 0x95, 0xc0, 0xc2, 0xf7, 0x0a, 0xe6,
+            0xc5, 0x30, 0x1a, 0xbf, 0xc3, 0x32, 0x3b, 0xd5, 0x42, 0x7e, 0xf4, 0x4d, 0xdf, 0xfc,
+            0xef, 0x85, 0x1d, 0xaa,
+        ]))
+    );
+
+    // `openssl req -x509 -key p256.key -subj /CN=p`
+    let p256_cert = Base64::decode_to_vec(
+        "MIIBbDCCAROgAwIBAgIUUYmx2epF+VxLYtug0SxqLGlA6d0wCgYIKoZIzj0EAwIwDDEKMAgGA1UEAwwBcDAeFw0y\
+         NjEwMTUwODAwMDZaFw0yNjEwMTYwODAwMDZaMAwxCjAIBgNVBAMMAXAwWTATBgcqhkjOPQIBBggqhkjOPQMBBwNC\
+         AATTKo49yzMEDB5bBIAlh/OGpwHKLK0Q8udjNyXvt9zFd71Zqpx+h0mqRYAmuQoZgHQmB3Efu8svlY7uuk/VrnTH\
+         o1MwUTAdBgNVHQ4EFgQUUzw3B5uU2pLUXicZLcXeQUK0O6EwHwYDVR0jBBgwFoAUUzw3B5uU2pLUXicZLcXeQUK0\
+         O6EwDwYDVR0TAQH/BAUwAwEB/zAKBggqhkjOPQQDAgNHADBEAiBRyDztGRJv0EvBNV6K9wOb6EAO/8LuPOEwh/QZ\
+         sTGVagIgXQAch9lmQZv++eciR406mbDgruIVpyMdWqfw7oOpZfk=",
+        None,
+    )
+    .unwrap();
+    assert_eq!(
+        PublicKey::from_x509_der(&p256_cert),
+        Err(Error::DerInvalidLength {
+            expected: 44,
+            actual: 91
+        })
+    );
+}